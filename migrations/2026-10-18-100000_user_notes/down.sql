@@ -0,0 +1,11 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP INDEX edits_for_note;
+DROP TABLE user_note_edits;
+DROP INDEX notes_for_user_by_guild;
+DROP TABLE user_notes;
+DROP TYPE moderation_action_type;
@@ -0,0 +1,37 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE moderation_action_type AS ENUM (
+	'automod',
+	'ban',
+	'kick',
+	'timeout'
+);
+
+CREATE TABLE user_notes (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	target_user discord_id NOT NULL,
+	author discord_id NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	body TEXT NOT NULL,
+	action_type moderation_action_type,
+	action_id TEXT,
+	ticket TEXT REFERENCES tickets,
+	CONSTRAINT action_link_complete CHECK((action_type IS NULL) = (action_id IS NULL))
+);
+
+CREATE INDEX notes_for_user_by_guild ON user_notes (guild, target_user);
+
+CREATE TABLE user_note_edits (
+	id TEXT PRIMARY KEY,
+	note TEXT NOT NULL REFERENCES user_notes ON DELETE CASCADE,
+	editor discord_id NOT NULL,
+	edit_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	previous_body TEXT NOT NULL
+);
+
+CREATE INDEX edits_for_note ON user_note_edits (note);
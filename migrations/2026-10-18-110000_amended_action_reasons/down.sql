@@ -0,0 +1,23 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE timeout_actions
+	DROP CONSTRAINT amendment_complete,
+	DROP COLUMN reason_amended_at,
+	DROP COLUMN reason_amended_by,
+	DROP COLUMN amended_reason;
+
+ALTER TABLE kick_actions
+	DROP CONSTRAINT amendment_complete,
+	DROP COLUMN reason_amended_at,
+	DROP COLUMN reason_amended_by,
+	DROP COLUMN amended_reason;
+
+ALTER TABLE ban_actions
+	DROP CONSTRAINT amendment_complete,
+	DROP COLUMN reason_amended_at,
+	DROP COLUMN reason_amended_by,
+	DROP COLUMN amended_reason;
@@ -0,0 +1,23 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE ban_actions
+	ADD COLUMN amended_reason TEXT,
+	ADD COLUMN reason_amended_by discord_id,
+	ADD COLUMN reason_amended_at TIMESTAMP WITH TIME ZONE,
+	ADD CONSTRAINT amendment_complete CHECK ((amended_reason IS NULL) = (reason_amended_by IS NULL) AND (amended_reason IS NULL) = (reason_amended_at IS NULL));
+
+ALTER TABLE kick_actions
+	ADD COLUMN amended_reason TEXT,
+	ADD COLUMN reason_amended_by discord_id,
+	ADD COLUMN reason_amended_at TIMESTAMP WITH TIME ZONE,
+	ADD CONSTRAINT amendment_complete CHECK ((amended_reason IS NULL) = (reason_amended_by IS NULL) AND (amended_reason IS NULL) = (reason_amended_at IS NULL));
+
+ALTER TABLE timeout_actions
+	ADD COLUMN amended_reason TEXT,
+	ADD COLUMN reason_amended_by discord_id,
+	ADD COLUMN reason_amended_at TIMESTAMP WITH TIME ZONE,
+	ADD CONSTRAINT amendment_complete CHECK ((amended_reason IS NULL) = (reason_amended_by IS NULL) AND (amended_reason IS NULL) = (reason_amended_at IS NULL));
@@ -0,0 +1,18 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE reason_policies (
+	guild discord_id PRIMARY KEY REFERENCES guilds,
+	minimum_length INTEGER NOT NULL CONSTRAINT minimum_length_positive CHECK (minimum_length > 0),
+	required_pattern TEXT,
+	exempt_bots BOOLEAN NOT NULL
+);
+
+CREATE TABLE reason_policy_exempt_roles (
+	guild discord_id NOT NULL REFERENCES guilds,
+	role discord_id NOT NULL,
+	PRIMARY KEY (guild, role)
+);
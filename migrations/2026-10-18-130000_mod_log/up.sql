@@ -0,0 +1,23 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE guilds ADD COLUMN mod_log_channel discord_id;
+
+ALTER TABLE automod_actions
+	ADD COLUMN mod_log_channel discord_id,
+	ADD COLUMN mod_log_message discord_id;
+
+ALTER TABLE ban_actions
+	ADD COLUMN mod_log_channel discord_id,
+	ADD COLUMN mod_log_message discord_id;
+
+ALTER TABLE kick_actions
+	ADD COLUMN mod_log_channel discord_id,
+	ADD COLUMN mod_log_message discord_id;
+
+ALTER TABLE timeout_actions
+	ADD COLUMN mod_log_channel discord_id,
+	ADD COLUMN mod_log_message discord_id;
@@ -0,0 +1,12 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE guilds ADD COLUMN last_audit_log_entry discord_id;
+
+ALTER TABLE automod_actions ADD COLUMN audit_log_entry discord_id UNIQUE;
+ALTER TABLE ban_actions ADD COLUMN audit_log_entry discord_id UNIQUE;
+ALTER TABLE kick_actions ADD COLUMN audit_log_entry discord_id UNIQUE;
+ALTER TABLE timeout_actions ADD COLUMN audit_log_entry discord_id UNIQUE;
@@ -0,0 +1,26 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP TABLE pending_automod_executions;
+
+DELETE FROM automod_actions WHERE action_type = 'flag_to_channel';
+UPDATE automod_actions SET message_content = '' WHERE message_content IS NULL;
+
+ALTER TABLE automod_actions
+	DROP COLUMN alert_message,
+	DROP COLUMN matched_content,
+	DROP COLUMN matched_keyword,
+	DROP COLUMN channel,
+	ALTER COLUMN message_content SET NOT NULL;
+
+ALTER TYPE automod_action_type RENAME TO automod_action_type_old;
+CREATE TYPE automod_action_type AS ENUM (
+	'block',
+	'disable_communication'
+);
+ALTER TABLE automod_actions
+	ALTER COLUMN action_type TYPE automod_action_type USING action_type::TEXT::automod_action_type;
+DROP TYPE automod_action_type_old;
@@ -0,0 +1,29 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TYPE automod_action_type ADD VALUE 'flag_to_channel';
+
+ALTER TABLE automod_actions
+	ALTER COLUMN message_content DROP NOT NULL,
+	ADD COLUMN channel discord_id,
+	ADD COLUMN matched_keyword TEXT,
+	ADD COLUMN matched_content TEXT,
+	ADD COLUMN alert_message discord_id;
+
+CREATE TABLE pending_automod_executions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	target_user discord_id NOT NULL,
+	action_type automod_action_type NOT NULL,
+	received_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	channel discord_id,
+	message_content TEXT NOT NULL,
+	matched_keyword TEXT,
+	matched_content TEXT,
+	alert_message discord_id
+);
+
+CREATE INDEX pending_automod_executions_by_user ON pending_automod_executions (guild, target_user);
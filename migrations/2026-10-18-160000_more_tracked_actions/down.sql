@@ -0,0 +1,13 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP TABLE untracked_action_types;
+DROP TYPE tracked_action_type;
+DROP TABLE voice_actions;
+DROP TYPE voice_action_type;
+DROP TABLE message_delete_actions;
+DROP TABLE nickname_change_actions;
+DROP TABLE role_change_actions;
@@ -0,0 +1,79 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE role_change_actions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	performing_user discord_id NOT NULL,
+	target_user discord_id NOT NULL,
+	role discord_id NOT NULL,
+	added BOOLEAN NOT NULL,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	reason TEXT NOT NULL,
+	audit_log_entry discord_id NOT NULL,
+	UNIQUE (audit_log_entry, role)
+);
+
+CREATE INDEX role_change_target_user_by_guild ON role_change_actions (guild, target_user);
+
+CREATE TABLE nickname_change_actions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	performing_user discord_id NOT NULL,
+	target_user discord_id NOT NULL,
+	old_nickname TEXT,
+	new_nickname TEXT,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	reason TEXT NOT NULL,
+	audit_log_entry discord_id NOT NULL UNIQUE
+);
+
+CREATE INDEX nickname_change_target_user_by_guild ON nickname_change_actions (guild, target_user);
+
+CREATE TABLE message_delete_actions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	performing_user discord_id NOT NULL,
+	target_user discord_id,
+	channel discord_id NOT NULL,
+	message_count INTEGER NOT NULL,
+	bulk BOOLEAN NOT NULL,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	audit_log_entry discord_id NOT NULL UNIQUE,
+	CONSTRAINT single_delete_has_target CHECK (bulk OR target_user IS NOT NULL)
+);
+
+CREATE INDEX message_delete_target_user_by_guild ON message_delete_actions (guild, target_user);
+
+CREATE TYPE voice_action_type AS ENUM (
+	'disconnect',
+	'move'
+);
+
+CREATE TABLE voice_actions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	performing_user discord_id NOT NULL,
+	action_type voice_action_type NOT NULL,
+	channel discord_id,
+	member_count INTEGER NOT NULL,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	audit_log_entry discord_id NOT NULL UNIQUE
+);
+
+CREATE TYPE tracked_action_type AS ENUM (
+	'role_change',
+	'nickname_change',
+	'message_delete',
+	'voice_disconnect',
+	'voice_move'
+);
+
+CREATE TABLE untracked_action_types (
+	guild discord_id NOT NULL REFERENCES guilds,
+	action_type tracked_action_type NOT NULL,
+	PRIMARY KEY (guild, action_type)
+);
@@ -0,0 +1,17 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE timeout_actions
+	DROP COLUMN via_bot_command,
+	DROP COLUMN evidence;
+
+ALTER TABLE kick_actions
+	DROP COLUMN via_bot_command,
+	DROP COLUMN evidence;
+
+ALTER TABLE ban_actions
+	DROP COLUMN via_bot_command,
+	DROP COLUMN evidence;
@@ -0,0 +1,22 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP TABLE warn_actions;
+
+ALTER TABLE guilds DROP COLUMN warning_expiry_days;
+
+UPDATE user_notes SET action_type = NULL, action_id = NULL WHERE action_type = 'warn';
+
+ALTER TYPE moderation_action_type RENAME TO moderation_action_type_old;
+CREATE TYPE moderation_action_type AS ENUM (
+	'automod',
+	'ban',
+	'kick',
+	'timeout'
+);
+ALTER TABLE user_notes
+	ALTER COLUMN action_type TYPE moderation_action_type USING action_type::TEXT::moderation_action_type;
+DROP TYPE moderation_action_type_old;
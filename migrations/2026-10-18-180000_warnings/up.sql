@@ -0,0 +1,29 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TYPE moderation_action_type ADD VALUE 'warn';
+
+ALTER TABLE guilds ADD COLUMN warning_expiry_days INTEGER;
+
+CREATE TABLE warn_actions (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	warning_user discord_id NOT NULL,
+	warned_user discord_id NOT NULL,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	reason TEXT NOT NULL,
+	rule TEXT,
+	expires_at TIMESTAMP WITH TIME ZONE,
+	user_notified BOOLEAN NOT NULL,
+	evidence TEXT,
+	amended_reason TEXT,
+	reason_amended_by discord_id,
+	reason_amended_at TIMESTAMP WITH TIME ZONE,
+	mod_log_channel discord_id,
+	mod_log_message discord_id
+);
+
+CREATE INDEX warned_user_by_guild ON warn_actions (guild, warned_user);
@@ -0,0 +1,14 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE timeout_actions DROP COLUMN escalation_rule;
+ALTER TABLE kick_actions DROP COLUMN escalation_rule;
+ALTER TABLE ban_actions DROP COLUMN escalation_rule;
+
+DROP TABLE escalation_applications;
+DROP TABLE escalation_rules;
+DROP TYPE escalation_action_type;
+DROP TYPE escalation_trigger_type;
@@ -0,0 +1,52 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE escalation_trigger_type AS ENUM (
+	'automod_block',
+	'automod_flag',
+	'automod_timeout',
+	'kick',
+	'timeout'
+);
+
+CREATE TYPE escalation_action_type AS ENUM (
+	'timeout',
+	'kick',
+	'ban',
+	'staff_review'
+);
+
+CREATE TABLE escalation_rules (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	trigger_type escalation_trigger_type NOT NULL,
+	threshold INTEGER NOT NULL CHECK (threshold > 0),
+	window_days INTEGER CHECK (window_days > 0),
+	action_type escalation_action_type NOT NULL,
+	timeout_minutes INTEGER CHECK (timeout_minutes > 0),
+	created_by discord_id NOT NULL,
+	created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	active BOOLEAN NOT NULL DEFAULT true,
+	CONSTRAINT timeout_has_duration CHECK ((action_type = 'timeout') = (timeout_minutes IS NOT NULL))
+);
+
+CREATE INDEX escalation_rules_by_guild ON escalation_rules (guild);
+
+CREATE TABLE escalation_applications (
+	id TEXT PRIMARY KEY,
+	rule TEXT NOT NULL REFERENCES escalation_rules,
+	guild discord_id NOT NULL REFERENCES guilds,
+	target_user discord_id NOT NULL,
+	applied_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	triggering_audit_log_entry discord_id NOT NULL,
+	UNIQUE (rule, triggering_audit_log_entry)
+);
+
+CREATE INDEX escalation_applications_by_user ON escalation_applications (rule, target_user);
+
+ALTER TABLE ban_actions ADD COLUMN escalation_rule TEXT REFERENCES escalation_rules;
+ALTER TABLE kick_actions ADD COLUMN escalation_rule TEXT REFERENCES escalation_rules;
+ALTER TABLE timeout_actions ADD COLUMN escalation_rule TEXT REFERENCES escalation_rules;
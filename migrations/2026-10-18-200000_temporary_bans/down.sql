@@ -0,0 +1,10 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+DROP INDEX ban_actions_expiry_idx;
+
+ALTER TABLE ban_actions DROP COLUMN original_ban;
+ALTER TABLE ban_actions DROP COLUMN expires_at;
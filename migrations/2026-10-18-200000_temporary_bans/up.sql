@@ -0,0 +1,10 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE ban_actions ADD COLUMN expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE ban_actions ADD COLUMN original_ban TEXT REFERENCES ban_actions;
+
+CREATE INDEX ban_actions_expiry_idx ON ban_actions (expires_at) WHERE added AND expires_at IS NOT NULL;
@@ -0,0 +1,35 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE guilds ADD COLUMN last_case_number INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE moderation_cases (
+	guild discord_id NOT NULL REFERENCES guilds,
+	case_number INTEGER NOT NULL,
+	action_type moderation_action_type NOT NULL,
+	action_id TEXT NOT NULL,
+	PRIMARY KEY (guild, case_number),
+	UNIQUE (action_type, action_id)
+);
+
+-- Actions recorded before case numbers existed are numbered in the order they happened
+INSERT INTO moderation_cases (guild, case_number, action_type, action_id)
+SELECT guild, ROW_NUMBER() OVER (PARTITION BY guild ORDER BY action_time, id), action_type, id
+FROM (
+	SELECT guild, action_time, id, 'automod'::moderation_action_type AS action_type FROM automod_actions
+	UNION ALL
+	SELECT guild, action_time, id, 'ban'::moderation_action_type FROM ban_actions
+	UNION ALL
+	SELECT guild, action_time, id, 'kick'::moderation_action_type FROM kick_actions
+	UNION ALL
+	SELECT guild, action_time, id, 'timeout'::moderation_action_type FROM timeout_actions
+	UNION ALL
+	SELECT guild, action_time, id, 'warn'::moderation_action_type FROM warn_actions
+) AS actions;
+
+UPDATE guilds SET last_case_number = cases.last_case_number
+FROM (SELECT guild, MAX(case_number) AS last_case_number FROM moderation_cases GROUP BY guild) AS cases
+WHERE guilds.guild_id = cases.guild;
@@ -0,0 +1,31 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE server_rules (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	rule_number INTEGER NOT NULL,
+	title TEXT NOT NULL,
+	description TEXT NOT NULL DEFAULT '',
+	active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+-- Removed rules are kept so actions that referenced them still can, so numbers only need to be unique among rules in use
+CREATE UNIQUE INDEX server_rule_number_by_guild ON server_rules (guild, rule_number) WHERE active;
+
+CREATE TABLE action_rules (
+	action_type moderation_action_type NOT NULL,
+	action_id TEXT NOT NULL,
+	rule TEXT NOT NULL REFERENCES server_rules,
+	action_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	PRIMARY KEY (action_type, action_id, rule)
+);
+
+CREATE INDEX action_rules_by_rule ON action_rules (rule, action_time);
+
+-- Warnings now reference rules from the registry, so rules given as free text are kept as part of the reason
+UPDATE warn_actions SET reason = reason || E'\nRule: ' || rule WHERE rule IS NOT NULL;
+ALTER TABLE warn_actions DROP COLUMN rule;
@@ -0,0 +1,33 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TABLE partnerships (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	partner_guild discord_id NOT NULL,
+	partner_name TEXT NOT NULL,
+	partner_icon TEXT,
+	member_count BIGINT NOT NULL,
+	invite_code TEXT NOT NULL,
+	representative discord_id NOT NULL,
+	ticket TEXT NOT NULL REFERENCES tickets,
+	approved_by discord_id NOT NULL,
+	approved_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX partnerships_by_guild ON partnerships (guild, partner_guild);
+
+CREATE TABLE declined_partnerships (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	partner_guild discord_id NOT NULL,
+	invite_code TEXT NOT NULL,
+	representative discord_id NOT NULL,
+	ticket TEXT NOT NULL REFERENCES tickets,
+	declined_by discord_id NOT NULL,
+	declined_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	reason TEXT NOT NULL
+);
@@ -0,0 +1,24 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE guilds ADD COLUMN partner_invite_alert_channel discord_id;
+
+CREATE TYPE partner_invite_status AS ENUM (
+	'valid',
+	'expiring',
+	'invalid'
+);
+
+CREATE TABLE partner_invite_checks (
+	id TEXT PRIMARY KEY,
+	partnership TEXT NOT NULL REFERENCES partnerships ON DELETE CASCADE,
+	checked_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	status partner_invite_status NOT NULL,
+	expires_at TIMESTAMP WITH TIME ZONE,
+	alert_message discord_id
+);
+
+CREATE INDEX partner_invite_checks_by_partnership ON partner_invite_checks (partnership, checked_at);
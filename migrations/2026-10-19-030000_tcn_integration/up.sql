@@ -0,0 +1,25 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE partnerships ADD COLUMN tcn_push_pending BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE tcn_partners (
+	guild_id discord_id PRIMARY KEY,
+	name TEXT NOT NULL,
+	invite_code TEXT NOT NULL,
+	synced_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE tcn_bans (
+	id TEXT PRIMARY KEY,
+	user_id discord_id NOT NULL,
+	origin_guild discord_id NOT NULL,
+	reason TEXT NOT NULL,
+	banned_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	synced_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX tcn_bans_by_user ON tcn_bans (user_id);
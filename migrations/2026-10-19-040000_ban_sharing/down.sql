@@ -0,0 +1,11 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE ban_actions DROP COLUMN shared_ban;
+
+DROP TABLE shared_bans;
+DROP TABLE trusted_guilds;
+DROP TYPE ban_share_policy;
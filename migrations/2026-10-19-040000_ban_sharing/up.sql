@@ -0,0 +1,39 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE ban_share_policy AS ENUM (
+	'alert',
+	'ban'
+);
+
+CREATE TABLE trusted_guilds (
+	guild discord_id NOT NULL REFERENCES guilds,
+	trusted_guild discord_id NOT NULL REFERENCES guilds,
+	policy ban_share_policy NOT NULL,
+	added_by discord_id NOT NULL,
+	added_at TIMESTAMP WITH TIME ZONE NOT NULL,
+	PRIMARY KEY (guild, trusted_guild)
+);
+
+CREATE INDEX trusted_guilds_by_trusted_guild ON trusted_guilds (trusted_guild);
+
+CREATE TABLE shared_bans (
+	id TEXT PRIMARY KEY,
+	origin_action TEXT NOT NULL REFERENCES ban_actions,
+	origin_guild discord_id NOT NULL REFERENCES guilds,
+	target_guild discord_id NOT NULL REFERENCES guilds,
+	banned_user discord_id NOT NULL,
+	added BOOLEAN NOT NULL,
+	reason TEXT NOT NULL,
+	policy ban_share_policy NOT NULL,
+	alert_channel discord_id,
+	alert_message discord_id,
+	shared_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX shared_bans_by_target ON shared_bans (target_guild, banned_user, shared_at);
+
+ALTER TABLE ban_actions ADD COLUMN shared_ban TEXT REFERENCES shared_bans;
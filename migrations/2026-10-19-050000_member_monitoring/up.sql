@@ -0,0 +1,19 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+ALTER TABLE guilds ADD COLUMN member_alert_channel discord_id;
+ALTER TABLE guilds ADD COLUMN new_account_age_days INTEGER;
+
+CREATE TABLE member_events (
+	id TEXT PRIMARY KEY,
+	guild discord_id NOT NULL REFERENCES guilds,
+	user_id discord_id NOT NULL,
+	joined BOOLEAN NOT NULL,
+	event_time TIMESTAMP WITH TIME ZONE NOT NULL,
+	account_created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX member_events_by_user ON member_events (guild, user_id, event_time);
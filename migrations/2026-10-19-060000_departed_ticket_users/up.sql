@@ -0,0 +1,13 @@
+-- © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+--
+-- This Source Code Form is subject to the terms of the Mozilla Public
+-- License, v. 2.0. If a copy of the MPL was not distributed with this
+-- file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+CREATE TYPE departed_ticket_user_policy AS ENUM (
+	'keep_open',
+	'close',
+	'convert_bans'
+);
+
+ALTER TABLE guilds ADD COLUMN departed_ticket_user_policy departed_ticket_user_policy NOT NULL DEFAULT 'keep_open';
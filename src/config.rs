@@ -13,6 +13,9 @@ pub struct ConfigData {
 	pub discord: DiscordArgs,
 	pub database: DatabaseArgs,
 	pub web: WebArgs,
+	/// Connection details for the partner network API. When not configured, TCN partner integration is unavailable
+	/// even for guilds that enable it.
+	pub tcn: Option<TcnArgs>,
 }
 
 #[derive(Debug)]
@@ -37,6 +40,12 @@ pub struct WebArgs {
 	pub base_url: String,
 }
 
+#[derive(Debug)]
+pub struct TcnArgs {
+	pub api_base_url: String,
+	pub api_token: String,
+}
+
 pub async fn parse_config(config_path: &str) -> miette::Result<ConfigData> {
 	let config_file_contents = read_to_string(config_path).await.into_diagnostic()?;
 	let config_document: KdlDocument = config_file_contents.parse()?;
@@ -337,7 +346,75 @@ pub async fn parse_config(config_path: &str) -> miette::Result<ConfigData> {
 		base_url: web_base_url,
 	};
 
-	let config = ConfigData { discord, database, web };
+	let tcn = match config_document.get("tcn") {
+		Some(tcn_args_node) => {
+			let Some(tcn_args) = tcn_args_node.children() else {
+				bail!(
+					miette!(code = "format::tcn", "expected tcn to have child nodes")
+						.with_source_code(format!("{}", tcn_args_node))
+				);
+			};
+			let Some(tcn_api_base_url) = tcn_args.get("api-base-url") else {
+				bail!(miette!(
+					code = "required::tcn::api-base-url",
+					"required api-base-url property of tcn"
+				));
+			};
+			let Some(tcn_api_token) = tcn_args.get("api-token") else {
+				bail!(miette!(
+					code = "required::tcn::api-token",
+					"required api-token property of tcn"
+				));
+			};
+
+			let Some(tcn_api_base_url) = tcn_api_base_url.get(0) else {
+				bail!(
+					miette!(
+						code = "value::tcn::api-base-url",
+						"expected tcn api-base-url to have a value"
+					)
+					.with_source_code(format!("{}", tcn_args_node))
+				);
+			};
+			let Some(tcn_api_base_url) = tcn_api_base_url.as_string() else {
+				bail!(
+					miette!(
+						code = "type::tcn::api-base-url",
+						"expected tcn api-base-url to be a string"
+					)
+					.with_source_code(format!("{}", tcn_args_node))
+				);
+			};
+			let tcn_api_base_url = tcn_api_base_url.trim_end_matches('/').to_string();
+
+			let Some(tcn_api_token) = tcn_api_token.get(0) else {
+				bail!(
+					miette!(code = "value::tcn::api-token", "expected tcn api-token to have a value")
+						.with_source_code(format!("{}", tcn_args_node))
+				);
+			};
+			let Some(tcn_api_token) = tcn_api_token.as_string() else {
+				bail!(
+					miette!(code = "type::tcn::api-token", "expected tcn api-token to be a string")
+						.with_source_code(format!("{}", tcn_args_node))
+				);
+			};
+			let tcn_api_token = tcn_api_token.to_string();
+
+			Some(TcnArgs {
+				api_base_url: tcn_api_base_url,
+				api_token: tcn_api_token,
+			})
+		}
+		None => None,
+	};
+
+	let config = ConfigData {
+		discord,
+		database,
+		web,
+		tcn,
+	};
 
 	Ok(config)
 }
@@ -0,0 +1,104 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::note::MAX_NOTE_LENGTH;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::CommandData;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::channel::message::component::{ActionRow, Component, TextInput, TextInputStyle};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::command::CommandBuilder;
+
+pub fn command_definition() -> Command {
+	CommandBuilder::new("Add Note", "", CommandType::User)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Add note command was used outside of a guild");
+	};
+	let Some(target_id) = command_data.target_id else {
+		bail!("Add note command was used without a target user");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let interaction_client = http_client.interaction(application_id);
+
+	if guild.is_none() {
+		let response = InteractionResponseDataBuilder::new()
+			.content(NOT_SET_UP_FOR_GUILD)
+			.flags(MessageFlags::EPHEMERAL)
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	}
+
+	let modal_id = format!("note/{}/add", target_id.get());
+	let body_input = Component::TextInput(TextInput {
+		custom_id: String::from("body"),
+		label: String::from("Note"),
+		max_length: Some(MAX_NOTE_LENGTH),
+		min_length: None,
+		placeholder: None,
+		required: Some(true),
+		style: TextInputStyle::Paragraph,
+		value: None,
+	});
+	let body_input_row = Component::ActionRow(ActionRow {
+		components: vec![body_input],
+	});
+	let response = InteractionResponseDataBuilder::new()
+		.custom_id(modal_id)
+		.title("Add Note")
+		.components(vec![body_input_row])
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::Modal,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
@@ -0,0 +1,240 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_BAN, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, NewActionRecord, audit_log_reason, defer_moderation_response, dm_result_text,
+	dm_user_about_action, dm_user_about_failed_action, dm_user_option, evidence_option, moderation_refusal,
+	perform_recorded_action, post_action_to_mod_log, reason_option, rule_number_option, send_moderation_response,
+	user_option,
+};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::temporary_bans::MAX_BAN_DURATION_DAYS;
+use crate::model::{BanAction, Guild, ModerationActionType, database_id_from_discord_id};
+use crate::schema::guilds;
+use chrono::{TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{CommandBuilder, IntegerBuilder};
+
+/// The most message history Discord can delete when banning a user
+const MAX_DELETE_MESSAGE_HOURS: i64 = 24 * 7;
+
+pub fn command_definition() -> Command {
+	let delete_message_hours = IntegerBuilder::new(
+		"delete_message_hours",
+		"How many hours of the user's most recent messages to delete (defaults to none)",
+	)
+	.min_value(0)
+	.max_value(MAX_DELETE_MESSAGE_HOURS)
+	.build();
+	let duration_days = IntegerBuilder::new(
+		"duration_days",
+		"How many days until the ban is lifted automatically (the ban is permanent if not set)",
+	)
+	.min_value(1)
+	.max_value(MAX_BAN_DURATION_DAYS)
+	.build();
+
+	CommandBuilder::new("ban", "Bans a user from the server", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::BAN_MEMBERS)
+		.option(user_option("The user to ban"))
+		.option(reason_option("Why the user is being banned"))
+		.option(evidence_option())
+		.option(rule_number_option())
+		.option(dm_user_option())
+		.option(delete_message_hours)
+		.option(duration_days)
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Ban command was used outside of a guild");
+	};
+	let (Some(moderator), Some(moderator_user)) = (interaction.member.as_ref(), interaction.author()) else {
+		bail!("Ban command was used by a non-member");
+	};
+
+	let (options, other_options) = ModerationCommandOptions::parse("ban", &command_data.options)?;
+	let mut delete_message_hours: i64 = 0;
+	let mut duration_days: Option<i64> = None;
+	for option in other_options {
+		match (option.name.as_str(), &option.value) {
+			("delete_message_hours", CommandOptionValue::Integer(hours)) => delete_message_hours = *hours,
+			("duration_days", CommandOptionValue::Integer(days)) => duration_days = Some(*days),
+			_ => bail!("Unexpected option for `/ban`: {:?}", option),
+		}
+	}
+	let Ok(delete_message_seconds) = u32::try_from(delete_message_hours.clamp(0, MAX_DELETE_MESSAGE_HOURS) * 3600)
+	else {
+		bail!("Ban message deletion duration out of range: {}", delete_message_hours);
+	};
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let refusal = moderation_refusal(
+		&mut db_connection,
+		http_client,
+		guild_id,
+		moderator_user.id,
+		moderator,
+		&options,
+		"ban",
+	)
+	.await?;
+	if let Some(refusal) = refusal {
+		return send_moderation_response(interaction, http_client, application_id, &refusal).await;
+	}
+
+	// Users can only be sent DMs while they share a server with the bot, so this has to happen before the ban
+	let dm_description = if duration_days.is_some() {
+		"temporarily banned from"
+	} else {
+		"banned from"
+	};
+	let dm_sent = if options.dm_user {
+		Some(
+			dm_user_about_action(
+				http_client,
+				guild_id,
+				options.target_user,
+				dm_description,
+				&options.reason,
+			)
+			.await,
+		)
+	} else {
+		None
+	};
+
+	let action_time = Utc::now();
+	let expires_at = duration_days.map(|days| action_time + TimeDelta::days(days.clamp(1, MAX_BAN_DURATION_DAYS)));
+	let new_ban_action = BanAction {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		banning_user: database_id_from_discord_id(moderator_user.id.get()),
+		banned_user: database_id_from_discord_id(options.target_user.get()),
+		added: true,
+		action_time,
+		reason: options.reason.clone(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: None,
+		evidence: options.evidence.clone(),
+		via_bot_command: true,
+		escalation_rule: None,
+		expires_at,
+		original_ban: None,
+		shared_ban: None,
+	};
+
+	let audit_reason = audit_log_reason(&moderator_user.name, &options.reason);
+	let ban = http_client
+		.create_ban(guild_id, options.target_user)
+		.delete_message_seconds(delete_message_seconds)
+		.reason(&audit_reason);
+	let ban_result = perform_recorded_action(&mut db_connection, NewActionRecord::Ban(&new_ban_action), ban).await?;
+	let case_number = match ban_result {
+		Ok(case_number) => case_number,
+		Err(error) => {
+			tracing::warn!(source = ?error, "Failed to ban a user through the ban command");
+			if dm_sent == Some(true) {
+				dm_user_about_failed_action(http_client, guild_id, options.target_user, dm_description).await;
+			}
+			let content = format!(
+				"I couldn't ban {}. Make sure I have the Ban Members permission and a role above theirs.",
+				options.target_user.mention()
+			);
+			return send_moderation_response(interaction, http_client, application_id, &content).await;
+		}
+	};
+	let rules = link_rules_from_reason(
+		&mut db_connection,
+		db_guild_id,
+		ModerationActionType::Ban,
+		&new_ban_action.id,
+		action_time,
+		&options.reason,
+		options.rule_number,
+	)
+	.into_diagnostic()?;
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	if let Some(evidence) = &options.evidence {
+		details.push(("Evidence", evidence.clone()));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: "Ban",
+		color: MOD_LOG_COLOR_BAN,
+		case_number: Some(case_number),
+		target_user: Some(options.target_user),
+		actor: ModLogActor::User(moderator_user.id),
+		reason: Some(&new_ban_action.reason),
+		expires_at,
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild,
+		ModerationActionType::Ban,
+		&new_ban_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	let content = match expires_at {
+		Some(expires_at) => format!(
+			"Banned {} until {}.{}",
+			options.target_user.mention(),
+			MentionTimestamp::new(expires_at.timestamp() as u64, Some(TimestampStyle::LongDateTime)).mention(),
+			dm_result_text(dm_sent)
+		),
+		None => format!("Banned {}.{}", options.target_user.mention(), dm_result_text(dm_sent)),
+	};
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
@@ -0,0 +1,125 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::mod_log::update_mod_log_expiry;
+use crate::discord::utils::moderation::{defer_moderation_response, send_moderation_response, user_option};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::temporary_bans::{MAX_BAN_DURATION_DAYS, latest_ban_action};
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::{ban_actions, guilds};
+use chrono::{TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+use twilight_util::builder::command::{CommandBuilder, IntegerBuilder};
+
+pub fn command_definition() -> Command {
+	let days = IntegerBuilder::new(
+		"days",
+		"How many days from now the ban is lifted (makes the ban permanent if not set)",
+	)
+	.min_value(1)
+	.max_value(MAX_BAN_DURATION_DAYS)
+	.build();
+
+	CommandBuilder::new(
+		"ban_expiry",
+		"Changes when a user's ban is lifted automatically",
+		CommandType::ChatInput,
+	)
+	.contexts([InteractionContextType::Guild])
+	.default_member_permissions(Permissions::BAN_MEMBERS)
+	.option(user_option("The banned user"))
+	.option(days)
+	.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Ban expiry command was used outside of a guild");
+	};
+	let Some(moderator_id) = interaction.author_id() else {
+		bail!("Ban expiry command was used by a non-user");
+	};
+
+	let mut target_user: Option<Id<UserMarker>> = None;
+	let mut days: Option<i64> = None;
+	for option in command_data.options.iter() {
+		match (option.name.as_str(), &option.value) {
+			("user", CommandOptionValue::User(user_id)) => target_user = Some(*user_id),
+			("days", CommandOptionValue::Integer(value)) => days = Some(*value),
+			_ => bail!("Unexpected option for `/ban_expiry`: {:?}", option),
+		}
+	}
+	let Some(target_user) = target_user else {
+		bail!("Command data is malformed; expected `/ban_expiry` to have a user");
+	};
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	if guild.is_none() {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	}
+
+	let ban = latest_ban_action(
+		&mut db_connection,
+		db_guild_id,
+		database_id_from_discord_id(target_user.get()),
+	)
+	.into_diagnostic()?;
+	let Some(ban) = ban.filter(|ban| ban.added) else {
+		let content = format!("{} doesn't have a recorded ban.", target_user.mention());
+		return send_moderation_response(interaction, http_client, application_id, &content).await;
+	};
+
+	let expires_at = days.map(|days| Utc::now() + TimeDelta::days(days.clamp(1, MAX_BAN_DURATION_DAYS)));
+	diesel::update(ban_actions::table)
+		.filter(ban_actions::id.eq(&ban.id))
+		.set(ban_actions::expires_at.eq(expires_at))
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+
+	if let (Some(mod_log_channel), Some(mod_log_message)) = (ban.get_mod_log_channel(), ban.get_mod_log_message()) {
+		let update_result =
+			update_mod_log_expiry(http_client, mod_log_channel, mod_log_message, expires_at, moderator_id).await;
+		if let Err(error) = update_result {
+			tracing::warn!(source = ?error, "Couldn't update the mod log entry for a changed ban expiry");
+		}
+	}
+
+	let content = match expires_at {
+		Some(expires_at) => format!(
+			"{}'s ban will be lifted on {}.",
+			target_user.mention(),
+			MentionTimestamp::new(expires_at.timestamp() as u64, Some(TimestampStyle::LongDateTime)).mention()
+		),
+		None => format!("{}'s ban is now permanent.", target_user.mention()),
+	};
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
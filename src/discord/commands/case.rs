@@ -0,0 +1,228 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::action_reasons::{MAX_REASON_LENGTH, amend_action_reason};
+use crate::discord::utils::cases::{CaseDetails, case_url, find_case};
+use crate::discord::utils::mod_log::update_mod_log_reason;
+use crate::discord::utils::moderation::{defer_moderation_response, send_moderation_response};
+use crate::discord::utils::rules::rules_for_action;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, ServerRule, database_id_from_discord_id};
+use crate::schema::guilds;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, GuildMarker};
+use twilight_util::builder::command::{CommandBuilder, IntegerBuilder, StringBuilder, SubCommandBuilder};
+
+pub fn command_definition() -> Command {
+	let number_option = || {
+		IntegerBuilder::new("number", "The case number")
+			.min_value(1)
+			.required(true)
+			.build()
+	};
+	let reason_option = StringBuilder::new("reason", "The new reason for the action")
+		.max_length(MAX_REASON_LENGTH)
+		.required(true)
+		.build();
+
+	let view = SubCommandBuilder::new("view", "Shows the details of a case").option(number_option());
+	let reason = SubCommandBuilder::new("reason", "Changes the reason given for a case")
+		.option(number_option())
+		.option(reason_option);
+	let link = SubCommandBuilder::new("link", "Gets links to a case").option(number_option());
+
+	CommandBuilder::new("case", "Works with moderation cases", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(view)
+		.option(reason)
+		.option(link)
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Case command was used outside of a guild");
+	};
+	let Some(author_id) = interaction.author_id() else {
+		bail!("Case command was used by a non-user");
+	};
+
+	let Some(subcommand) = command_data.options.first() else {
+		bail!("Command data is malformed; expected `/case` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &subcommand.value else {
+		bail!(
+			"Command data is malformed; expected `/case {}` to get subcommand data",
+			subcommand.name
+		);
+	};
+
+	let mut case_number: Option<i64> = None;
+	let mut reason: Option<String> = None;
+	for option in options.iter() {
+		match (option.name.as_str(), &option.value) {
+			("number", CommandOptionValue::Integer(value)) => case_number = Some(*value),
+			("reason", CommandOptionValue::String(value)) => reason = Some(value.trim().to_string()),
+			_ => bail!("Unexpected option for `/case {}`: {:?}", subcommand.name, option),
+		}
+	}
+	let Some(case_number) = case_number else {
+		bail!(
+			"Command data is malformed; expected `/case {}` to have a case number",
+			subcommand.name
+		);
+	};
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let case = match i32::try_from(case_number) {
+		Ok(case_number) => find_case(&mut db_connection, db_guild_id, case_number).into_diagnostic()?,
+		Err(_) => None,
+	};
+	let Some(case) = case else {
+		let content = format!("There's no case {} in this server.", case_number);
+		return send_moderation_response(interaction, http_client, application_id, &content).await;
+	};
+
+	match subcommand.name.as_str() {
+		"view" => {
+			let rules =
+				rules_for_action(&mut db_connection, case.case.action_type, &case.case.action_id).into_diagnostic()?;
+			let content = describe_case(config, &guild, guild_id, &case, &rules);
+			send_moderation_response(interaction, http_client, application_id, &content).await
+		}
+		"reason" => {
+			let Some(reason) = reason.filter(|reason| !reason.is_empty()) else {
+				return send_moderation_response(interaction, http_client, application_id, "No reason was provided.")
+					.await;
+			};
+
+			let amended_action = amend_action_reason(
+				&mut db_connection,
+				db_guild_id,
+				case.case.action_type,
+				&case.case.action_id,
+				&reason,
+				database_id_from_discord_id(author_id.get()),
+				Utc::now(),
+			)
+			.into_diagnostic()?;
+			let Some(amended_action) = amended_action else {
+				let content = format!("The reason for case {} can't be changed.", case.case.case_number);
+				return send_moderation_response(interaction, http_client, application_id, &content).await;
+			};
+
+			if let (Some(mod_log_channel), Some(mod_log_message)) =
+				(amended_action.mod_log_channel, amended_action.mod_log_message)
+			{
+				let update_result =
+					update_mod_log_reason(http_client, mod_log_channel, mod_log_message, &reason, author_id).await;
+				if let Err(error) = update_result {
+					tracing::warn!(source = ?error, "Couldn't update the mod log entry for an amended case reason");
+				}
+			}
+
+			let content = format!("Updated the reason for case {}.", case.case.case_number);
+			send_moderation_response(interaction, http_client, application_id, &content).await
+		}
+		"link" => {
+			let mut lines = vec![format!("Web: <{}>", case_url(config, &guild, case.case.case_number))];
+			if let Some(link) = mod_log_link(guild_id, &case) {
+				lines.push(format!("Mod log: {}", link));
+			}
+			send_moderation_response(interaction, http_client, application_id, &lines.join("\n")).await
+		}
+		_ => bail!(
+			"Unknown case subcommand encountered: {}\n{:?}",
+			subcommand.name,
+			command_data
+		),
+	}
+}
+
+fn mod_log_link(guild_id: Id<GuildMarker>, case: &CaseDetails) -> Option<String> {
+	match (case.mod_log_channel, case.mod_log_message) {
+		(Some(channel), Some(message)) => Some(format!(
+			"https://discord.com/channels/{}/{}/{}",
+			guild_id, channel, message
+		)),
+		_ => None,
+	}
+}
+
+fn describe_case(
+	config: &ConfigData,
+	guild: &Guild,
+	guild_id: Id<GuildMarker>,
+	case: &CaseDetails,
+	rules: &[ServerRule],
+) -> String {
+	let timestamp = |time: DateTime<Utc>| {
+		MentionTimestamp::new(time.timestamp() as u64, Some(TimestampStyle::LongDateTime))
+			.mention()
+			.to_string()
+	};
+
+	let mut lines = vec![
+		format!("**Case {}: {}**", case.case.case_number, case.title),
+		format!("User: {}", case.target_user.mention()),
+	];
+	match case.moderator {
+		Some(moderator) => lines.push(format!("Moderator: {}", moderator.mention())),
+		None => lines.push(String::from("Moderator: AutoMod")),
+	}
+	lines.push(format!("Time: {}", timestamp(case.action_time)));
+	if let Some(expires_at) = case.expires_at {
+		lines.push(format!("Expires: {}", timestamp(expires_at)));
+	}
+	lines.push(format!("Reason: {}", case.current_reason()));
+	if case.amended_reason.is_some() {
+		lines.push(format!("Original reason: {}", case.reason));
+	}
+	for rule in rules.iter() {
+		lines.push(format!("Rule broken: {} ({})", rule.rule_number, rule.title));
+	}
+	if let Some(evidence) = &case.evidence {
+		lines.push(format!("Evidence: {}", evidence));
+	}
+	lines.push(format!("Web: <{}>", case_url(config, guild, case.case.case_number)));
+	if let Some(link) = mod_log_link(guild_id, case) {
+		lines.push(format!("Mod log: {}", link));
+	}
+	lines.join("\n")
+}
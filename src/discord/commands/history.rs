@@ -0,0 +1,121 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::history::{HistoryFilter, history_page};
+use crate::discord::utils::moderation::user_option;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::command::{CommandBuilder, StringBuilder};
+
+pub fn command_definition() -> Command {
+	let show = StringBuilder::new("show", "Which kinds of history to show (shows everything if not set)")
+		.choices(HistoryFilter::command_choices())
+		.build();
+
+	CommandBuilder::new("history", "Shows a user's moderation history", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(user_option("The user whose history to show"))
+		.option(show)
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let mut target_user: Option<Id<UserMarker>> = None;
+	let mut filter = HistoryFilter::All;
+	for option in command_data.options.iter() {
+		match (option.name.as_str(), &option.value) {
+			("user", CommandOptionValue::User(user_id)) => target_user = Some(*user_id),
+			("show", CommandOptionValue::String(value)) => {
+				filter = HistoryFilter::from_name(value).unwrap_or(HistoryFilter::All)
+			}
+			_ => bail!("Unexpected option for `/history`: {:?}", option),
+		}
+	}
+	let Some(target_user) = target_user else {
+		bail!("Command data is malformed; expected `/history` to have a user");
+	};
+
+	show_history(
+		interaction,
+		http_client,
+		application_id,
+		db_connection_pool,
+		target_user,
+		filter,
+	)
+	.await
+}
+
+/// Responds to a command with the first page of a user's history
+pub async fn show_history(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	target_user: Id<UserMarker>,
+	filter: HistoryFilter,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("History command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let response = match guild {
+		Some(_) => {
+			let page = history_page(&mut db_connection, db_guild_id, target_user, filter, 0).into_diagnostic()?;
+			InteractionResponseDataBuilder::new()
+				.content(page.content)
+				.components(page.components)
+				.allowed_mentions(AllowedMentions::default())
+				.flags(MessageFlags::EPHEMERAL)
+				.build()
+		}
+		None => InteractionResponseDataBuilder::new()
+			.content(NOT_SET_UP_FOR_GUILD)
+			.flags(MessageFlags::EPHEMERAL)
+			.build(),
+	};
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
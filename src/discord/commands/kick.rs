@@ -0,0 +1,190 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_KICK, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, NewActionRecord, audit_log_reason, defer_moderation_response, dm_result_text,
+	dm_user_about_action, dm_user_about_failed_action, dm_user_option, evidence_option, moderation_refusal,
+	perform_recorded_action, post_action_to_mod_log, reason_option, rule_number_option, send_moderation_response,
+	user_option,
+};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, KickAction, ModerationActionType, database_id_from_discord_id};
+use crate::schema::guilds;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::CommandData;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::CommandBuilder;
+
+pub fn command_definition() -> Command {
+	CommandBuilder::new("kick", "Kicks a user from the server", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::KICK_MEMBERS)
+		.option(user_option("The user to kick"))
+		.option(reason_option("Why the user is being kicked"))
+		.option(evidence_option())
+		.option(rule_number_option())
+		.option(dm_user_option())
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Kick command was used outside of a guild");
+	};
+	let (Some(moderator), Some(moderator_user)) = (interaction.member.as_ref(), interaction.author()) else {
+		bail!("Kick command was used by a non-member");
+	};
+
+	let (options, other_options) = ModerationCommandOptions::parse("kick", &command_data.options)?;
+	if let Some(option) = other_options.first() {
+		bail!("Unexpected option for `/kick`: {:?}", option);
+	}
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let refusal = moderation_refusal(
+		&mut db_connection,
+		http_client,
+		guild_id,
+		moderator_user.id,
+		moderator,
+		&options,
+		"kick",
+	)
+	.await?;
+	if let Some(refusal) = refusal {
+		return send_moderation_response(interaction, http_client, application_id, &refusal).await;
+	}
+
+	// Users can only be sent DMs while they share a server with the bot, so this has to happen before the kick
+	let dm_sent = if options.dm_user {
+		Some(
+			dm_user_about_action(
+				http_client,
+				guild_id,
+				options.target_user,
+				"kicked from",
+				&options.reason,
+			)
+			.await,
+		)
+	} else {
+		None
+	};
+
+	let action_time = Utc::now();
+	let new_kick_action = KickAction {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		kicking_user: database_id_from_discord_id(moderator_user.id.get()),
+		kicked_user: database_id_from_discord_id(options.target_user.get()),
+		action_time,
+		reason: options.reason.clone(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: None,
+		evidence: options.evidence.clone(),
+		via_bot_command: true,
+		escalation_rule: None,
+	};
+
+	let audit_reason = audit_log_reason(&moderator_user.name, &options.reason);
+	let kick = http_client
+		.remove_guild_member(guild_id, options.target_user)
+		.reason(&audit_reason);
+	let kick_result =
+		perform_recorded_action(&mut db_connection, NewActionRecord::Kick(&new_kick_action), kick).await?;
+	let case_number = match kick_result {
+		Ok(case_number) => case_number,
+		Err(error) => {
+			tracing::warn!(source = ?error, "Failed to kick a user through the kick command");
+			if dm_sent == Some(true) {
+				dm_user_about_failed_action(http_client, guild_id, options.target_user, "kicked from").await;
+			}
+			let content = format!(
+				"I couldn't kick {}. Make sure they're in the server and I have the Kick Members permission and a role above theirs.",
+				options.target_user.mention()
+			);
+			return send_moderation_response(interaction, http_client, application_id, &content).await;
+		}
+	};
+	let rules = link_rules_from_reason(
+		&mut db_connection,
+		db_guild_id,
+		ModerationActionType::Kick,
+		&new_kick_action.id,
+		action_time,
+		&options.reason,
+		options.rule_number,
+	)
+	.into_diagnostic()?;
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	if let Some(evidence) = &options.evidence {
+		details.push(("Evidence", evidence.clone()));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: "Kick",
+		color: MOD_LOG_COLOR_KICK,
+		case_number: Some(case_number),
+		target_user: Some(options.target_user),
+		actor: ModLogActor::User(moderator_user.id),
+		reason: Some(&new_kick_action.reason),
+		expires_at: None,
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild,
+		ModerationActionType::Kick,
+		&new_kick_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	let content = format!("Kicked {}.{}", options.target_user.mention(), dm_result_text(dm_sent));
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::bail;
@@ -17,23 +18,45 @@ use twilight_model::id::Id;
 use twilight_model::id::marker::ApplicationMarker;
 use type_map::concurrent::TypeMap;
 
+mod add_note;
+mod ban;
+mod ban_expiry;
+mod case;
 mod close;
+mod history;
+mod kick;
 mod list_restricted_users;
+mod note;
 mod reply;
 mod restrict_ticket_user;
 mod settings;
 mod setup;
+mod timeout;
 mod unrestrict_ticket_user;
+mod user_history;
+mod warn;
+mod warn_user;
 
 pub fn command_definitions() -> Vec<Command> {
 	vec![
+		add_note::command_definition(),
+		ban::command_definition(),
+		ban_expiry::command_definition(),
+		case::command_definition(),
 		close::command_definition(),
+		history::command_definition(),
+		kick::command_definition(),
 		list_restricted_users::command_definition(),
+		note::command_definition(),
 		reply::command_definition(),
 		restrict_ticket_user::command_definition(),
 		setup::command_definition(),
 		settings::command_definition(),
+		timeout::command_definition(),
 		unrestrict_ticket_user::command_definition(),
+		user_history::command_definition(),
+		warn::command_definition(),
+		warn_user::command_definition(),
 	]
 }
 
@@ -41,15 +64,100 @@ pub async fn route_command(
 	interaction: &InteractionCreate,
 	command_data: &CommandData,
 	http_client: &Arc<Client>,
+	config: &ConfigData,
 	application_id: Id<ApplicationMarker>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	bot_state: Arc<RwLock<TypeMap>>,
 ) -> miette::Result<()> {
 	match command_data.name.as_str() {
+		"Add Note" => {
+			add_note::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"ban" => {
+			ban::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"ban_expiry" => {
+			ban_expiry::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"case" => {
+			case::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"close" => close::handle_command(interaction, http_client, application_id, db_connection_pool).await,
+		"history" => {
+			history::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"kick" => {
+			kick::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"list_restricted_users" => {
 			list_restricted_users::handle_command(interaction, http_client, application_id, db_connection_pool).await
 		}
+		"Moderation History" => {
+			user_history::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"note" => {
+			note::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"reply" => reply::handle_command(interaction, http_client, application_id, db_connection_pool, bot_state).await,
 		"restrict_ticket_user" => {
 			restrict_ticket_user::handle_command(
@@ -67,12 +175,24 @@ pub async fn route_command(
 				interaction,
 				command_data,
 				http_client,
+				config,
 				application_id,
 				db_connection_pool,
 				bot_state,
 			)
 			.await
 		}
+		"timeout" => {
+			timeout::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"unrestrict_ticket_user" => {
 			unrestrict_ticket_user::handle_command(
 				interaction,
@@ -83,6 +203,27 @@ pub async fn route_command(
 			)
 			.await
 		}
+		"warn" => {
+			warn::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"Warn" => {
+			warn_user::handle_command(
+				interaction,
+				command_data,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		_ => bail!("Unknown command encountered: {}\n{:?}", command_data.name, command_data),
 	}
 }
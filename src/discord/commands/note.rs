@@ -0,0 +1,536 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::web_urls::guild_web_url;
+use crate::model::{Guild, ModerationActionType, Ticket, UserNote, database_id_from_discord_id};
+use crate::schema::{
+	automod_actions, ban_actions, guilds, kick_actions, tickets, timeout_actions, user_note_edits, user_notes,
+	warn_actions,
+};
+use chrono::Utc;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use std::collections::HashMap;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::channel::message::component::{ActionRow, Component, TextInput, TextInputStyle};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::command::{CommandBuilder, StringBuilder, SubCommandBuilder, UserBuilder};
+
+/// The longest note body we accept; matches the maximum length of a modal text input so that notes can always be
+/// edited in full.
+pub const MAX_NOTE_LENGTH: u16 = 4000;
+
+/// How many characters of each note's body are shown when listing notes; the full notes are on the website
+const NOTE_PREVIEW_LENGTH: usize = 200;
+
+/// How much of a note list message can be taken up by notes, leaving room for the footer linking to the website
+const NOTE_LIST_CONTENT_LENGTH: usize = 1700;
+
+pub fn command_definition() -> Command {
+	let add_user = UserBuilder::new("user", "The user the note is about")
+		.required(true)
+		.build();
+	let add_note = StringBuilder::new("note", "The text of the note")
+		.max_length(MAX_NOTE_LENGTH)
+		.required(true)
+		.build();
+	let add_action_type = StringBuilder::new("action_type", "The type of moderation action the note is about")
+		.choices([
+			("Automod", "automod"),
+			("Ban", "ban"),
+			("Kick", "kick"),
+			("Timeout", "timeout"),
+			("Warning", "warn"),
+		])
+		.build();
+	let add_action_id = StringBuilder::new("action_id", "The ID of the moderation action the note is about").build();
+	let add_ticket_id = StringBuilder::new("ticket_id", "The ID of the ticket the note is about").build();
+	let add = SubCommandBuilder::new("add", "Adds a note about a user")
+		.option(add_user)
+		.option(add_note)
+		.option(add_action_type)
+		.option(add_action_id)
+		.option(add_ticket_id);
+
+	let list_user = UserBuilder::new("user", "The user for whom to list notes")
+		.required(true)
+		.build();
+	let list = SubCommandBuilder::new("list", "Lists notes about a user").option(list_user);
+
+	let edit_note_id = StringBuilder::new("note_id", "The ID of the note to edit")
+		.required(true)
+		.build();
+	let edit = SubCommandBuilder::new("edit", "Edits a note").option(edit_note_id);
+
+	let remove_note_id = StringBuilder::new("note_id", "The ID of the note to remove")
+		.required(true)
+		.build();
+	let remove = SubCommandBuilder::new("remove", "Removes a note").option(remove_note_id);
+
+	CommandBuilder::new("note", "Manage staff notes about users", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(add)
+		.option(list)
+		.option(edit)
+		.option(remove)
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			NOT_SET_UP_FOR_GUILD.to_string(),
+			true,
+		)
+		.await;
+	};
+
+	let Some(subcommand) = command_data.options.first() else {
+		bail!("Note command invoked with no subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &subcommand.value else {
+		bail!(
+			"Command data is malformed; expected `/note {}` to get subcommand data",
+			subcommand.name
+		);
+	};
+
+	let mut user: Option<Id<UserMarker>> = None;
+	let mut strings: HashMap<&str, &str> = HashMap::new();
+	for option in options.iter() {
+		match &option.value {
+			CommandOptionValue::User(value) => user = Some(*value),
+			CommandOptionValue::String(value) => {
+				strings.insert(option.name.as_str(), value.as_str());
+			}
+			_ => bail!(
+				"Unexpected option type for `/note {}` option {}",
+				subcommand.name,
+				option.name
+			),
+		}
+	}
+
+	match subcommand.name.as_str() {
+		"add" => {
+			let Some(user) = user else {
+				bail!("Command data is malformed; `/note add` requires a user");
+			};
+			let Some(body) = strings.get("note") else {
+				bail!("Command data is malformed; `/note add` requires a note");
+			};
+			add_note(
+				interaction,
+				user,
+				body,
+				strings.get("action_type").copied(),
+				strings.get("action_id").copied(),
+				strings.get("ticket_id").copied(),
+				http_client,
+				application_id,
+				&mut db_connection,
+			)
+			.await
+		}
+		"list" => {
+			let Some(user) = user else {
+				bail!("Command data is malformed; `/note list` requires a user");
+			};
+			list_notes(
+				interaction,
+				&guild,
+				user,
+				http_client,
+				config,
+				application_id,
+				&mut db_connection,
+			)
+			.await
+		}
+		"edit" => {
+			let Some(note_id) = strings.get("note_id") else {
+				bail!("Command data is malformed; `/note edit` requires a note ID");
+			};
+			edit_note(interaction, note_id, http_client, application_id, &mut db_connection).await
+		}
+		"remove" => {
+			let Some(note_id) = strings.get("note_id") else {
+				bail!("Command data is malformed; `/note remove` requires a note ID");
+			};
+			remove_note(interaction, note_id, http_client, application_id, &mut db_connection).await
+		}
+		_ => bail!(
+			"Unknown note subcommand encountered: {}\n{:?}",
+			subcommand.name,
+			subcommand
+		),
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn add_note(
+	interaction: &InteractionCreate,
+	user: Id<UserMarker>,
+	body: &str,
+	action_type: Option<&str>,
+	action_id: Option<&str>,
+	ticket_id: Option<&str>,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note command was used outside of a guild");
+	};
+	let Some(author) = interaction.author_id() else {
+		bail!("Note command was used by a non-user");
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let db_user_id = database_id_from_discord_id(user.get());
+
+	let action_type = match action_type {
+		Some("automod") => Some(ModerationActionType::Automod),
+		Some("ban") => Some(ModerationActionType::Ban),
+		Some("kick") => Some(ModerationActionType::Kick),
+		Some("timeout") => Some(ModerationActionType::Timeout),
+		Some("warn") => Some(ModerationActionType::Warn),
+		Some(other) => bail!("Unexpected action type for `/note add`: {}", other),
+		None => None,
+	};
+	let (action_type, action_id) = match (action_type, action_id) {
+		(Some(action_type), Some(action_id)) => {
+			if !action_exists(db_connection, db_guild_id, db_user_id, action_type, action_id)? {
+				let content = format!(
+					"No {} action with ID `{}` was found for {}.",
+					action_type.to_string().to_lowercase(),
+					action_id,
+					user.mention()
+				);
+				return respond_with_message(interaction, http_client, application_id, content, true).await;
+			}
+			(Some(action_type), Some(action_id.to_string()))
+		}
+		(None, None) => (None, None),
+		_ => {
+			let content =
+				String::from("To link a note to a moderation action, both the action type and ID are required.");
+			return respond_with_message(interaction, http_client, application_id, content, true).await;
+		}
+	};
+
+	let ticket: Option<Ticket> = match ticket_id {
+		Some(ticket_id) => {
+			let ticket: Option<Ticket> = tickets::table
+				.find(ticket_id)
+				.filter(tickets::guild.eq(db_guild_id))
+				.first(db_connection)
+				.optional()
+				.into_diagnostic()?;
+			if ticket.is_none() {
+				let content = format!("No ticket with ID `{}` exists on this server.", ticket_id);
+				return respond_with_message(interaction, http_client, application_id, content, true).await;
+			}
+			ticket
+		}
+		None => match interaction.channel.as_ref() {
+			Some(channel) => {
+				let db_channel_id = database_id_from_discord_id(channel.id.get());
+				tickets::table
+					.filter(
+						tickets::staff_thread
+							.eq(db_channel_id)
+							.and(tickets::with_user.eq(db_user_id)),
+					)
+					.first(db_connection)
+					.optional()
+					.into_diagnostic()?
+			}
+			None => None,
+		},
+	};
+
+	let note = UserNote {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		target_user: db_user_id,
+		author: database_id_from_discord_id(author.get()),
+		created_at: Utc::now(),
+		body: body.to_string(),
+		action_type,
+		action_id,
+		ticket: ticket.map(|ticket| ticket.id),
+	};
+	diesel::insert_into(user_notes::table)
+		.values(&note)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	let content = format!("Added note `{}` for {}.", note.id, user.mention());
+	respond_with_message(interaction, http_client, application_id, content, true).await
+}
+
+async fn list_notes(
+	interaction: &InteractionCreate,
+	guild: &Guild,
+	user: Id<UserMarker>,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let db_user_id = database_id_from_discord_id(user.get());
+
+	let notes: Vec<UserNote> = user_notes::table
+		.filter(
+			user_notes::guild
+				.eq(guild.guild_id)
+				.and(user_notes::target_user.eq(db_user_id)),
+		)
+		.order(user_notes::created_at.desc())
+		.load(db_connection)
+		.into_diagnostic()?;
+
+	if notes.is_empty() {
+		let content = format!("There are no notes for {}.", user.mention());
+		return respond_with_message(interaction, http_client, application_id, content, true).await;
+	}
+
+	let note_ids: Vec<&str> = notes.iter().map(|note| note.id.as_str()).collect();
+	let edit_counts: Vec<(String, i64)> = user_note_edits::table
+		.filter(user_note_edits::note.eq_any(&note_ids))
+		.group_by(user_note_edits::note)
+		.select((user_note_edits::note, count_star()))
+		.load(db_connection)
+		.into_diagnostic()?;
+	let edit_counts: HashMap<String, i64> = edit_counts.into_iter().collect();
+
+	let mut content = format!("Notes for {}:", user.mention());
+	let mut any_truncated = false;
+	let mut omitted_notes = 0;
+	for note in notes.iter() {
+		let created_at = MentionTimestamp::new(note.created_at.timestamp() as u64, Some(TimestampStyle::ShortDateTime));
+		let mut line = format!(
+			"\n- `{}` by {} {}",
+			note.id,
+			note.get_author().mention(),
+			created_at.mention()
+		);
+		if let (Some(action_type), Some(action_id)) = (note.action_type, note.action_id.as_ref()) {
+			line = format!(
+				"{} (about {} `{}`)",
+				line,
+				action_type.to_string().to_lowercase(),
+				action_id
+			);
+		}
+		if let Some(ticket) = note.ticket.as_ref() {
+			line = format!("{} (about ticket `{}`)", line, ticket);
+		}
+		if let Some(count) = edit_counts.get(&note.id) {
+			line = format!("{} (edited {} times)", line, count);
+		}
+		let preview = match note.body.char_indices().nth(NOTE_PREVIEW_LENGTH) {
+			Some((preview_end, _)) => {
+				any_truncated = true;
+				format!("{}…", &note.body[..preview_end])
+			}
+			None => note.body.clone(),
+		};
+		line = format!("{}: {}", line, preview);
+
+		if omitted_notes > 0 || content.len() + line.len() > NOTE_LIST_CONTENT_LENGTH {
+			omitted_notes += 1;
+			continue;
+		}
+		content.push_str(&line);
+	}
+	if omitted_notes > 0 {
+		content = format!("{}\n...and {} more notes", content, omitted_notes);
+	}
+	if any_truncated || omitted_notes > 0 {
+		let notes_url = guild_web_url(config, guild, &format!("staff/user_notes/{}", user));
+		content = format!("{}\nSee the full notes on the website: <{}>", content, notes_url);
+	}
+
+	respond_with_message(interaction, http_client, application_id, content, true).await
+}
+
+async fn edit_note(
+	interaction: &InteractionCreate,
+	note_id: &str,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note command was used outside of a guild");
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+
+	let note: Option<UserNote> = user_notes::table
+		.find(note_id)
+		.filter(user_notes::guild.eq(db_guild_id))
+		.first(db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(note) = note else {
+		let content = format!("No note with ID `{}` exists on this server.", note_id);
+		return respond_with_message(interaction, http_client, application_id, content, true).await;
+	};
+
+	let modal_id = format!("note/{}/edit", note.id);
+	let body_input = Component::TextInput(TextInput {
+		custom_id: String::from("body"),
+		label: String::from("Note"),
+		max_length: Some(MAX_NOTE_LENGTH),
+		min_length: None,
+		placeholder: None,
+		required: Some(true),
+		style: TextInputStyle::Paragraph,
+		value: Some(note.body),
+	});
+	let body_input_row = Component::ActionRow(ActionRow {
+		components: vec![body_input],
+	});
+	let response = InteractionResponseDataBuilder::new()
+		.custom_id(modal_id)
+		.title("Edit Note")
+		.components(vec![body_input_row])
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::Modal,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+async fn remove_note(
+	interaction: &InteractionCreate,
+	note_id: &str,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note command was used outside of a guild");
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+
+	let removed_note: Option<UserNote> = diesel::delete(user_notes::table)
+		.filter(user_notes::id.eq(note_id).and(user_notes::guild.eq(db_guild_id)))
+		.get_result(db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let content = match removed_note {
+		Some(note) => format!("Removed note `{}` for {}.", note.id, note.get_target_user().mention()),
+		None => format!("No note with ID `{}` exists on this server.", note_id),
+	};
+	respond_with_message(interaction, http_client, application_id, content, true).await
+}
+
+/// Checks whether the given moderation action exists for the given user in the given guild
+fn action_exists(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	db_user_id: i64,
+	action_type: ModerationActionType,
+	action_id: &str,
+) -> miette::Result<bool> {
+	let count: i64 = match action_type {
+		ModerationActionType::Automod => automod_actions::table
+			.filter(
+				automod_actions::id
+					.eq(action_id)
+					.and(automod_actions::guild.eq(db_guild_id))
+					.and(automod_actions::target_user.eq(db_user_id)),
+			)
+			.count()
+			.get_result(db_connection),
+		ModerationActionType::Ban => ban_actions::table
+			.filter(
+				ban_actions::id
+					.eq(action_id)
+					.and(ban_actions::guild.eq(db_guild_id))
+					.and(ban_actions::banned_user.eq(db_user_id)),
+			)
+			.count()
+			.get_result(db_connection),
+		ModerationActionType::Kick => kick_actions::table
+			.filter(
+				kick_actions::id
+					.eq(action_id)
+					.and(kick_actions::guild.eq(db_guild_id))
+					.and(kick_actions::kicked_user.eq(db_user_id)),
+			)
+			.count()
+			.get_result(db_connection),
+		ModerationActionType::Timeout => timeout_actions::table
+			.filter(
+				timeout_actions::id
+					.eq(action_id)
+					.and(timeout_actions::guild.eq(db_guild_id))
+					.and(timeout_actions::target_user.eq(db_user_id)),
+			)
+			.count()
+			.get_result(db_connection),
+		ModerationActionType::Warn => warn_actions::table
+			.filter(
+				warn_actions::id
+					.eq(action_id)
+					.and(warn_actions::guild.eq(db_guild_id))
+					.and(warn_actions::warned_user.eq(db_user_id)),
+			)
+			.count()
+			.get_result(db_connection),
+	}
+	.into_diagnostic()?;
+	Ok(count > 0)
+}
@@ -0,0 +1,133 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{DepartedTicketUserPolicy, Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{StringBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+fn policy_from_option_value(value: &str) -> Option<DepartedTicketUserPolicy> {
+	match value {
+		"keep_open" => Some(DepartedTicketUserPolicy::KeepOpen),
+		"close" => Some(DepartedTicketUserPolicy::Close),
+		"convert_bans" => Some(DepartedTicketUserPolicy::ConvertBans),
+		_ => None,
+	}
+}
+
+pub fn subcommand_definition() -> CommandOption {
+	let policy_option = StringBuilder::new("policy", "What to do with open tickets when their user departs")
+		.choices([
+			("Keep tickets open", "keep_open"),
+			("Close tickets", "close"),
+			("Convert tickets of banned users to ban appeals", "convert_bans"),
+		])
+		.required(true)
+		.build();
+
+	let get = SubCommandBuilder::new("get", "Gets what happens to tickets when their user departs");
+	let set =
+		SubCommandBuilder::new("set", "Sets what happens to tickets when their user departs").option(policy_option);
+
+	SubCommandGroupBuilder::new(
+		"departed_ticket_user_policy",
+		"Manages what happens to open tickets when their user leaves, is kicked, or is banned",
+	)
+	.subcommands([get, set])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!(
+			"Command data is malformed; expected `/settings departed_ticket_user_policy` to get a subcommand group value"
+		);
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings departed_ticket_user_policy` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings departed_ticket_user_policy {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"get" => {
+			let content = format!(
+				"When a ticket's user leaves, is kicked, or is banned, the bot will {}.",
+				guild.departed_ticket_user_policy
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"set" => {
+			let policy = match options.first().map(|option| &option.value) {
+				Some(CommandOptionValue::String(value)) => policy_from_option_value(value),
+				_ => None,
+			};
+			let Some(policy) = policy else {
+				bail!(
+					"Command data is malformed; expected `/settings departed_ticket_user_policy set` to have a policy"
+				);
+			};
+			diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(db_guild_id))
+				.set(guilds::departed_ticket_user_policy.eq(policy))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+			let content = format!(
+				"When a ticket's user leaves, is kicked, or is banned, the bot will now {}. Staff are told in the ticket either way.",
+				policy
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		_ => bail!(
+			"Unknown settings departed_ticket_user_policy subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
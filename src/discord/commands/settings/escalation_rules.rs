@@ -0,0 +1,320 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::escalations::describe_escalation_rule;
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{EscalationActionType, EscalationRule, EscalationTriggerType, Guild, database_id_from_discord_id};
+use crate::schema::{escalation_rules, guilds};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{IntegerBuilder, StringBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+/// The most actions a rule can require before it applies
+const MAX_ESCALATION_THRESHOLD: i64 = 100;
+
+/// The longest window a rule can count actions over, in days
+const MAX_ESCALATION_WINDOW_DAYS: i64 = 365;
+
+fn trigger_type_from_option_value(value: &str) -> Option<EscalationTriggerType> {
+	match value {
+		"automod_block" => Some(EscalationTriggerType::AutomodBlock),
+		"automod_flag" => Some(EscalationTriggerType::AutomodFlag),
+		"automod_timeout" => Some(EscalationTriggerType::AutomodTimeout),
+		"kick" => Some(EscalationTriggerType::Kick),
+		"timeout" => Some(EscalationTriggerType::Timeout),
+		_ => None,
+	}
+}
+
+fn action_type_from_option_value(value: &str) -> Option<EscalationActionType> {
+	match value {
+		"timeout" => Some(EscalationActionType::Timeout),
+		"kick" => Some(EscalationActionType::Kick),
+		"ban" => Some(EscalationActionType::Ban),
+		"staff_review" => Some(EscalationActionType::StaffReview),
+		_ => None,
+	}
+}
+
+pub fn subcommand_definition() -> CommandOption {
+	let trigger_option = StringBuilder::new("trigger", "The type of action to count")
+		.choices([
+			("Automod message blocks", "automod_block"),
+			("Automod flags", "automod_flag"),
+			("Automod timeouts", "automod_timeout"),
+			("Kicks", "kick"),
+			("Timeouts", "timeout"),
+		])
+		.required(true)
+		.build();
+	let threshold_option = IntegerBuilder::new("threshold", "How many of those actions cause the rule to apply")
+		.min_value(1)
+		.max_value(MAX_ESCALATION_THRESHOLD)
+		.required(true)
+		.build();
+	let action_option = StringBuilder::new("action", "What to do when the rule applies")
+		.choices([
+			("Time out the user", "timeout"),
+			("Kick the user", "kick"),
+			("Ban the user", "ban"),
+			("Ask staff to review the user", "staff_review"),
+		])
+		.required(true)
+		.build();
+	let window_option = IntegerBuilder::new(
+		"window_days",
+		"Only count actions from this many days back (counts all actions if not set)",
+	)
+	.min_value(1)
+	.max_value(MAX_ESCALATION_WINDOW_DAYS)
+	.build();
+	let timeout_option = IntegerBuilder::new("timeout_duration", "How long to time out the user for")
+		.choices([
+			("1 hour", 60),
+			("6 hours", 360),
+			("1 day", 1440),
+			("3 days", 4320),
+			("1 week", 10080),
+			("28 days", 40320),
+		])
+		.build();
+	let rule_id_option = StringBuilder::new("rule_id", "The ID of the rule to remove (shown by list)")
+		.required(true)
+		.build();
+
+	let list = SubCommandBuilder::new("list", "Lists the escalation rules for this server");
+	let add = SubCommandBuilder::new("add", "Adds an escalation rule")
+		.option(trigger_option)
+		.option(threshold_option)
+		.option(action_option)
+		.option(window_option)
+		.option(timeout_option);
+	let remove = SubCommandBuilder::new("remove", "Removes an escalation rule").option(rule_id_option);
+
+	SubCommandGroupBuilder::new(
+		"escalation_rules",
+		"Manages actions taken automatically against users with repeated moderation actions",
+	)
+	.subcommands([list, add, remove])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings escalation_rules` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings escalation_rules` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings escalation_rules {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"list" => {
+			let rules: Vec<EscalationRule> = escalation_rules::table
+				.filter(
+					escalation_rules::guild
+						.eq(db_guild_id)
+						.and(escalation_rules::active.eq(true)),
+				)
+				.order(escalation_rules::created_at.asc())
+				.load(&mut db_connection)
+				.into_diagnostic()?;
+			let content = if rules.is_empty() {
+				String::from("There are no escalation rules set up for this server.")
+			} else {
+				let lines: Vec<String> = rules
+					.iter()
+					.map(|rule| format!("- `{}`: {}", rule.id, describe_escalation_rule(rule)))
+					.collect();
+				format!("Escalation rules:\n{}", lines.join("\n"))
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"add" => {
+			let Some(author_id) = interaction.author_id() else {
+				bail!("Settings command was used without an author");
+			};
+
+			let mut trigger_type: Option<EscalationTriggerType> = None;
+			let mut threshold: Option<i64> = None;
+			let mut action_type: Option<EscalationActionType> = None;
+			let mut window_days: Option<i64> = None;
+			let mut timeout_minutes: Option<i64> = None;
+			for option in options.iter() {
+				match (option.name.as_str(), &option.value) {
+					("trigger", CommandOptionValue::String(value)) => {
+						trigger_type = trigger_type_from_option_value(value)
+					}
+					("threshold", CommandOptionValue::Integer(value)) => threshold = Some(*value),
+					("action", CommandOptionValue::String(value)) => action_type = action_type_from_option_value(value),
+					("window_days", CommandOptionValue::Integer(value)) => window_days = Some(*value),
+					("timeout_duration", CommandOptionValue::Integer(value)) => timeout_minutes = Some(*value),
+					_ => (),
+				}
+			}
+			let (Some(trigger_type), Some(threshold), Some(action_type)) = (trigger_type, threshold, action_type)
+			else {
+				bail!(
+					"Command data is malformed; expected `/settings escalation_rules add` to have a trigger, threshold, and action"
+				);
+			};
+
+			match (action_type, timeout_minutes) {
+				(EscalationActionType::Timeout, None) => {
+					return respond_with_message(
+						interaction,
+						http_client,
+						application_id,
+						String::from("Rules that time out users need a timeout duration."),
+						true,
+					)
+					.await;
+				}
+				(EscalationActionType::Timeout, Some(_)) | (_, None) => (),
+				(_, Some(_)) => {
+					return respond_with_message(
+						interaction,
+						http_client,
+						application_id,
+						String::from("A timeout duration can only be set for rules that time out users."),
+						true,
+					)
+					.await;
+				}
+			}
+
+			// Staff reviews are requested in the mod log, so without one they'd never reach staff
+			if action_type == EscalationActionType::StaffReview && guild.get_mod_log_channel().is_none() {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					String::from("Rules that ask staff to review users need a mod log channel to be set up first."),
+					true,
+				)
+				.await;
+			}
+
+			let Ok(threshold) = i32::try_from(threshold.clamp(1, MAX_ESCALATION_THRESHOLD)) else {
+				bail!("Escalation threshold out of range: {}", threshold);
+			};
+			let window_days = window_days
+				.map(|days| i32::try_from(days.clamp(1, MAX_ESCALATION_WINDOW_DAYS)))
+				.transpose()
+				.into_diagnostic()?;
+			let timeout_minutes = timeout_minutes.map(i32::try_from).transpose().into_diagnostic()?;
+
+			let new_rule = EscalationRule {
+				id: cuid2::create_id(),
+				guild: db_guild_id,
+				trigger_type,
+				threshold,
+				window_days,
+				action_type,
+				timeout_minutes,
+				created_by: database_id_from_discord_id(author_id.get()),
+				created_at: Utc::now(),
+				active: true,
+			};
+			diesel::insert_into(escalation_rules::table)
+				.values(&new_rule)
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			let content = format!(
+				"Added escalation rule `{}`: {}",
+				new_rule.id,
+				describe_escalation_rule(&new_rule)
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"remove" => {
+			let Some(CommandOptionValue::String(rule_id)) = options.first().map(|option| &option.value) else {
+				bail!("Command data is malformed; expected `/settings escalation_rules remove` to have a rule ID");
+			};
+
+			// Rules are deactivated rather than deleted so actions they took still show which rule applied them
+			let updated_count = diesel::update(escalation_rules::table)
+				.filter(
+					escalation_rules::id
+						.eq(rule_id)
+						.and(escalation_rules::guild.eq(db_guild_id))
+						.and(escalation_rules::active.eq(true)),
+				)
+				.set(escalation_rules::active.eq(false))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			if updated_count == 0 {
+				respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					format!("There's no escalation rule with the ID `{}`.", rule_id),
+					true,
+				)
+				.await
+			} else {
+				respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					format!("Removed escalation rule `{}`.", rule_id),
+					false,
+				)
+				.await
+			}
+		}
+		_ => bail!(
+			"Unknown settings escalation_rules subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
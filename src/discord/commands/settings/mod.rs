@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::bail;
@@ -24,12 +25,25 @@ mod action_reason_complain_channel;
 mod admin_role;
 mod ban_appeal_ticket;
 mod custom_categories;
+mod departed_ticket_user_policy;
+mod escalation_rules;
 mod existing_partner_ticket;
+mod member_alert_channel;
 mod message_reports_channel;
+mod mod_log_channel;
+mod new_account_age;
 mod new_partner_ticket;
+mod partner_invite_alert_channel;
+mod partner_list_channel;
+mod reason_policy;
+mod rules;
 mod staff_role;
 mod start_ticket_channel;
 mod start_ticket_message;
+mod tcn_partner_integration;
+mod tracked_actions;
+mod trusted_guilds;
+mod warning_expiry;
 
 pub fn command_definition() -> Command {
 	CommandBuilder::new(
@@ -43,12 +57,25 @@ pub fn command_definition() -> Command {
 	.option(admin_role::subcommand_definition())
 	.option(ban_appeal_ticket::subcommand_definition())
 	.option(custom_categories::subcommand_definition())
+	.option(departed_ticket_user_policy::subcommand_definition())
+	.option(escalation_rules::subcommand_definition())
 	.option(existing_partner_ticket::subcommand_definition())
+	.option(member_alert_channel::subcommand_definition())
 	.option(message_reports_channel::subcommand_definition())
+	.option(mod_log_channel::subcommand_definition())
+	.option(new_account_age::subcommand_definition())
 	.option(new_partner_ticket::subcommand_definition())
+	.option(partner_invite_alert_channel::subcommand_definition())
+	.option(partner_list_channel::subcommand_definition())
+	.option(reason_policy::subcommand_definition())
+	.option(rules::subcommand_definition())
 	.option(staff_role::subcommand_definition())
 	.option(start_ticket_channel::subcommand_definition())
 	.option(start_ticket_message::subcommand_definition())
+	.option(tcn_partner_integration::subcommand_definition())
+	.option(tracked_actions::subcommand_definition())
+	.option(trusted_guilds::subcommand_definition())
+	.option(warning_expiry::subcommand_definition())
 	.build()
 }
 
@@ -56,6 +83,7 @@ pub async fn handle_command(
 	interaction: &InteractionCreate,
 	command_data: &CommandData,
 	http_client: &Client,
+	config: &ConfigData,
 	application_id: Id<ApplicationMarker>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	bot_state: Arc<RwLock<TypeMap>>,
@@ -107,6 +135,26 @@ pub async fn handle_command(
 			)
 			.await
 		}
+		"departed_ticket_user_policy" => {
+			departed_ticket_user_policy::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"escalation_rules" => {
+			escalation_rules::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"existing_partner_ticket" => {
 			existing_partner_ticket::handle_subcommand(
 				interaction,
@@ -118,6 +166,16 @@ pub async fn handle_command(
 			)
 			.await
 		}
+		"member_alert_channel" => {
+			member_alert_channel::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"message_reports_channel" => {
 			message_reports_channel::handle_subcommand(
 				interaction,
@@ -128,6 +186,26 @@ pub async fn handle_command(
 			)
 			.await
 		}
+		"mod_log_channel" => {
+			mod_log_channel::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"new_account_age" => {
+			new_account_age::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"new_partner_ticket" => {
 			new_partner_ticket::handle_subcommand(
 				interaction,
@@ -139,6 +217,46 @@ pub async fn handle_command(
 			)
 			.await
 		}
+		"partner_invite_alert_channel" => {
+			partner_invite_alert_channel::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"partner_list_channel" => {
+			partner_list_channel::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"reason_policy" => {
+			reason_policy::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"rules" => {
+			rules::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		"staff_role" => {
 			staff_role::handle_subcommand(
 				interaction,
@@ -169,6 +287,47 @@ pub async fn handle_command(
 			)
 			.await
 		}
+		"tracked_actions" => {
+			tracked_actions::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"trusted_guilds" => {
+			trusted_guilds::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"tcn_partner_integration" => {
+			tcn_partner_integration::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"warning_expiry" => {
+			warning_expiry::handle_subcommand(
+				interaction,
+				&subcommand_data.value,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		_ => bail!(
 			"Unknown settings subcommand encountered: {}\n{:?}",
 			subcommand_data.name,
@@ -0,0 +1,129 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{IntegerBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+/// The oldest an account can be and still be flagged as new, in days
+const MAX_NEW_ACCOUNT_AGE_DAYS: i64 = 365;
+
+pub fn subcommand_definition() -> CommandOption {
+	let days_option = IntegerBuilder::new(
+		"days",
+		"Accounts younger than this many days are flagged when they join",
+	)
+	.min_value(1)
+	.max_value(MAX_NEW_ACCOUNT_AGE_DAYS)
+	.required(true)
+	.build();
+
+	let get = SubCommandBuilder::new("get", "Gets how new an account must be to be flagged when it joins");
+	let set = SubCommandBuilder::new("set", "Sets how new an account must be to be flagged when it joins")
+		.option(days_option);
+	let unset = SubCommandBuilder::new("unset", "Stops flagging new accounts when they join");
+
+	SubCommandGroupBuilder::new("new_account_age", "Manages flagging new accounts when they join")
+		.subcommands([get, set, unset])
+		.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings new_account_age` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings new_account_age` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings new_account_age {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"get" => {
+			let content = match guild.new_account_age_days {
+				Some(days) => format!("Accounts younger than {} days are flagged when they join.", days),
+				None => String::from("New accounts aren't flagged when they join."),
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"set" => {
+			let Some(CommandOptionValue::Integer(days)) = options.first().map(|option| &option.value) else {
+				bail!("Command data is malformed; expected `/settings new_account_age set` to have a number of days");
+			};
+			let Ok(days) = i32::try_from((*days).clamp(1, MAX_NEW_ACCOUNT_AGE_DAYS)) else {
+				bail!("New account age out of range: {}", days);
+			};
+			diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(db_guild_id))
+				.set(guilds::new_account_age_days.eq(days))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+			let content = format!(
+				"Accounts younger than {} days will be flagged in the member alert channel when they join.",
+				days
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"unset" => {
+			diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(db_guild_id))
+				.set(guilds::new_account_age_days.eq(None::<i32>))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+			let content = String::from("New accounts will no longer be flagged when they join.");
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		_ => bail!(
+			"Unknown settings new_account_age subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
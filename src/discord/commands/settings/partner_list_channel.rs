@@ -0,0 +1,330 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::partner_list::{active_partnerships, partner_list_embed};
+use crate::discord::utils::permissions::channel_permissions;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail, ensure};
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::channel::ChannelType;
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, GuildMarker};
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::command::{ChannelBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+pub fn subcommand_definition() -> CommandOption {
+	let channel_option = ChannelBuilder::new(
+		"partner_list_channel",
+		"The channel in which the bot keeps a list of active partners",
+	)
+	.channel_types([ChannelType::GuildText])
+	.required(true)
+	.build();
+
+	let get = SubCommandBuilder::new("get", "Gets the partner list channel");
+	let set = SubCommandBuilder::new("set", "Sets the partner list channel").option(channel_option);
+	let unset = SubCommandBuilder::new("unset", "Removes the partner list channel");
+
+	SubCommandGroupBuilder::new(
+		"partner_list_channel",
+		"Manages the channel in which the bot keeps a list of active partners",
+	)
+	.subcommands([get, set, unset])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: QueryResult<Option<Guild>> = guilds::table.find(db_guild_id).first(&mut db_connection).optional();
+
+	let interaction_client = http_client.interaction(application_id);
+
+	let guild = match guild {
+		Ok(Some(guild)) => guild,
+		Ok(None) => {
+			let response = InteractionResponseDataBuilder::new()
+				.content(NOT_SET_UP_FOR_GUILD)
+				.build();
+			let response = InteractionResponse {
+				kind: InteractionResponseType::ChannelMessageWithSource,
+				data: Some(response),
+			};
+			interaction_client
+				.create_response(interaction.id, &interaction.token, &response)
+				.await
+				.into_diagnostic()?;
+			return Ok(());
+		}
+		Err(error) => {
+			tracing::error!(source = ?error, "Failed to retrieve guild for getting or updating partner list channel");
+			let response = InteractionResponseDataBuilder::new()
+				.content("An internal error occurred handling this command.")
+				.flags(MessageFlags::EPHEMERAL)
+				.build();
+			let response = InteractionResponse {
+				kind: InteractionResponseType::ChannelMessageWithSource,
+				data: Some(response),
+			};
+			interaction_client
+				.create_response(interaction.id, &interaction.token, &response)
+				.await
+				.into_diagnostic()?;
+			return Ok(());
+		}
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings partner_list_channel` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings partner_list_channel to have a subcommand");
+	};
+	match value.name.as_str() {
+		"get" => get_list_channel(interaction, &guild, http_client, application_id).await,
+		"set" => {
+			set_list_channel(
+				interaction,
+				guild_id,
+				&guild,
+				&value.value,
+				http_client,
+				application_id,
+				&mut db_connection,
+			)
+			.await
+		}
+		"unset" => unset_list_channel(interaction, &guild, http_client, application_id, &mut db_connection).await,
+		_ => bail!(
+			"Unknown settings partner_list_channel subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
+
+async fn get_list_channel(
+	interaction: &InteractionCreate,
+	guild: &Guild,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+) -> miette::Result<()> {
+	let channel = guild.get_partner_list_channel();
+
+	let interaction_client = http_client.interaction(application_id);
+	let response_content = match channel {
+		Some(channel) => format!("The partner list channel is set up as {}.", channel.mention()),
+		None => String::from("No partner list channel is set."),
+	};
+	let response = InteractionResponseDataBuilder::new()
+		.content(response_content)
+		.allowed_mentions(AllowedMentions::default())
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+async fn set_list_channel(
+	interaction: &InteractionCreate,
+	guild_id: Id<GuildMarker>,
+	guild: &Guild,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let CommandOptionValue::SubCommand(values) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings partner_list_channel set` to get subcommand data");
+	};
+	let Some(list_channel) = values.first() else {
+		bail!(
+			"Command data is malformed; expected `/settings partner_list_channel set` to have required option `partner_list_channel`"
+		);
+	};
+	ensure!(
+		list_channel.name.as_str() == "partner_list_channel",
+		"The only option for `/settings partner_list_channel set` should be `partner_list_channel`"
+	);
+
+	let CommandOptionValue::Channel(list_channel) = list_channel.value else {
+		bail!(
+			"Command data is malformed; expected `partner_list_channel` option of `/settings partner_list_channel set` to be a channel"
+		);
+	};
+
+	let permissions_in_channel = channel_permissions(guild_id, list_channel, http_client).await?;
+
+	let interaction_client = http_client.interaction(application_id);
+	if !permissions_in_channel.contains(Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS) {
+		let response_content = format!(
+			"The channel {} doesn't have the necessary permissions (Send Messages, Embed Links) for me to post to it.",
+			list_channel.mention()
+		);
+		let response = InteractionResponseDataBuilder::new().content(response_content).build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	}
+
+	if let (Some(original_channel), Some(original_message)) =
+		(guild.get_partner_list_channel(), guild.get_partner_list_message())
+	{
+		http_client
+			.delete_message(original_channel, original_message)
+			.reason("Partner list channel was changed")
+			.await
+			.into_diagnostic()?;
+	}
+
+	let partners = active_partnerships(guild, db_connection).into_diagnostic()?;
+	let new_message = http_client
+		.create_message(list_channel)
+		.embeds(&[partner_list_embed(&partners)])
+		.await
+		.into_diagnostic()?;
+	let new_message = new_message.model().await.into_diagnostic()?;
+	let db_message_id = database_id_from_discord_id(new_message.id.get());
+
+	let db_channel_id = database_id_from_discord_id(list_channel.get());
+
+	let db_result = diesel::update(guilds::table)
+		.filter(guilds::guild_id.eq(guild.guild_id))
+		.set((
+			guilds::partner_list_channel.eq(Some(db_channel_id)),
+			guilds::partner_list_message.eq(Some(db_message_id)),
+		))
+		.execute(db_connection);
+	match db_result {
+		Ok(_) => {
+			let response = InteractionResponseDataBuilder::new()
+				.content(format!(
+					"Updated the partner list channel to {}.",
+					list_channel.mention()
+				))
+				.allowed_mentions(AllowedMentions::default())
+				.build();
+			let response = InteractionResponse {
+				kind: InteractionResponseType::ChannelMessageWithSource,
+				data: Some(response),
+			};
+			interaction_client
+				.create_response(interaction.id, &interaction.token, &response)
+				.await
+				.into_diagnostic()?;
+		}
+		Err(error) => {
+			tracing::error!(source = ?error, "Failed to update the partner list channel for a server");
+			let response = InteractionResponseDataBuilder::new()
+				.content("An internal error caused the update to fail.")
+				.flags(MessageFlags::EPHEMERAL)
+				.build();
+			let response = InteractionResponse {
+				kind: InteractionResponseType::ChannelMessageWithSource,
+				data: Some(response),
+			};
+			interaction_client
+				.create_response(interaction.id, &interaction.token, &response)
+				.await
+				.into_diagnostic()?;
+		}
+	}
+
+	Ok(())
+}
+
+async fn unset_list_channel(
+	interaction: &InteractionCreate,
+	guild: &Guild,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let original_channel = guild.get_partner_list_channel();
+	let original_message = guild.get_partner_list_message();
+	let interaction_client = http_client.interaction(application_id);
+	let response = match original_channel {
+		Some(original_channel) => {
+			let no_id: Option<i64> = None;
+			let db_result = diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(guild.guild_id))
+				.set((
+					guilds::partner_list_channel.eq(no_id),
+					guilds::partner_list_message.eq(no_id),
+				))
+				.execute(db_connection);
+			match db_result {
+				Ok(_) => {
+					if let Some(original_message) = original_message {
+						http_client
+							.delete_message(original_channel, original_message)
+							.reason("Removing partner list message")
+							.await
+							.into_diagnostic()?;
+					}
+					InteractionResponseDataBuilder::new()
+						.content("The partner list channel has been unset, and the partner list message was removed.")
+						.build()
+				}
+				Err(error) => {
+					tracing::error!(source = ?error, "Failed to remove the partner list channel for a server");
+					InteractionResponseDataBuilder::new()
+						.content("An internal error occurred, so the partner list channel couldn't be unset.")
+						.flags(MessageFlags::EPHEMERAL)
+						.build()
+				}
+			}
+		}
+		None => InteractionResponseDataBuilder::new()
+			.content("Your server didn't have this channel set up, so the setting value remains unset.")
+			.flags(MessageFlags::EPHEMERAL)
+			.build(),
+	};
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
@@ -0,0 +1,266 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, ReasonPolicy, ReasonPolicyExemptRole, database_id_from_discord_id};
+use crate::schema::{guilds, reason_policies, reason_policy_exempt_roles};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::upsert::excluded;
+use miette::{IntoDiagnostic, bail};
+use regex::Regex;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{
+	BooleanBuilder, IntegerBuilder, RoleBuilder, StringBuilder, SubCommandBuilder, SubCommandGroupBuilder,
+};
+
+pub fn subcommand_definition() -> CommandOption {
+	let minimum_length_option = IntegerBuilder::new("minimum_length", "The minimum number of characters in a reason")
+		.min_value(1)
+		.max_value(512)
+		.build();
+	let required_pattern_option = StringBuilder::new(
+		"required_pattern",
+		"A regular expression reasons must match (e.g. a rule reference like `R\\d+`)",
+	)
+	.max_length(200)
+	.build();
+	let exempt_bots_option = BooleanBuilder::new("exempt_bots", "Whether actions taken by bots are exempt").build();
+	let add_role_option = RoleBuilder::new("role", "The role to exempt from the reason policy")
+		.required(true)
+		.build();
+	let remove_role_option = RoleBuilder::new("role", "The role that should no longer be exempt")
+		.required(true)
+		.build();
+
+	let get = SubCommandBuilder::new("get", "Gets the reason policy");
+	let set = SubCommandBuilder::new("set", "Updates parts of the reason policy")
+		.option(minimum_length_option)
+		.option(required_pattern_option)
+		.option(exempt_bots_option);
+	let unset_pattern = SubCommandBuilder::new("unset_pattern", "Removes the required pattern from the reason policy");
+	let add_exempt_role =
+		SubCommandBuilder::new("add_exempt_role", "Exempts a role from the reason policy").option(add_role_option);
+	let remove_exempt_role = SubCommandBuilder::new("remove_exempt_role", "Removes a role's reason policy exemption")
+		.option(remove_role_option);
+	let reset = SubCommandBuilder::new(
+		"reset",
+		"Resets the reason policy to only require that a reason is given",
+	);
+
+	SubCommandGroupBuilder::new(
+		"reason_policy",
+		"Manages what moderation action reasons must contain to avoid a complaint",
+	)
+	.subcommands([get, set, unset_pattern, add_exempt_role, remove_exempt_role, reset])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	if guild.is_none() {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	}
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings reason_policy` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings reason_policy` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings reason_policy {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	let policy: Option<ReasonPolicy> = reason_policies::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let mut policy = policy.unwrap_or(ReasonPolicy {
+		guild: db_guild_id,
+		minimum_length: 1,
+		required_pattern: None,
+		exempt_bots: false,
+	});
+
+	match value.name.as_str() {
+		"get" => {
+			let exempt_roles: Vec<ReasonPolicyExemptRole> = reason_policy_exempt_roles::table
+				.filter(reason_policy_exempt_roles::guild.eq(db_guild_id))
+				.load(&mut db_connection)
+				.into_diagnostic()?;
+			let exempt_roles: Vec<String> = exempt_roles
+				.iter()
+				.map(|exempt_role| exempt_role.get_role().mention().to_string())
+				.collect();
+			let pattern = match &policy.required_pattern {
+				Some(pattern) => format!("`{}`", pattern),
+				None => String::from("none"),
+			};
+			let exempt_roles = if exempt_roles.is_empty() {
+				String::from("none")
+			} else {
+				exempt_roles.join(", ")
+			};
+			let content = format!(
+				"Reason policy:\n- Minimum length: {}\n- Required pattern: {}\n- Bots exempt: {}\n- Exempt roles: {}",
+				policy.minimum_length,
+				pattern,
+				if policy.exempt_bots { "yes" } else { "no" },
+				exempt_roles
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"set" => {
+			for option in options.iter() {
+				match (option.name.as_str(), &option.value) {
+					("minimum_length", CommandOptionValue::Integer(length)) => {
+						let Ok(length) = i32::try_from(*length) else {
+							bail!("Minimum length out of range: {}", length);
+						};
+						policy.minimum_length = length;
+					}
+					("required_pattern", CommandOptionValue::String(pattern)) => {
+						if let Err(error) = Regex::new(pattern) {
+							let content = format!("The required pattern isn't a valid regular expression: {}", error);
+							return respond_with_message(interaction, http_client, application_id, content, true).await;
+						}
+						policy.required_pattern = Some(pattern.clone());
+					}
+					("exempt_bots", CommandOptionValue::Boolean(exempt_bots)) => policy.exempt_bots = *exempt_bots,
+					_ => bail!("Unexpected option for `/settings reason_policy set`: {:?}", option),
+				}
+			}
+			save_policy(&mut db_connection, &policy)?;
+			respond_with_message(
+				interaction,
+				http_client,
+				application_id,
+				String::from("Updated the reason policy."),
+				false,
+			)
+			.await
+		}
+		"unset_pattern" => {
+			policy.required_pattern = None;
+			save_policy(&mut db_connection, &policy)?;
+			respond_with_message(
+				interaction,
+				http_client,
+				application_id,
+				String::from("Removed the required pattern from the reason policy."),
+				false,
+			)
+			.await
+		}
+		"add_exempt_role" | "remove_exempt_role" => {
+			let Some(CommandOptionValue::Role(role)) = options.first().map(|option| &option.value) else {
+				bail!(
+					"Command data is malformed; expected `/settings reason_policy {}` to have a role",
+					value.name
+				);
+			};
+			let exempt_role = ReasonPolicyExemptRole {
+				guild: db_guild_id,
+				role: database_id_from_discord_id(role.get()),
+			};
+			let content = if value.name == "add_exempt_role" {
+				diesel::insert_into(reason_policy_exempt_roles::table)
+					.values(&exempt_role)
+					.on_conflict_do_nothing()
+					.execute(&mut db_connection)
+					.into_diagnostic()?;
+				format!("{} is now exempt from the reason policy.", role.mention())
+			} else {
+				diesel::delete(reason_policy_exempt_roles::table)
+					.filter(
+						reason_policy_exempt_roles::guild
+							.eq(exempt_role.guild)
+							.and(reason_policy_exempt_roles::role.eq(exempt_role.role)),
+					)
+					.execute(&mut db_connection)
+					.into_diagnostic()?;
+				format!("{} is no longer exempt from the reason policy.", role.mention())
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"reset" => {
+			db_connection
+				.transaction(|db_connection| {
+					diesel::delete(reason_policy_exempt_roles::table)
+						.filter(reason_policy_exempt_roles::guild.eq(db_guild_id))
+						.execute(db_connection)?;
+					diesel::delete(reason_policies::table)
+						.filter(reason_policies::guild.eq(db_guild_id))
+						.execute(db_connection)
+				})
+				.into_diagnostic()?;
+			respond_with_message(
+				interaction,
+				http_client,
+				application_id,
+				String::from("Reset the reason policy. Reasons now only need to be provided."),
+				false,
+			)
+			.await
+		}
+		_ => bail!(
+			"Unknown settings reason_policy subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
+
+fn save_policy(db_connection: &mut PgConnection, policy: &ReasonPolicy) -> miette::Result<()> {
+	diesel::insert_into(reason_policies::table)
+		.values(policy)
+		.on_conflict(reason_policies::guild)
+		.do_update()
+		.set((
+			reason_policies::minimum_length.eq(excluded(reason_policies::minimum_length)),
+			reason_policies::required_pattern.eq(excluded(reason_policies::required_pattern)),
+			reason_policies::exempt_bots.eq(excluded(reason_policies::exempt_bots)),
+		))
+		.execute(db_connection)
+		.into_diagnostic()?;
+	Ok(())
+}
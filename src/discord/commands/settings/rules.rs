@@ -0,0 +1,243 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::rules::{MAX_RULE_DESCRIPTION_LENGTH, MAX_RULE_TITLE_LENGTH};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, ServerRule, database_id_from_discord_id};
+use crate::schema::{guilds, server_rules};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{IntegerBuilder, StringBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+pub fn subcommand_definition() -> CommandOption {
+	let number_option = || {
+		IntegerBuilder::new("number", "The rule number")
+			.min_value(1)
+			.required(true)
+			.build()
+	};
+	let title_option = |required: bool| {
+		StringBuilder::new("title", "A short title for the rule")
+			.max_length(MAX_RULE_TITLE_LENGTH)
+			.required(required)
+			.build()
+	};
+	let description_option = || {
+		StringBuilder::new("description", "The full text of the rule")
+			.max_length(MAX_RULE_DESCRIPTION_LENGTH)
+			.build()
+	};
+
+	let list = SubCommandBuilder::new("list", "Lists the rules for this server");
+	let add = SubCommandBuilder::new("add", "Adds a rule")
+		.option(number_option())
+		.option(title_option(true))
+		.option(description_option());
+	let edit = SubCommandBuilder::new("edit", "Changes the title or text of a rule")
+		.option(number_option())
+		.option(title_option(false))
+		.option(description_option());
+	let remove = SubCommandBuilder::new("remove", "Removes a rule").option(number_option());
+
+	SubCommandGroupBuilder::new(
+		"rules",
+		"Manages the server rules that moderation actions can reference",
+	)
+	.subcommands([list, add, edit, remove])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	if guild.is_none() {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	}
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings rules` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings rules` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings rules {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	let mut rule_number: Option<i64> = None;
+	let mut title: Option<String> = None;
+	let mut description: Option<String> = None;
+	for option in options.iter() {
+		match (option.name.as_str(), &option.value) {
+			("number", CommandOptionValue::Integer(value)) => rule_number = Some(*value),
+			("title", CommandOptionValue::String(value)) => title = Some(value.trim().to_string()),
+			("description", CommandOptionValue::String(value)) => description = Some(value.trim().to_string()),
+			_ => (),
+		}
+	}
+	let title = title.filter(|title| !title.is_empty());
+	let rule_number = rule_number.map(i32::try_from).transpose().into_diagnostic()?;
+
+	if value.name == "list" {
+		let rules: Vec<ServerRule> = server_rules::table
+			.filter(server_rules::guild.eq(db_guild_id).and(server_rules::active.eq(true)))
+			.order(server_rules::rule_number.asc())
+			.load(&mut db_connection)
+			.into_diagnostic()?;
+		let content = if rules.is_empty() {
+			String::from("There are no rules set up for this server.")
+		} else {
+			let lines: Vec<String> = rules
+				.iter()
+				.map(|rule| format!("{}. **{}**", rule.rule_number, rule.title))
+				.collect();
+			format!("Server rules:\n{}", lines.join("\n"))
+		};
+		return respond_with_message(interaction, http_client, application_id, content, false).await;
+	}
+
+	let Some(rule_number) = rule_number else {
+		bail!(
+			"Command data is malformed; expected `/settings rules {}` to have a rule number",
+			value.name
+		);
+	};
+	let existing_rule: Option<ServerRule> = server_rules::table
+		.filter(
+			server_rules::guild
+				.eq(db_guild_id)
+				.and(server_rules::rule_number.eq(rule_number))
+				.and(server_rules::active.eq(true)),
+		)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	match (value.name.as_str(), existing_rule) {
+		("add", Some(_)) => {
+			respond_with_message(
+				interaction,
+				http_client,
+				application_id,
+				format!("There's already a rule {}.", rule_number),
+				true,
+			)
+			.await
+		}
+		("add", None) => {
+			let Some(title) = title else {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					String::from("Rules need a title."),
+					true,
+				)
+				.await;
+			};
+			let new_rule = ServerRule {
+				id: cuid2::create_id(),
+				guild: db_guild_id,
+				rule_number,
+				title,
+				description: description.unwrap_or_default(),
+				active: true,
+			};
+			diesel::insert_into(server_rules::table)
+				.values(&new_rule)
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			let content = format!("Added rule {}: {}", new_rule.rule_number, new_rule.title);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		("edit", Some(rule)) => {
+			if title.is_none() && description.is_none() {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					String::from("Nothing was changed."),
+					true,
+				)
+				.await;
+			}
+			let title = title.unwrap_or(rule.title);
+			let description = description.unwrap_or(rule.description);
+			diesel::update(server_rules::table)
+				.filter(server_rules::id.eq(&rule.id))
+				.set((
+					server_rules::title.eq(&title),
+					server_rules::description.eq(&description),
+				))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			let content = format!("Updated rule {}: {}", rule.rule_number, title);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		("remove", Some(rule)) => {
+			// Rules are deactivated rather than deleted so actions that referenced them still can
+			diesel::update(server_rules::table)
+				.filter(server_rules::id.eq(&rule.id))
+				.set(server_rules::active.eq(false))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			let content = format!("Removed rule {}.", rule.rule_number);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		("edit", None) | ("remove", None) => {
+			respond_with_message(
+				interaction,
+				http_client,
+				application_id,
+				format!("There's no rule {}.", rule_number),
+				true,
+			)
+			.await
+		}
+		_ => bail!(
+			"Unknown settings rules subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
@@ -0,0 +1,137 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::{guilds, partnerships};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{BooleanBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+pub fn subcommand_definition() -> CommandOption {
+	let enabled_option = BooleanBuilder::new("enabled", "Whether partnerships are shared with the partner network")
+		.required(true)
+		.build();
+
+	let get = SubCommandBuilder::new("get", "Gets whether TCN partner integration is enabled");
+	let set = SubCommandBuilder::new("set", "Enables or disables TCN partner integration").option(enabled_option);
+
+	SubCommandGroupBuilder::new(
+		"tcn_partner_integration",
+		"Manages whether partnerships and bans are shared with the partner network",
+	)
+	.subcommands([get, set])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!(
+			"Command data is malformed; expected `/settings tcn_partner_integration` to get a subcommand group value"
+		);
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings tcn_partner_integration` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings tcn_partner_integration {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	let not_configured_note = if config.tcn.is_none() {
+		"\nThe bot isn't connected to a partner network, so nothing will be shared until its operator configures one."
+	} else {
+		""
+	};
+
+	match value.name.as_str() {
+		"get" => {
+			let content = if guild.tcn_partner_integration {
+				format!("TCN partner integration is enabled.{}", not_configured_note)
+			} else {
+				String::from("TCN partner integration is disabled.")
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"set" => {
+			let Some(CommandOptionValue::Boolean(enabled)) = options.first().map(|option| &option.value) else {
+				bail!("Command data is malformed; expected `/settings tcn_partner_integration set` to have `enabled`");
+			};
+			let enabled = *enabled;
+			db_connection
+				.transaction(|db_connection| {
+					diesel::update(guilds::table)
+						.filter(guilds::guild_id.eq(db_guild_id))
+						.set(guilds::tcn_partner_integration.eq(enabled))
+						.execute(db_connection)?;
+					if enabled && !guild.tcn_partner_integration {
+						// Partnerships made while the integration was off haven't been shared yet
+						diesel::update(partnerships::table)
+							.filter(partnerships::guild.eq(db_guild_id).and(partnerships::active.eq(true)))
+							.set(partnerships::tcn_push_pending.eq(true))
+							.execute(db_connection)?;
+					}
+					Ok::<(), diesel::result::Error>(())
+				})
+				.into_diagnostic()?;
+			let content = if enabled {
+				format!(
+					"TCN partner integration is enabled. Partnership approvals and removals will be shared with the partner network.{}",
+					not_configured_note
+				)
+			} else {
+				String::from("TCN partner integration is disabled. Partnership changes will no longer be shared.")
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		_ => bail!(
+			"Unknown settings tcn_partner_integration subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
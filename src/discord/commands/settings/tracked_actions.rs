@@ -0,0 +1,175 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, TrackedActionType, UntrackedActionType, database_id_from_discord_id};
+use crate::schema::{guilds, untracked_action_types};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{StringBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+fn action_type_option(description: &str) -> CommandOption {
+	StringBuilder::new("action_type", description)
+		.choices([
+			("Role changes", "role_change"),
+			("Nickname changes", "nickname_change"),
+			("Message deletions", "message_delete"),
+			("Voice disconnects", "voice_disconnect"),
+			("Voice moves", "voice_move"),
+		])
+		.required(true)
+		.build()
+}
+
+fn action_type_from_option_value(value: &str) -> Option<TrackedActionType> {
+	match value {
+		"role_change" => Some(TrackedActionType::RoleChange),
+		"nickname_change" => Some(TrackedActionType::NicknameChange),
+		"message_delete" => Some(TrackedActionType::MessageDelete),
+		"voice_disconnect" => Some(TrackedActionType::VoiceDisconnect),
+		"voice_move" => Some(TrackedActionType::VoiceMove),
+		_ => None,
+	}
+}
+
+pub fn subcommand_definition() -> CommandOption {
+	let list = SubCommandBuilder::new("list", "Lists which optional moderation actions are recorded");
+	let enable = SubCommandBuilder::new("enable", "Starts recording a type of moderation action")
+		.option(action_type_option("The type of action to record"));
+	let disable = SubCommandBuilder::new("disable", "Stops recording a type of moderation action")
+		.option(action_type_option("The type of action to stop recording"));
+
+	SubCommandGroupBuilder::new(
+		"tracked_actions",
+		"Manages which optional moderation actions are recorded and logged",
+	)
+	.subcommands([list, enable, disable])
+	.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	if guild.is_none() {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	}
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings tracked_actions` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings tracked_actions` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings tracked_actions {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"list" => {
+			let untracked: Vec<UntrackedActionType> = untracked_action_types::table
+				.filter(untracked_action_types::guild.eq(db_guild_id))
+				.load(&mut db_connection)
+				.into_diagnostic()?;
+			let lines: Vec<String> = TrackedActionType::ALL
+				.iter()
+				.map(|action_type| {
+					let is_tracked = !untracked
+						.iter()
+						.any(|untracked_type| untracked_type.action_type == *action_type);
+					format!(
+						"- {}: {}",
+						action_type,
+						if is_tracked { "recorded" } else { "not recorded" }
+					)
+				})
+				.collect();
+			let content = format!(
+				"Optional moderation actions (bans, kicks, timeouts, and automod actions are always recorded):\n{}",
+				lines.join("\n")
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"enable" | "disable" => {
+			let Some(CommandOptionValue::String(action_type_value)) = options.first().map(|option| &option.value)
+			else {
+				bail!(
+					"Command data is malformed; expected `/settings tracked_actions {}` to have an action type",
+					value.name
+				);
+			};
+			let Some(action_type) = action_type_from_option_value(action_type_value) else {
+				bail!(
+					"Unknown action type for `/settings tracked_actions {}`: {}",
+					value.name,
+					action_type_value
+				);
+			};
+
+			let content = if value.name == "enable" {
+				diesel::delete(untracked_action_types::table)
+					.filter(
+						untracked_action_types::guild
+							.eq(db_guild_id)
+							.and(untracked_action_types::action_type.eq(action_type)),
+					)
+					.execute(&mut db_connection)
+					.into_diagnostic()?;
+				format!("{} will now be recorded.", action_type)
+			} else {
+				let untracked_action_type = UntrackedActionType {
+					guild: db_guild_id,
+					action_type,
+				};
+				diesel::insert_into(untracked_action_types::table)
+					.values(&untracked_action_type)
+					.on_conflict_do_nothing()
+					.execute(&mut db_connection)
+					.into_diagnostic()?;
+				format!("{} will no longer be recorded.", action_type)
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		_ => bail!(
+			"Unknown settings tracked_actions subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
@@ -0,0 +1,301 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{BanSharePolicy, Guild, TrustedGuild, database_id_from_discord_id};
+use crate::schema::{guilds, trusted_guilds};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, GuildMarker};
+use twilight_util::builder::command::{StringBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+fn policy_from_option_value(value: &str) -> Option<BanSharePolicy> {
+	match value {
+		"alert" => Some(BanSharePolicy::Alert),
+		"ban" => Some(BanSharePolicy::Ban),
+		_ => None,
+	}
+}
+
+fn describe_policy(policy: BanSharePolicy) -> &'static str {
+	match policy {
+		BanSharePolicy::Alert => "alert staff in the mod log channel",
+		BanSharePolicy::Ban => "ban and unban automatically",
+	}
+}
+
+/// Gets a guild's name for display, falling back to its ID if Discord won't give it to us
+async fn guild_display_name(http_client: &Client, guild_id: Id<GuildMarker>) -> String {
+	let guild_response = match http_client.guild(guild_id).await {
+		Ok(response) => response,
+		Err(_) => return guild_id.to_string(),
+	};
+	match guild_response.model().await {
+		Ok(guild) => format!("{} ({})", guild.name, guild_id),
+		Err(_) => guild_id.to_string(),
+	}
+}
+
+pub fn subcommand_definition() -> CommandOption {
+	let add_guild_option = StringBuilder::new("guild_id", "The ID of the server to trust")
+		.required(true)
+		.build();
+	let policy_option = StringBuilder::new("policy", "What to do when the server bans or unbans someone")
+		.choices([
+			("Alert staff in the mod log channel", "alert"),
+			("Ban and unban automatically", "ban"),
+		])
+		.required(true)
+		.build();
+	let remove_guild_option = StringBuilder::new("guild_id", "The ID of the server to stop trusting")
+		.required(true)
+		.build();
+
+	let list = SubCommandBuilder::new("list", "Lists the servers this server trusts");
+	let add = SubCommandBuilder::new("add", "Trusts a server's bans, or changes how they're handled")
+		.option(add_guild_option)
+		.option(policy_option);
+	let remove = SubCommandBuilder::new("remove", "Stops trusting a server's bans").option(remove_guild_option);
+
+	SubCommandGroupBuilder::new("trusted_guilds", "Manages the servers that share bans with this server")
+		.subcommands([list, add, remove])
+		.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	if guild.is_none() {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	}
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings trusted_guilds` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings trusted_guilds` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings trusted_guilds {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"list" => {
+			let trusts: Vec<TrustedGuild> = trusted_guilds::table
+				.filter(trusted_guilds::guild.eq(db_guild_id))
+				.order(trusted_guilds::added_at.asc())
+				.load(&mut db_connection)
+				.into_diagnostic()?;
+			let trusted_back: Vec<i64> = trusted_guilds::table
+				.filter(trusted_guilds::trusted_guild.eq(db_guild_id))
+				.select(trusted_guilds::guild)
+				.load(&mut db_connection)
+				.into_diagnostic()?;
+
+			let content = if trusts.is_empty() {
+				String::from("This server doesn't trust any other servers.")
+			} else {
+				let mut lines: Vec<String> = Vec::with_capacity(trusts.len());
+				for trust in trusts.iter() {
+					let mutual_note = if trusted_back.contains(&trust.trusted_guild) {
+						""
+					} else {
+						" (waiting for that server to trust this one)"
+					};
+					lines.push(format!(
+						"- {}: {}{}",
+						guild_display_name(http_client, trust.get_trusted_guild()).await,
+						describe_policy(trust.policy),
+						mutual_note
+					));
+				}
+				format!("Trusted servers:\n{}", lines.join("\n"))
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"add" => {
+			let Some(author_id) = interaction.author_id() else {
+				bail!("Settings command was used without an author");
+			};
+
+			let mut trusted_guild_value: Option<&str> = None;
+			let mut policy: Option<BanSharePolicy> = None;
+			for option in options.iter() {
+				match (option.name.as_str(), &option.value) {
+					("guild_id", CommandOptionValue::String(value)) => trusted_guild_value = Some(value.as_str()),
+					("policy", CommandOptionValue::String(value)) => policy = policy_from_option_value(value),
+					_ => (),
+				}
+			}
+			let (Some(trusted_guild_value), Some(policy)) = (trusted_guild_value, policy) else {
+				bail!(
+					"Command data is malformed; expected `/settings trusted_guilds add` to have a guild ID and policy"
+				);
+			};
+
+			let trusted_guild_id: Option<Id<GuildMarker>> = trusted_guild_value.trim().parse().ok();
+			let Some(trusted_guild_id) = trusted_guild_id else {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					format!("`{}` isn't a valid server ID.", trusted_guild_value),
+					true,
+				)
+				.await;
+			};
+			if trusted_guild_id == guild_id {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					String::from("A server can't trust itself."),
+					true,
+				)
+				.await;
+			}
+
+			let db_trusted_guild_id = database_id_from_discord_id(trusted_guild_id.get());
+			let trusted_guild_count: i64 = guilds::table
+				.filter(guilds::guild_id.eq(db_trusted_guild_id))
+				.count()
+				.get_result(&mut db_connection)
+				.into_diagnostic()?;
+			if trusted_guild_count == 0 {
+				return respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					String::from("That server isn't set up with this bot, so it can't share bans."),
+					true,
+				)
+				.await;
+			}
+
+			let new_trust = TrustedGuild {
+				guild: db_guild_id,
+				trusted_guild: db_trusted_guild_id,
+				policy,
+				added_by: database_id_from_discord_id(author_id.get()),
+				added_at: Utc::now(),
+			};
+			diesel::insert_into(trusted_guilds::table)
+				.values(&new_trust)
+				.on_conflict((trusted_guilds::guild, trusted_guilds::trusted_guild))
+				.do_update()
+				.set(trusted_guilds::policy.eq(policy))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+
+			let trusted_back_count: i64 = trusted_guilds::table
+				.filter(
+					trusted_guilds::guild
+						.eq(db_trusted_guild_id)
+						.and(trusted_guilds::trusted_guild.eq(db_guild_id)),
+				)
+				.count()
+				.get_result(&mut db_connection)
+				.into_diagnostic()?;
+			let sharing_note = if trusted_back_count > 0 {
+				"Bans and unbans are now shared between both servers."
+			} else {
+				"Bans will be shared once that server's admins trust this server too."
+			};
+
+			let content = format!(
+				"This server now trusts {}. When it bans or unbans someone, this server will {}. {}",
+				guild_display_name(http_client, trusted_guild_id).await,
+				describe_policy(policy),
+				sharing_note
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"remove" => {
+			let Some(CommandOptionValue::String(trusted_guild_value)) = options.first().map(|option| &option.value)
+			else {
+				bail!("Command data is malformed; expected `/settings trusted_guilds remove` to have a guild ID");
+			};
+
+			let trusted_guild_id: Option<Id<GuildMarker>> = trusted_guild_value.trim().parse().ok();
+			let deleted_count = match trusted_guild_id {
+				Some(trusted_guild_id) => diesel::delete(trusted_guilds::table)
+					.filter(
+						trusted_guilds::guild
+							.eq(db_guild_id)
+							.and(trusted_guilds::trusted_guild.eq(database_id_from_discord_id(trusted_guild_id.get()))),
+					)
+					.execute(&mut db_connection)
+					.into_diagnostic()?,
+				None => 0,
+			};
+
+			if deleted_count == 0 {
+				respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					format!(
+						"This server doesn't trust a server with the ID `{}`.",
+						trusted_guild_value
+					),
+					true,
+				)
+				.await
+			} else {
+				respond_with_message(
+					interaction,
+					http_client,
+					application_id,
+					format!(
+						"This server no longer trusts `{}`, and bans will no longer be shared between them.",
+						trusted_guild_value
+					),
+					false,
+				)
+				.await
+			}
+		}
+		_ => bail!(
+			"Unknown settings trusted_guilds subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
@@ -0,0 +1,125 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::{IntegerBuilder, SubCommandBuilder, SubCommandGroupBuilder};
+
+/// The longest time warnings can be set to last for, in days
+const MAX_WARNING_EXPIRY_DAYS: i64 = 3650;
+
+pub fn subcommand_definition() -> CommandOption {
+	let days_option = IntegerBuilder::new("days", "How many days warnings remain active for")
+		.min_value(1)
+		.max_value(MAX_WARNING_EXPIRY_DAYS)
+		.required(true)
+		.build();
+
+	let get = SubCommandBuilder::new("get", "Gets how long warnings remain active for");
+	let set = SubCommandBuilder::new("set", "Sets how long new warnings remain active for").option(days_option);
+	let unset = SubCommandBuilder::new("unset", "Makes new warnings never expire");
+
+	SubCommandGroupBuilder::new("warning_expiry", "Manages how long warnings remain active for")
+		.subcommands([get, set, unset])
+		.build()
+}
+
+pub async fn handle_subcommand(
+	interaction: &InteractionCreate,
+	subcommand_value: &CommandOptionValue,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Settings command was used outside of a guild");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let Some(guild) = guild else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from(NOT_SET_UP_FOR_GUILD),
+			true,
+		)
+		.await;
+	};
+
+	let CommandOptionValue::SubCommandGroup(value_data) = subcommand_value else {
+		bail!("Command data is malformed; expected `/settings warning_expiry` to get a subcommand group value");
+	};
+	let Some(value) = value_data.first() else {
+		bail!("Command data is malformed; expected `/settings warning_expiry` to have a subcommand");
+	};
+	let CommandOptionValue::SubCommand(options) = &value.value else {
+		bail!(
+			"Command data is malformed; expected `/settings warning_expiry {}` to get subcommand data",
+			value.name
+		);
+	};
+
+	match value.name.as_str() {
+		"get" => {
+			let content = match guild.warning_expiry_days {
+				Some(days) => format!("New warnings remain active for {} days.", days),
+				None => String::from("Warnings don't expire."),
+			};
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"set" => {
+			let Some(CommandOptionValue::Integer(days)) = options.first().map(|option| &option.value) else {
+				bail!("Command data is malformed; expected `/settings warning_expiry set` to have a number of days");
+			};
+			let Ok(days) = i32::try_from((*days).clamp(1, MAX_WARNING_EXPIRY_DAYS)) else {
+				bail!("Warning expiry out of range: {}", days);
+			};
+			diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(db_guild_id))
+				.set(guilds::warning_expiry_days.eq(days))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+			let content = format!(
+				"New warnings will remain active for {} days. Existing warnings keep their current expiry.",
+				days
+			);
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		"unset" => {
+			diesel::update(guilds::table)
+				.filter(guilds::guild_id.eq(db_guild_id))
+				.set(guilds::warning_expiry_days.eq(None::<i32>))
+				.execute(&mut db_connection)
+				.into_diagnostic()?;
+			let content = String::from("New warnings won't expire. Existing warnings keep their current expiry.");
+			respond_with_message(interaction, http_client, application_id, content, false).await
+		}
+		_ => bail!(
+			"Unknown settings warning_expiry subcommand encountered: {}\n{:?}",
+			value.name,
+			subcommand_value
+		),
+	}
+}
@@ -0,0 +1,221 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_TIMEOUT, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, NewActionRecord, audit_log_reason, defer_moderation_response, dm_result_text,
+	dm_user_about_action, dm_user_option, evidence_option, moderation_refusal, perform_recorded_action,
+	post_action_to_mod_log, reason_option, rule_number_option, send_moderation_response, user_option,
+};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, ModerationActionType, TimeoutAction, database_id_from_discord_id};
+use crate::schema::guilds;
+use chrono::{TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::{CommandData, CommandOptionValue};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_model::util::datetime::Timestamp;
+use twilight_util::builder::command::{CommandBuilder, IntegerBuilder};
+
+pub fn command_definition() -> Command {
+	let duration = IntegerBuilder::new("duration", "How long to time out the user for")
+		.choices([
+			("1 minute", 1),
+			("5 minutes", 5),
+			("10 minutes", 10),
+			("1 hour", 60),
+			("1 day", 60 * 24),
+			("1 week", 60 * 24 * 7),
+		])
+		.required(true)
+		.build();
+
+	CommandBuilder::new("timeout", "Times out a user in the server", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(user_option("The user to time out"))
+		.option(reason_option("Why the user is being timed out"))
+		.option(duration)
+		.option(evidence_option())
+		.option(rule_number_option())
+		.option(dm_user_option())
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Timeout command was used outside of a guild");
+	};
+	let (Some(moderator), Some(moderator_user)) = (interaction.member.as_ref(), interaction.author()) else {
+		bail!("Timeout command was used by a non-member");
+	};
+
+	let (options, other_options) = ModerationCommandOptions::parse("timeout", &command_data.options)?;
+	let mut duration_minutes: Option<i64> = None;
+	for option in other_options {
+		match (option.name.as_str(), &option.value) {
+			("duration", CommandOptionValue::Integer(minutes)) => duration_minutes = Some(*minutes),
+			_ => bail!("Unexpected option for `/timeout`: {:?}", option),
+		}
+	}
+	let Some(duration_minutes) = duration_minutes else {
+		bail!("Command data is malformed; `/timeout` requires a duration");
+	};
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let refusal = moderation_refusal(
+		&mut db_connection,
+		http_client,
+		guild_id,
+		moderator_user.id,
+		moderator,
+		&options,
+		"time out",
+	)
+	.await?;
+	if let Some(refusal) = refusal {
+		return send_moderation_response(interaction, http_client, application_id, &refusal).await;
+	}
+
+	let action_time = Utc::now();
+	let Some(timeout_until) = TimeDelta::try_minutes(duration_minutes).map(|duration| action_time + duration) else {
+		bail!("Timeout duration out of range: {}", duration_minutes);
+	};
+	let communication_disabled_until = Timestamp::from_secs(timeout_until.timestamp()).into_diagnostic()?;
+	let new_timeout_action = TimeoutAction {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		performing_user: database_id_from_discord_id(moderator_user.id.get()),
+		target_user: database_id_from_discord_id(options.target_user.get()),
+		action_time,
+		timeout_until: Some(timeout_until),
+		reason: options.reason.clone(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: None,
+		evidence: options.evidence.clone(),
+		via_bot_command: true,
+		escalation_rule: None,
+	};
+
+	let audit_reason = audit_log_reason(&moderator_user.name, &options.reason);
+	let timeout = http_client
+		.update_guild_member(guild_id, options.target_user)
+		.communication_disabled_until(Some(communication_disabled_until))
+		.reason(&audit_reason);
+	let timeout_result = perform_recorded_action(
+		&mut db_connection,
+		NewActionRecord::Timeout(&new_timeout_action),
+		timeout,
+	)
+	.await?;
+	let case_number = match timeout_result {
+		Ok(case_number) => case_number,
+		Err(error) => {
+			tracing::warn!(source = ?error, "Failed to time out a user through the timeout command");
+			let content = format!(
+				"I couldn't time out {}. Make sure they're in the server and I have the Timeout Members permission and a role above theirs.",
+				options.target_user.mention()
+			);
+			return send_moderation_response(interaction, http_client, application_id, &content).await;
+		}
+	};
+
+	let dm_sent = if options.dm_user {
+		Some(
+			dm_user_about_action(
+				http_client,
+				guild_id,
+				options.target_user,
+				"timed out in",
+				&options.reason,
+			)
+			.await,
+		)
+	} else {
+		None
+	};
+
+	let rules = link_rules_from_reason(
+		&mut db_connection,
+		db_guild_id,
+		ModerationActionType::Timeout,
+		&new_timeout_action.id,
+		action_time,
+		&options.reason,
+		options.rule_number,
+	)
+	.into_diagnostic()?;
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	if let Some(evidence) = &options.evidence {
+		details.push(("Evidence", evidence.clone()));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: "Timeout",
+		color: MOD_LOG_COLOR_TIMEOUT,
+		case_number: Some(case_number),
+		target_user: Some(options.target_user),
+		actor: ModLogActor::User(moderator_user.id),
+		reason: Some(&new_timeout_action.reason),
+		expires_at: Some(timeout_until),
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild,
+		ModerationActionType::Timeout,
+		&new_timeout_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	let content = format!(
+		"Timed out {}.{}",
+		options.target_user.mention(),
+		dm_result_text(dm_sent)
+	);
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
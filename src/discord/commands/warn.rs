@@ -0,0 +1,89 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, defer_moderation_response, dm_user_option, evidence_option, moderation_refusal,
+	reason_option, rule_number_option, send_moderation_response, user_option,
+};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::warnings::give_warning;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::command::{Command, CommandType};
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::interaction::application_command::CommandData;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::command::CommandBuilder;
+
+pub fn command_definition() -> Command {
+	CommandBuilder::new("warn", "Warns a user", CommandType::ChatInput)
+		.contexts([InteractionContextType::Guild])
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(user_option("The user to warn"))
+		.option(reason_option("Why the user is being warned"))
+		.option(evidence_option())
+		.option(rule_number_option())
+		.option(dm_user_option())
+		.build()
+}
+
+pub async fn handle_command(
+	interaction: &InteractionCreate,
+	command_data: &CommandData,
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Warn command was used outside of a guild");
+	};
+	let (Some(moderator), Some(moderator_id)) = (interaction.member.as_ref(), interaction.author_id()) else {
+		bail!("Warn command was used by a non-member");
+	};
+
+	let (options, other_options) = ModerationCommandOptions::parse("warn", &command_data.options)?;
+	if let Some(option) = other_options.first() {
+		bail!("Unexpected option for `/warn`: {:?}", option);
+	}
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let guild: Option<Guild> = guilds::table
+		.find(database_id_from_discord_id(guild_id.get()))
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let refusal = moderation_refusal(
+		&mut db_connection,
+		http_client,
+		guild_id,
+		moderator_id,
+		moderator,
+		&options,
+		"warn",
+	)
+	.await?;
+	if let Some(refusal) = refusal {
+		return send_moderation_response(interaction, http_client, application_id, &refusal).await;
+	}
+
+	let content = give_warning(&mut db_connection, http_client, config, &guild, moderator_id, &options).await?;
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
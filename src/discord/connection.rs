@@ -5,9 +5,13 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::commands::{command_definitions, route_command};
-use super::events::route_events;
+use super::events::{
+	catch_up_audit_logs, handle_automod_execution, handle_member_add, handle_member_ban, handle_member_remove,
+	route_events,
+};
 use super::incoming_messages::handle_message;
 use super::interactions::{route_interaction, route_modal_submit};
+use super::scheduler::run_scheduler;
 use crate::config::ConfigData;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
@@ -32,7 +36,11 @@ pub async fn run_bot(
 	config: Arc<ConfigData>,
 	http_client: Arc<Client>,
 ) -> miette::Result<()> {
-	let intents = Intents::GUILD_MODERATION | Intents::GUILD_MESSAGES | Intents::MESSAGE_CONTENT;
+	let intents = Intents::GUILD_MODERATION
+		| Intents::GUILD_MEMBERS
+		| Intents::GUILD_MESSAGES
+		| Intents::MESSAGE_CONTENT
+		| Intents::AUTO_MODERATION_EXECUTION;
 
 	let mut shard = Shard::new(ShardId::ONE, config.discord.bot_token.clone(), intents);
 
@@ -54,6 +62,12 @@ pub async fn run_bot(
 			.into_diagnostic()?;
 	}
 
+	tokio::spawn(run_scheduler(
+		Arc::clone(&http_client),
+		Arc::clone(&config),
+		db_connection_pool.clone(),
+	));
+
 	let bot_state = Arc::new(RwLock::new(TypeMap::new()));
 
 	while let Some(event) = shard.next_event(EventTypeFlags::all()).await {
@@ -69,6 +83,7 @@ pub async fn run_bot(
 		tokio::spawn(handle_event(
 			event,
 			Arc::clone(&http_client),
+			Arc::clone(&config),
 			application_id,
 			db_connection_pool.clone(),
 			Arc::clone(&bot_state),
@@ -81,11 +96,20 @@ pub async fn run_bot(
 async fn handle_event(
 	event: Event,
 	http_client: Arc<Client>,
+	config: Arc<ConfigData>,
 	application_id: Id<ApplicationMarker>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	bot_state: Arc<RwLock<TypeMap>>,
 ) {
-	let event_result = handle_event_route(event, &http_client, application_id, db_connection_pool, bot_state).await;
+	let event_result = handle_event_route(
+		event,
+		&http_client,
+		&config,
+		application_id,
+		db_connection_pool,
+		bot_state,
+	)
+	.await;
 	if let Err(error) = event_result {
 		tracing::error!(source = ?error, "An error occurred handling a gateway event");
 	}
@@ -94,6 +118,7 @@ async fn handle_event(
 async fn handle_event_route(
 	event: Event,
 	http_client: &Arc<Client>,
+	config: &ConfigData,
 	application_id: Id<ApplicationMarker>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	bot_state: Arc<RwLock<TypeMap>>,
@@ -106,6 +131,7 @@ async fn handle_event_route(
 					&interaction,
 					command_data,
 					http_client,
+					config,
 					application_id,
 					db_connection_pool,
 					bot_state,
@@ -128,6 +154,7 @@ async fn handle_event_route(
 					&interaction,
 					modal_data,
 					http_client,
+					config,
 					application_id,
 					db_connection_pool,
 					bot_state,
@@ -136,14 +163,25 @@ async fn handle_event_route(
 			}
 			_ => (),
 		},
+		Event::AutoModerationActionExecution(execution) => {
+			handle_automod_execution(&execution, db_connection_pool).await?
+		}
+		Event::BanAdd(ban_add) => handle_member_ban(&ban_add, http_client, db_connection_pool).await?,
 		Event::GuildAuditLogEntryCreate(event_audit_data) => {
-			route_events(&event_audit_data.0, http_client, db_connection_pool).await?
+			route_events(&event_audit_data.0, http_client, config, db_connection_pool).await?
+		}
+		Event::MemberAdd(member_add) => handle_member_add(&member_add, http_client, config, db_connection_pool).await?,
+		Event::MemberRemove(member_remove) => {
+			handle_member_remove(&member_remove, http_client, db_connection_pool).await?
 		}
 		Event::MessageCreate(message_created) => {
 			handle_message(&message_created.0, http_client, db_connection_pool).await?
 		}
 		Event::Ready(_) => {
 			tracing::info!("Discord gateway is ready");
+			// Ready is received on startup and whenever a new session is started after a dropped connection; resumed
+			// sessions replay missed events instead.
+			catch_up_audit_logs(http_client, config, db_connection_pool).await?;
 		}
 		_ => (),
 	}
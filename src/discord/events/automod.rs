@@ -4,16 +4,89 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
+use crate::discord::utils::cases::assign_case_number;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_AUTOMOD, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::post_action_to_mod_log;
 use crate::discord::utils::timestamp::datetime_from_id;
-use crate::model::{AutomodAction, AutomodActionType, Guild, database_id_from_discord_id};
-use crate::schema::{automod_actions, guilds};
+use crate::model::{
+	AutomodAction, AutomodActionType, Guild, ModerationActionType, PendingAutomodExecution, database_id_from_discord_id,
+};
+use crate::schema::{automod_actions, guilds, pending_automod_executions};
+use chrono::{TimeDelta, Utc};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::gateway::payload::incoming::AutoModerationActionExecution;
 use twilight_model::guild::audit_log::AuditLogEntry;
+use twilight_model::guild::auto_moderation::AutoModerationActionType;
+
+/// How far apart an execution event and an audit log entry can be while still being considered the same action.
+///
+/// Discord doesn't give us anything to directly link the two, so we match them by guild, user, action type, and time.
+const EXECUTION_MATCH_WINDOW: TimeDelta = TimeDelta::seconds(30);
+
+/// How long an unmatched execution event is kept before it's assumed its audit log entry will never be received
+const PENDING_EXECUTION_LIFETIME: TimeDelta = TimeDelta::minutes(10);
 
 pub async fn handle_block(
 	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	record_automod_action(
+		event_audit_entry,
+		AutomodActionType::Block,
+		"AutoMod: Message Blocked",
+		http_client,
+		config,
+		db_connection_pool,
+	)
+	.await
+}
+
+pub async fn handle_flag(
+	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	record_automod_action(
+		event_audit_entry,
+		AutomodActionType::FlagToChannel,
+		"AutoMod: Message Flagged",
+		http_client,
+		config,
+		db_connection_pool,
+	)
+	.await
+}
+
+pub async fn handle_timeout(
+	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	record_automod_action(
+		event_audit_entry,
+		AutomodActionType::DisableCommunication,
+		"AutoMod: Timeout",
+		http_client,
+		config,
+		db_connection_pool,
+	)
+	.await
+}
+
+async fn record_automod_action(
+	event_audit_entry: &AuditLogEntry,
+	action_type: AutomodActionType,
+	mod_log_title: &str,
+	http_client: &Client,
+	config: &ConfigData,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
 	let Some(guild_id) = event_audit_entry.guild_id else {
@@ -31,81 +104,216 @@ pub async fn handle_block(
 		.unwrap_or_default();
 
 	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
-		bail!("Invalid timestamp received for automod block: {:?}", event_audit_entry);
+		bail!("Invalid timestamp received for automod action: {:?}", event_audit_entry);
 	};
 
 	let guild = database_id_from_discord_id(guild_id.get());
 	let target_user = database_id_from_discord_id(target_user_id.get());
 	let reason = event_audit_entry.reason.clone().unwrap_or_default();
+	let channel = auto_mod_action_data
+		.channel_id
+		.map(|channel_id| database_id_from_discord_id(channel_id.get()));
 
 	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
 
-	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
-	match db_guild_result {
-		Ok(Some(_)) => guild,
-		Ok(None) => return Ok(()),
-		Err(error) => bail!(error),
+	let insert_result: QueryResult<Option<(Guild, AutomodAction, i32)>> = db_connection.transaction(|db_connection| {
+		let Some(guild_data) = lock_guild(db_connection, guild)? else {
+			return Ok(None);
+		};
+
+		let pending_execution: Option<PendingAutomodExecution> = pending_automod_executions::table
+			.filter(
+				pending_automod_executions::guild
+					.eq(guild)
+					.and(pending_automod_executions::target_user.eq(target_user))
+					.and(pending_automod_executions::action_type.eq(action_type))
+					.and(pending_automod_executions::received_at.between(
+						action_time - EXECUTION_MATCH_WINDOW,
+						action_time + EXECUTION_MATCH_WINDOW,
+					)),
+			)
+			.order(pending_automod_executions::received_at.asc())
+			.first(db_connection)
+			.optional()?;
+
+		let mut new_automod_action = AutomodAction {
+			id: cuid2::create_id(),
+			guild,
+			target_user,
+			action_type,
+			action_time,
+			reason,
+			rule_name,
+			message_content: None,
+			mod_log_channel: None,
+			mod_log_message: None,
+			audit_log_entry: Some(database_id_from_discord_id(event_audit_entry.id.get())),
+			channel,
+			matched_keyword: None,
+			matched_content: None,
+			alert_message: None,
+		};
+		if let Some(pending_execution) = &pending_execution {
+			new_automod_action.message_content = Some(pending_execution.message_content.clone());
+			new_automod_action.channel = pending_execution.channel.or(channel);
+			new_automod_action.matched_keyword = pending_execution.matched_keyword.clone();
+			new_automod_action.matched_content = pending_execution.matched_content.clone();
+			new_automod_action.alert_message = pending_execution.alert_message;
+		}
+
+		let inserted_count = diesel::insert_into(automod_actions::table)
+			.values(&new_automod_action)
+			.on_conflict(automod_actions::audit_log_entry)
+			.do_nothing()
+			.execute(db_connection)?;
+		if inserted_count == 0 {
+			return Ok(None);
+		}
+
+		if let Some(pending_execution) = pending_execution {
+			diesel::delete(pending_automod_executions::table)
+				.filter(pending_automod_executions::id.eq(&pending_execution.id))
+				.execute(db_connection)?;
+		}
+
+		let case_number = assign_case_number(
+			db_connection,
+			guild,
+			ModerationActionType::Automod,
+			&new_automod_action.id,
+		)?;
+
+		Ok(Some((guild_data, new_automod_action, case_number)))
+	});
+	let Some((guild_data, new_automod_action, case_number)) = insert_result.into_diagnostic()? else {
+		return Ok(());
 	};
 
-	let new_automod_action = AutomodAction {
-		id: cuid2::create_id(),
-		guild,
-		target_user,
-		action_type: AutomodActionType::Block,
+	let mod_log_entry = ModLogEntry {
+		title: mod_log_title,
+		color: MOD_LOG_COLOR_AUTOMOD,
+		case_number: Some(case_number),
+		target_user: Some(target_user_id),
+		actor: ModLogActor::AutoMod {
+			rule_name: new_automod_action.rule_name.clone(),
+		},
+		reason: Some(&new_automod_action.reason),
+		expires_at: None,
 		action_time,
-		reason,
-		rule_name,
+		details: Vec::new(),
 	};
-
-	diesel::insert_into(automod_actions::table)
-		.values(new_automod_action)
-		.execute(&mut db_connection)
-		.into_diagnostic()?;
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild_data,
+		ModerationActionType::Automod,
+		&new_automod_action.id,
+		&mod_log_entry,
+	)
+	.await;
 
 	Ok(())
 }
 
-pub async fn handle_timeout(
-	event_audit_entry: &AuditLogEntry,
+/// Records the message details from an automod execution event.
+///
+/// The details are added to the matching automod action if its audit log entry has already been received. Otherwise,
+/// they're held until the audit log entry arrives.
+pub async fn handle_automod_execution(
+	execution: &AutoModerationActionExecution,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
-	let Some(guild_id) = event_audit_entry.guild_id else {
-		return Ok(());
-	};
-	let Some(target_user_id) = event_audit_entry.user_id else {
-		return Ok(());
-	};
-	let Some(auto_mod_action_data) = &event_audit_entry.options else {
-		return Ok(());
+	let action_type = match execution.action.kind {
+		AutoModerationActionType::BlockMessage => AutomodActionType::Block,
+		AutoModerationActionType::SendAlertMessage => AutomodActionType::FlagToChannel,
+		AutoModerationActionType::Timeout => AutomodActionType::DisableCommunication,
+		_ => return Ok(()),
 	};
-	let rule_name = auto_mod_action_data
-		.auto_moderation_rule_name
-		.clone()
-		.unwrap_or_default();
 
-	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
-		bail!(
-			"Invalid timestamp received for automod timeout: {:?}",
-			event_audit_entry
-		);
-	};
+	let received_at = Utc::now();
+	let guild = database_id_from_discord_id(execution.guild_id.get());
+	let target_user = database_id_from_discord_id(execution.user_id.get());
+	let channel = execution
+		.channel_id
+		.map(|channel_id| database_id_from_discord_id(channel_id.get()));
+	let alert_message = execution
+		.alert_system_message_id
+		.map(|message_id| database_id_from_discord_id(message_id.get()));
 
 	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
 
-	let new_automod_action = AutomodAction {
-		id: cuid2::create_id(),
-		guild: database_id_from_discord_id(guild_id.get()),
-		target_user: database_id_from_discord_id(target_user_id.get()),
-		action_type: AutomodActionType::DisableCommunication,
-		action_time,
-		reason: event_audit_entry.reason.clone().unwrap_or_default(),
-		rule_name,
-	};
+	let record_result: QueryResult<()> = db_connection.transaction(|db_connection| {
+		if lock_guild(db_connection, guild)?.is_none() {
+			return Ok(());
+		}
 
-	diesel::insert_into(automod_actions::table)
-		.values(new_automod_action)
-		.execute(&mut db_connection)
-		.into_diagnostic()?;
+		diesel::delete(pending_automod_executions::table)
+			.filter(
+				pending_automod_executions::guild
+					.eq(guild)
+					.and(pending_automod_executions::received_at.lt(received_at - PENDING_EXECUTION_LIFETIME)),
+			)
+			.execute(db_connection)?;
 
-	Ok(())
+		let matching_action_id: Option<String> = automod_actions::table
+			.filter(
+				automod_actions::guild
+					.eq(guild)
+					.and(automod_actions::target_user.eq(target_user))
+					.and(automod_actions::action_type.eq(action_type))
+					.and(automod_actions::message_content.is_null())
+					.and(automod_actions::action_time.between(
+						received_at - EXECUTION_MATCH_WINDOW,
+						received_at + EXECUTION_MATCH_WINDOW,
+					)),
+			)
+			.order(automod_actions::action_time.desc())
+			.select(automod_actions::id)
+			.first(db_connection)
+			.optional()?;
+
+		match matching_action_id {
+			Some(action_id) => {
+				diesel::update(automod_actions::table)
+					.filter(automod_actions::id.eq(&action_id))
+					.set((
+						automod_actions::message_content.eq(Some(&execution.content)),
+						automod_actions::channel.eq(channel),
+						automod_actions::matched_keyword.eq(&execution.matched_keyword),
+						automod_actions::matched_content.eq(&execution.matched_content),
+						automod_actions::alert_message.eq(alert_message),
+					))
+					.execute(db_connection)?;
+			}
+			None => {
+				let pending_execution = PendingAutomodExecution {
+					id: cuid2::create_id(),
+					guild,
+					target_user,
+					action_type,
+					received_at,
+					channel,
+					message_content: execution.content.clone(),
+					matched_keyword: execution.matched_keyword.clone(),
+					matched_content: execution.matched_content.clone(),
+					alert_message,
+				};
+				diesel::insert_into(pending_automod_executions::table)
+					.values(&pending_execution)
+					.execute(db_connection)?;
+			}
+		}
+
+		Ok(())
+	});
+	record_result.into_diagnostic()
+}
+
+/// Locks the guild's row for the rest of the transaction so that an execution event and its audit log entry being
+/// processed at the same time can't both miss each other.
+///
+/// Returns [None] if the guild isn't set up.
+fn lock_guild(db_connection: &mut PgConnection, guild: i64) -> QueryResult<Option<Guild>> {
+	guilds::table.find(guild).for_update().first(db_connection).optional()
 }
@@ -4,14 +4,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
+use crate::discord::utils::action_reasons::provide_reason_components;
+use crate::discord::utils::ban_sharing::share_ban_action;
+use crate::discord::utils::cases::assign_case_number;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_BAN, MOD_LOG_COLOR_UNBAN, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{COMMAND_ACTION_CLAIM_WINDOW, post_action_to_mod_log};
+use crate::discord::utils::reason_policy::{check_reason_policy, reason_complaint_message};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
 use crate::discord::utils::timestamp::datetime_from_id;
-use crate::model::{BanAction, Guild, database_id_from_discord_id};
+use crate::model::{BanAction, Guild, ModerationActionType, ServerRule, database_id_from_discord_id};
 use crate::schema::{ban_actions, guilds};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::{IntoDiagnostic, bail};
 use twilight_http::client::Client;
-use twilight_mention::fmt::Mention;
 use twilight_model::channel::message::AllowedMentions;
 use twilight_model::guild::audit_log::AuditLogEntry;
 use twilight_model::id::Id;
@@ -20,6 +27,7 @@ use twilight_model::id::marker::UserMarker;
 pub async fn handle_ban(
 	event_audit_entry: &AuditLogEntry,
 	http_client: &Client,
+	config: &ConfigData,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
 	let Some(guild_id) = event_audit_entry.guild_id else {
@@ -50,6 +58,33 @@ pub async fn handle_ban(
 		Err(error) => bail!(error),
 	};
 
+	// Actions taken through the bot's moderation commands, by escalation rules, or from shared bans are recorded when
+	// they're taken, so the audit log entry only needs to be attached to that record
+	let claimed_actions: Vec<BanAction> = diesel::update(ban_actions::table)
+		.filter(
+			ban_actions::guild
+				.eq(guild)
+				.and(ban_actions::banned_user.eq(banned_user))
+				.and(ban_actions::added.eq(true))
+				.and(
+					ban_actions::via_bot_command
+						.eq(true)
+						.or(ban_actions::escalation_rule.is_not_null())
+						.or(ban_actions::shared_ban.is_not_null()),
+				)
+				.and(ban_actions::audit_log_entry.is_null())
+				.and(ban_actions::action_time.gt(action_time - COMMAND_ACTION_CLAIM_WINDOW)),
+		)
+		.set(ban_actions::audit_log_entry.eq(database_id_from_discord_id(event_audit_entry.id.get())))
+		.get_results(&mut db_connection)
+		.into_diagnostic()?;
+	if !claimed_actions.is_empty() {
+		for claimed_action in claimed_actions.iter() {
+			share_with_trusted_guilds(http_client, config, &mut db_connection, claimed_action).await;
+		}
+		return Ok(());
+	}
+
 	let new_ban_action = BanAction {
 		id: cuid2::create_id(),
 		guild,
@@ -58,27 +93,100 @@ pub async fn handle_ban(
 		added: true,
 		action_time,
 		reason: event_audit_entry.reason.clone().unwrap_or_default(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: Some(database_id_from_discord_id(event_audit_entry.id.get())),
+		evidence: None,
+		via_bot_command: false,
+		escalation_rule: None,
+		expires_at: None,
+		original_ban: None,
+		shared_ban: None,
 	};
 
-	diesel::insert_into(ban_actions::table)
-		.values(new_ban_action)
-		.execute(&mut db_connection)
-		.into_diagnostic()?;
+	let recorded: QueryResult<Option<(i32, Vec<ServerRule>)>> = db_connection.transaction(|db_connection| {
+		let inserted_count = diesel::insert_into(ban_actions::table)
+			.values(&new_ban_action)
+			.on_conflict(ban_actions::audit_log_entry)
+			.do_nothing()
+			.execute(db_connection)?;
+		if inserted_count == 0 {
+			return Ok(None);
+		}
+
+		let case_number = assign_case_number(db_connection, guild, ModerationActionType::Ban, &new_ban_action.id)?;
+		let rules = link_rules_from_reason(
+			db_connection,
+			guild,
+			ModerationActionType::Ban,
+			&new_ban_action.id,
+			action_time,
+			&new_ban_action.reason,
+			None,
+		)?;
+
+		Ok(Some((case_number, rules)))
+	});
+	let Some((case_number, rules)) = recorded.into_diagnostic()? else {
+		return Ok(());
+	};
+
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: "Ban",
+		color: MOD_LOG_COLOR_BAN,
+		case_number: Some(case_number),
+		target_user: Some(banned_user_id),
+		actor: ModLogActor::User(banning_user_id),
+		reason: Some(&new_ban_action.reason),
+		expires_at: None,
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild_data,
+		ModerationActionType::Ban,
+		&new_ban_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	share_with_trusted_guilds(http_client, config, &mut db_connection, &new_ban_action).await;
 
 	if let Some(complain_channel_id) = guild_data.get_action_reason_complain_channel() {
-		let complain_message = format!(
-			"{0}, {1} has been banned, but you didn't provide a reason. Please write a note about {1} as soon as you can.",
-			banning_user_id.mention(),
-			banned_user_id.mention()
-		);
-		let mut allowed_mentions = AllowedMentions::default();
-		allowed_mentions.users.push(banning_user_id);
-		http_client
-			.create_message(complain_channel_id)
-			.content(&complain_message)
-			.allowed_mentions(Some(&allowed_mentions))
-			.await
-			.into_diagnostic()?;
+		let policy_failure = check_reason_policy(
+			&mut db_connection,
+			http_client,
+			guild_id,
+			banning_user_id,
+			&new_ban_action.reason,
+		)
+		.await?;
+		if let Some(policy_failure) = policy_failure {
+			let complain_message =
+				reason_complaint_message(banning_user_id, banned_user_id, "has been banned", &policy_failure);
+			let mut allowed_mentions = AllowedMentions::default();
+			allowed_mentions.users.push(banning_user_id);
+			http_client
+				.create_message(complain_channel_id)
+				.content(&complain_message)
+				.allowed_mentions(Some(&allowed_mentions))
+				.components(&provide_reason_components(
+					ModerationActionType::Ban,
+					&new_ban_action.id,
+				))
+				.await
+				.into_diagnostic()?;
+		}
 	}
 
 	Ok(())
@@ -86,21 +194,24 @@ pub async fn handle_ban(
 
 pub async fn handle_unban(
 	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
 	let Some(guild_id) = event_audit_entry.guild_id else {
 		return Ok(());
 	};
-	let Some(banning_user) = event_audit_entry.user_id else {
+	let Some(banning_user_id) = event_audit_entry.user_id else {
 		bail!("Unbanning user not in unban audit data: {:?}", event_audit_entry);
 	};
-	let Some(banned_user) = event_audit_entry.target_id else {
+	let Some(banned_user_id) = event_audit_entry.target_id else {
 		bail!("Unbanned user not in unban audit data: {:?}", event_audit_entry);
 	};
+	let banned_user_id: Id<UserMarker> = banned_user_id.cast();
 
 	let guild = database_id_from_discord_id(guild_id.get());
-	let banning_user = database_id_from_discord_id(banning_user.get());
-	let banned_user = database_id_from_discord_id(banned_user.get());
+	let banning_user = database_id_from_discord_id(banning_user_id.get());
+	let banned_user = database_id_from_discord_id(banned_user_id.get());
 
 	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
 		bail!("Invalid timestamp provided with unban: {:?}", event_audit_entry);
@@ -109,10 +220,35 @@ pub async fn handle_unban(
 	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
 
 	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
-	match db_guild_result {
-		Ok(Some(_)) => (),
+	let guild_data = match db_guild_result {
+		Ok(Some(guild)) => guild,
 		Ok(None) => return Ok(()),
 		Err(error) => bail!(error),
+	};
+
+	// Unbans for expired temporary bans and shared unbans are recorded when the bot lifts the ban
+	let claimed_actions: Vec<BanAction> = diesel::update(ban_actions::table)
+		.filter(
+			ban_actions::guild
+				.eq(guild)
+				.and(ban_actions::banned_user.eq(banned_user))
+				.and(ban_actions::added.eq(false))
+				.and(
+					ban_actions::original_ban
+						.is_not_null()
+						.or(ban_actions::shared_ban.is_not_null()),
+				)
+				.and(ban_actions::audit_log_entry.is_null())
+				.and(ban_actions::action_time.gt(action_time - COMMAND_ACTION_CLAIM_WINDOW)),
+		)
+		.set(ban_actions::audit_log_entry.eq(database_id_from_discord_id(event_audit_entry.id.get())))
+		.get_results(&mut db_connection)
+		.into_diagnostic()?;
+	if !claimed_actions.is_empty() {
+		for claimed_action in claimed_actions.iter() {
+			share_with_trusted_guilds(http_client, config, &mut db_connection, claimed_action).await;
+		}
+		return Ok(());
 	}
 
 	let new_unban_action = BanAction {
@@ -123,12 +259,74 @@ pub async fn handle_unban(
 		added: false,
 		action_time,
 		reason: String::new(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: Some(database_id_from_discord_id(event_audit_entry.id.get())),
+		evidence: None,
+		via_bot_command: false,
+		escalation_rule: None,
+		expires_at: None,
+		original_ban: None,
+		shared_ban: None,
 	};
 
-	diesel::insert_into(ban_actions::table)
-		.values(new_unban_action)
-		.execute(&mut db_connection)
-		.into_diagnostic()?;
+	let recorded: QueryResult<Option<i32>> = db_connection.transaction(|db_connection| {
+		let inserted_count = diesel::insert_into(ban_actions::table)
+			.values(&new_unban_action)
+			.on_conflict(ban_actions::audit_log_entry)
+			.do_nothing()
+			.execute(db_connection)?;
+		if inserted_count == 0 {
+			return Ok(None);
+		}
+
+		let case_number = assign_case_number(db_connection, guild, ModerationActionType::Ban, &new_unban_action.id)?;
+
+		Ok(Some(case_number))
+	});
+	let Some(case_number) = recorded.into_diagnostic()? else {
+		return Ok(());
+	};
+
+	let mod_log_entry = ModLogEntry {
+		title: "Unban",
+		color: MOD_LOG_COLOR_UNBAN,
+		case_number: Some(case_number),
+		target_user: Some(banned_user_id),
+		actor: ModLogActor::User(banning_user_id),
+		reason: Some(&new_unban_action.reason),
+		expires_at: None,
+		action_time,
+		details: Vec::new(),
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild_data,
+		ModerationActionType::Ban,
+		&new_unban_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	share_with_trusted_guilds(http_client, config, &mut db_connection, &new_unban_action).await;
 
 	Ok(())
 }
+
+/// Shares a ban or unban with trusted guilds. Sharing problems are logged rather than returned so they don't stop the
+/// action from being handled in its own guild.
+async fn share_with_trusted_guilds(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+	ban_action: &BanAction,
+) {
+	if let Err(error) = share_ban_action(http_client, config, db_connection, ban_action).await {
+		tracing::warn!(source = ?error, ban = %ban_action.id, "Failed to share a ban with trusted guilds");
+	}
+}
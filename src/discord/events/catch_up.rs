@@ -0,0 +1,122 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::route_events;
+use crate::config::ConfigData;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_model::id::Id;
+use twilight_model::id::marker::GuildMarker;
+
+/// The most audit log entries Discord returns in one request
+const AUDIT_LOG_PAGE_SIZE: u16 = 100;
+
+/// Processes audit log entries created since the last processed entry for every set-up guild.
+///
+/// Gateway events aren't replayed after the bot is offline or starts a new gateway session, so this fills in any
+/// actions that would otherwise be missed. Each action is stored with the ID of its audit log entry, so an entry that
+/// was also received live is only recorded once. Escalation rules aren't applied for entries that are too old.
+pub async fn catch_up_audit_logs(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let guilds: Vec<Guild> = {
+		let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+		guilds::table.load(&mut db_connection).into_diagnostic()?
+	};
+
+	for guild in guilds.iter() {
+		let catch_up_result = catch_up_guild_audit_log(guild, http_client, config, &db_connection_pool).await;
+		if let Err(error) = catch_up_result {
+			tracing::warn!(source = ?error, guild = %guild.get_guild_id(), "Failed to catch up on a guild's audit log");
+		}
+	}
+
+	Ok(())
+}
+
+async fn catch_up_guild_audit_log(
+	guild: &Guild,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let guild_id = guild.get_guild_id();
+
+	let Some(mut last_entry_id) = guild.get_last_audit_log_entry() else {
+		// Without a starting point, we can't tell which entries were already recorded, so we start tracking from the
+		// newest entry rather than importing the guild's entire audit log.
+		return start_tracking_audit_log(guild_id, http_client, db_connection_pool).await;
+	};
+
+	loop {
+		let mut entries = http_client
+			.audit_log(guild_id)
+			.after(last_entry_id.get())
+			.limit(AUDIT_LOG_PAGE_SIZE)
+			.await
+			.into_diagnostic()?
+			.model()
+			.await
+			.into_diagnostic()?
+			.entries;
+		let page_length = entries.len();
+		entries.sort_by_key(|entry| entry.id);
+
+		for mut entry in entries {
+			last_entry_id = entry.id;
+			// Entries from the HTTP API don't include the guild ID, which the handlers rely on
+			entry.guild_id = Some(guild_id);
+			let route_result = route_events(&entry, http_client, config, db_connection_pool.clone()).await;
+			if let Err(error) = route_result {
+				tracing::error!(source = ?error, "Failed to process a missed audit log entry");
+			}
+		}
+
+		if page_length < usize::from(AUDIT_LOG_PAGE_SIZE) {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+async fn start_tracking_audit_log(
+	guild_id: Id<GuildMarker>,
+	http_client: &Client,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let entries = http_client
+		.audit_log(guild_id)
+		.limit(1)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?
+		.entries;
+	let Some(newest_entry) = entries.first() else {
+		return Ok(());
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	diesel::update(guilds::table)
+		.filter(
+			guilds::guild_id
+				.eq(database_id_from_discord_id(guild_id.get()))
+				.and(guilds::last_audit_log_entry.is_null()),
+		)
+		.set(guilds::last_audit_log_entry.eq(database_id_from_discord_id(newest_entry.id.get())))
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+
+	Ok(())
+}
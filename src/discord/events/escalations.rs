@@ -0,0 +1,512 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::escalations::describe_escalation_rule;
+use crate::discord::utils::mod_log::{
+	MOD_LOG_COLOR_BAN, MOD_LOG_COLOR_KICK, MOD_LOG_COLOR_STAFF_REVIEW, MOD_LOG_COLOR_TIMEOUT, ModLogActor, ModLogEntry,
+	post_mod_log_entry,
+};
+use crate::discord::utils::moderation::{NewActionRecord, perform_recorded_action, post_action_to_mod_log};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::model::{
+	AutomodActionType, BanAction, EscalationActionType, EscalationApplication, EscalationRule, EscalationTriggerType,
+	Guild, KickAction, ModerationActionType, TimeoutAction, database_id_from_discord_id,
+};
+use crate::schema::{
+	automod_actions, escalation_applications, escalation_rules, guilds, kick_actions, timeout_actions,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_model::guild::audit_log::{AuditLogChange, AuditLogEntry, AuditLogEventType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+use twilight_model::util::datetime::Timestamp;
+
+/// How old an audit log entry can be while still triggering escalation rules.
+///
+/// Entries processed while catching up on the audit log can be from long before the bot came back online, and applying
+/// escalations for them would act against users well after the fact.
+const ESCALATION_ENTRY_MAX_AGE: TimeDelta = TimeDelta::hours(1);
+
+/// Gets the type of action an audit log entry records for the purposes of escalation rules, along with the user the
+/// action was taken against. Returns [None] if the entry can't trigger escalation rules.
+pub fn escalation_trigger(event_audit_entry: &AuditLogEntry) -> Option<(EscalationTriggerType, Id<UserMarker>)> {
+	let trigger_type = match event_audit_entry.action_type {
+		AuditLogEventType::AutoModerationBlockMessage => EscalationTriggerType::AutomodBlock,
+		AuditLogEventType::AutoModerationFlagToChannel => EscalationTriggerType::AutomodFlag,
+		AuditLogEventType::AutoModerationUserCommunicationDisabled => EscalationTriggerType::AutomodTimeout,
+		AuditLogEventType::MemberKick => EscalationTriggerType::Kick,
+		AuditLogEventType::MemberUpdate => {
+			let timeout_added = event_audit_entry
+				.changes
+				.iter()
+				.any(|change| matches!(change, AuditLogChange::CommunicationDisabledUntil { new: Some(_), .. }));
+			if !timeout_added {
+				return None;
+			}
+			EscalationTriggerType::Timeout
+		}
+		_ => return None,
+	};
+
+	// Automod entries are attributed to the user who triggered automod
+	let target_user = match trigger_type {
+		EscalationTriggerType::AutomodBlock
+		| EscalationTriggerType::AutomodFlag
+		| EscalationTriggerType::AutomodTimeout => event_audit_entry.user_id,
+		EscalationTriggerType::Kick | EscalationTriggerType::Timeout => {
+			event_audit_entry.target_id.map(|target_id| target_id.cast())
+		}
+	}?;
+
+	Some((trigger_type, target_user))
+}
+
+/// Checks the guild's escalation rules after an action was recorded against a user, applying any that were triggered
+pub async fn evaluate_escalations(
+	event_audit_entry: &AuditLogEntry,
+	trigger_type: EscalationTriggerType,
+	target_user_id: Id<UserMarker>,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let guild = database_id_from_discord_id(guild_id.get());
+	let target_user = database_id_from_discord_id(target_user_id.get());
+	let triggering_audit_log_entry = database_id_from_discord_id(event_audit_entry.id.get());
+	let now = Utc::now();
+
+	let Some(entry_time) = datetime_from_id(event_audit_entry.id) else {
+		bail!("Invalid timestamp for escalation trigger: {:?}", event_audit_entry);
+	};
+	if now - entry_time > ESCALATION_ENTRY_MAX_AGE {
+		return Ok(());
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let triggered: QueryResult<Option<(Guild, Vec<EscalationRule>)>> = db_connection.transaction(|db_connection| {
+		// Locking the guild keeps two actions recorded at the same time from both triggering the same rule
+		let guild_data: Option<Guild> = guilds::table.find(guild).for_update().first(db_connection).optional()?;
+		let Some(guild_data) = guild_data else {
+			return Ok(None);
+		};
+
+		let rules: Vec<EscalationRule> = escalation_rules::table
+			.filter(
+				escalation_rules::guild
+					.eq(guild)
+					.and(escalation_rules::trigger_type.eq(trigger_type))
+					.and(escalation_rules::active.eq(true)),
+			)
+			.order(escalation_rules::created_at.asc())
+			.load(db_connection)?;
+
+		let mut triggered_rules = Vec::new();
+		for rule in rules {
+			// Once a rule has been applied to a user, only actions after that count towards applying it again
+			let last_applied: Option<DateTime<Utc>> = escalation_applications::table
+				.filter(
+					escalation_applications::rule
+						.eq(&rule.id)
+						.and(escalation_applications::target_user.eq(target_user)),
+				)
+				.select(max(escalation_applications::applied_at))
+				.get_result(db_connection)?;
+			let window_start = rule
+				.window_days
+				.and_then(|days| TimeDelta::try_days(i64::from(days)))
+				.map(|window| now - window);
+			let count_since = match (last_applied, window_start) {
+				(Some(last_applied), Some(window_start)) => Some(last_applied.max(window_start)),
+				(last_applied, window_start) => last_applied.or(window_start),
+			};
+
+			let action_count = count_trigger_actions(db_connection, guild, target_user, trigger_type, count_since)?;
+			if action_count < i64::from(rule.threshold) {
+				continue;
+			}
+
+			let application = EscalationApplication {
+				id: cuid2::create_id(),
+				rule: rule.id.clone(),
+				guild,
+				target_user,
+				applied_at: now,
+				triggering_audit_log_entry,
+			};
+			let inserted_count = diesel::insert_into(escalation_applications::table)
+				.values(&application)
+				.on_conflict((
+					escalation_applications::rule,
+					escalation_applications::triggering_audit_log_entry,
+				))
+				.do_nothing()
+				.execute(db_connection)?;
+			if inserted_count > 0 {
+				triggered_rules.push(rule);
+			}
+		}
+
+		Ok(Some((guild_data, triggered_rules)))
+	});
+	let Some((guild_data, triggered_rules)) = triggered.into_diagnostic()? else {
+		return Ok(());
+	};
+
+	for rule in triggered_rules.iter() {
+		let apply_result = apply_escalation(
+			rule,
+			&guild_data,
+			target_user_id,
+			http_client,
+			config,
+			&mut db_connection,
+		)
+		.await;
+		if let Err(error) = apply_result {
+			tracing::warn!(source = ?error, rule = %rule.id, "Failed to apply an escalation rule");
+			// Removing the application lets the rule apply again when the entry is next handled
+			let delete_result = diesel::delete(escalation_applications::table)
+				.filter(
+					escalation_applications::rule
+						.eq(&rule.id)
+						.and(escalation_applications::triggering_audit_log_entry.eq(triggering_audit_log_entry)),
+				)
+				.execute(&mut db_connection);
+			if let Err(error) = delete_result {
+				tracing::warn!(source = ?error, rule = %rule.id, "Failed to remove a failed escalation application");
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Counts the actions of the trigger type taken against the user, optionally only counting actions after a given time.
+///
+/// Actions applied by escalation rules don't count, so that rules can't trigger each other indefinitely.
+fn count_trigger_actions(
+	db_connection: &mut PgConnection,
+	guild: i64,
+	target_user: i64,
+	trigger_type: EscalationTriggerType,
+	since: Option<DateTime<Utc>>,
+) -> QueryResult<i64> {
+	let automod_action_type = match trigger_type {
+		EscalationTriggerType::AutomodBlock => AutomodActionType::Block,
+		EscalationTriggerType::AutomodFlag => AutomodActionType::FlagToChannel,
+		EscalationTriggerType::AutomodTimeout => AutomodActionType::DisableCommunication,
+		EscalationTriggerType::Kick => {
+			let mut query = kick_actions::table
+				.filter(
+					kick_actions::guild
+						.eq(guild)
+						.and(kick_actions::kicked_user.eq(target_user))
+						.and(kick_actions::escalation_rule.is_null()),
+				)
+				.into_boxed();
+			if let Some(since) = since {
+				query = query.filter(kick_actions::action_time.gt(since));
+			}
+			return query.count().get_result(db_connection);
+		}
+		EscalationTriggerType::Timeout => {
+			let mut query = timeout_actions::table
+				.filter(
+					timeout_actions::guild
+						.eq(guild)
+						.and(timeout_actions::target_user.eq(target_user))
+						.and(timeout_actions::timeout_until.is_not_null())
+						.and(timeout_actions::escalation_rule.is_null()),
+				)
+				.into_boxed();
+			if let Some(since) = since {
+				query = query.filter(timeout_actions::action_time.gt(since));
+			}
+			return query.count().get_result(db_connection);
+		}
+	};
+
+	let mut query = automod_actions::table
+		.filter(
+			automod_actions::guild
+				.eq(guild)
+				.and(automod_actions::target_user.eq(target_user))
+				.and(automod_actions::action_type.eq(automod_action_type)),
+		)
+		.into_boxed();
+	if let Some(since) = since {
+		query = query.filter(automod_actions::action_time.gt(since));
+	}
+	query.count().get_result(db_connection)
+}
+
+/// Takes the action for a triggered escalation rule and posts it to the mod log
+async fn apply_escalation(
+	rule: &EscalationRule,
+	guild: &Guild,
+	target_user_id: Id<UserMarker>,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let guild_id = guild.get_guild_id();
+	let rule_description = describe_escalation_rule(rule);
+	let audit_log_reason = format!("Escalation rule: {}", rule_description);
+	let action_time = Utc::now();
+	let mod_log_actor = ModLogActor::Escalation {
+		rule_description: rule_description.clone(),
+	};
+
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let target_user = database_id_from_discord_id(target_user_id.get());
+
+	match rule.action_type {
+		EscalationActionType::StaffReview => {
+			return request_staff_review(
+				rule,
+				guild,
+				target_user_id,
+				mod_log_actor,
+				action_time,
+				http_client,
+				config,
+			)
+			.await;
+		}
+		EscalationActionType::Timeout => {
+			let performing_user = bot_user_database_id(http_client).await?;
+			let Some(timeout_minutes) = rule.timeout_minutes else {
+				bail!("Escalation rule {} is a timeout rule with no duration", rule.id);
+			};
+			let timeout_until = action_time + TimeDelta::minutes(i64::from(timeout_minutes));
+			let communication_disabled_until = Timestamp::from_secs(timeout_until.timestamp()).into_diagnostic()?;
+			let new_timeout_action = TimeoutAction {
+				id: cuid2::create_id(),
+				guild: db_guild_id,
+				performing_user,
+				target_user,
+				action_time,
+				timeout_until: Some(timeout_until),
+				reason: audit_log_reason.clone(),
+				amended_reason: None,
+				reason_amended_by: None,
+				reason_amended_at: None,
+				mod_log_channel: None,
+				mod_log_message: None,
+				audit_log_entry: None,
+				evidence: None,
+				via_bot_command: false,
+				escalation_rule: Some(rule.id.clone()),
+			};
+			let timeout = http_client
+				.update_guild_member(guild_id, target_user_id)
+				.communication_disabled_until(Some(communication_disabled_until))
+				.reason(&audit_log_reason);
+			let case_number =
+				perform_recorded_action(db_connection, NewActionRecord::Timeout(&new_timeout_action), timeout)
+					.await?
+					.into_diagnostic()?;
+			let mod_log_entry = ModLogEntry {
+				title: "Timeout",
+				color: MOD_LOG_COLOR_TIMEOUT,
+				case_number: Some(case_number),
+				target_user: Some(target_user_id),
+				actor: mod_log_actor,
+				reason: Some(&new_timeout_action.reason),
+				expires_at: Some(timeout_until),
+				action_time,
+				details: Vec::new(),
+			};
+			post_action_to_mod_log(
+				http_client,
+				config,
+				db_connection,
+				guild,
+				ModerationActionType::Timeout,
+				&new_timeout_action.id,
+				&mod_log_entry,
+			)
+			.await;
+		}
+		EscalationActionType::Kick => {
+			let performing_user = bot_user_database_id(http_client).await?;
+			let new_kick_action = KickAction {
+				id: cuid2::create_id(),
+				guild: db_guild_id,
+				kicking_user: performing_user,
+				kicked_user: target_user,
+				action_time,
+				reason: audit_log_reason.clone(),
+				amended_reason: None,
+				reason_amended_by: None,
+				reason_amended_at: None,
+				mod_log_channel: None,
+				mod_log_message: None,
+				audit_log_entry: None,
+				evidence: None,
+				via_bot_command: false,
+				escalation_rule: Some(rule.id.clone()),
+			};
+			let kick = http_client
+				.remove_guild_member(guild_id, target_user_id)
+				.reason(&audit_log_reason);
+			let case_number = perform_recorded_action(db_connection, NewActionRecord::Kick(&new_kick_action), kick)
+				.await?
+				.into_diagnostic()?;
+			let mod_log_entry = ModLogEntry {
+				title: "Kick",
+				color: MOD_LOG_COLOR_KICK,
+				case_number: Some(case_number),
+				target_user: Some(target_user_id),
+				actor: mod_log_actor,
+				reason: Some(&new_kick_action.reason),
+				expires_at: None,
+				action_time,
+				details: Vec::new(),
+			};
+			post_action_to_mod_log(
+				http_client,
+				config,
+				db_connection,
+				guild,
+				ModerationActionType::Kick,
+				&new_kick_action.id,
+				&mod_log_entry,
+			)
+			.await;
+		}
+		EscalationActionType::Ban => {
+			let performing_user = bot_user_database_id(http_client).await?;
+			let new_ban_action = BanAction {
+				id: cuid2::create_id(),
+				guild: db_guild_id,
+				banning_user: performing_user,
+				banned_user: target_user,
+				added: true,
+				action_time,
+				reason: audit_log_reason.clone(),
+				amended_reason: None,
+				reason_amended_by: None,
+				reason_amended_at: None,
+				mod_log_channel: None,
+				mod_log_message: None,
+				audit_log_entry: None,
+				evidence: None,
+				via_bot_command: false,
+				escalation_rule: Some(rule.id.clone()),
+				expires_at: None,
+				original_ban: None,
+				shared_ban: None,
+			};
+			let ban = http_client
+				.create_ban(guild_id, target_user_id)
+				.reason(&audit_log_reason);
+			let case_number = perform_recorded_action(db_connection, NewActionRecord::Ban(&new_ban_action), ban)
+				.await?
+				.into_diagnostic()?;
+			let mod_log_entry = ModLogEntry {
+				title: "Ban",
+				color: MOD_LOG_COLOR_BAN,
+				case_number: Some(case_number),
+				target_user: Some(target_user_id),
+				actor: mod_log_actor,
+				reason: Some(&new_ban_action.reason),
+				expires_at: None,
+				action_time,
+				details: Vec::new(),
+			};
+			post_action_to_mod_log(
+				http_client,
+				config,
+				db_connection,
+				guild,
+				ModerationActionType::Ban,
+				&new_ban_action.id,
+				&mod_log_entry,
+			)
+			.await;
+		}
+	}
+
+	Ok(())
+}
+
+/// Gets the bot's user ID in database form, for recording the actions the bot takes for escalation rules
+async fn bot_user_database_id(http_client: &Client) -> miette::Result<i64> {
+	let bot_user = http_client
+		.current_user()
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	Ok(database_id_from_discord_id(bot_user.id.get()))
+}
+
+/// Asks staff to review a user by posting to the mod log and opening a thread for discussion on the post.
+///
+/// A staff review that can't reach staff is an error, since otherwise the rule would silently do nothing.
+async fn request_staff_review(
+	rule: &EscalationRule,
+	guild: &Guild,
+	target_user_id: Id<UserMarker>,
+	mod_log_actor: ModLogActor,
+	action_time: DateTime<Utc>,
+	http_client: &Client,
+	config: &ConfigData,
+) -> miette::Result<()> {
+	let mod_log_entry = ModLogEntry {
+		title: "Staff Review Needed",
+		color: MOD_LOG_COLOR_STAFF_REVIEW,
+		case_number: None,
+		target_user: Some(target_user_id),
+		actor: mod_log_actor,
+		reason: None,
+		expires_at: None,
+		action_time,
+		details: Vec::new(),
+	};
+	let Some((channel_id, message_id)) = post_mod_log_entry(http_client, config, guild, &mod_log_entry).await? else {
+		bail!(
+			"Escalation rule {} requested a staff review, but the guild has no mod log channel to post it to",
+			rule.id
+		);
+	};
+	open_review_thread(http_client, channel_id, message_id, target_user_id).await
+}
+
+/// Opens a thread on a staff review mod log entry so staff can discuss the user
+async fn open_review_thread(
+	http_client: &Client,
+	channel_id: Id<ChannelMarker>,
+	message_id: Id<MessageMarker>,
+	target_user_id: Id<UserMarker>,
+) -> miette::Result<()> {
+	let target_user = http_client
+		.user(target_user_id)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let thread_name = format!("Review: {}", target_user.name);
+	http_client
+		.create_thread_from_message(channel_id, message_id, &thread_name)
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
@@ -0,0 +1,233 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::history::history_summary;
+use crate::discord::utils::mod_log::user_history_url;
+use crate::discord::utils::temporary_bans::latest_ban_action;
+use crate::discord::utils::ticket_departures::{TicketUserDeparture, handle_ticket_user_departure};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::model::{Guild, MemberEvent, database_id_from_discord_id};
+use crate::schema::{guilds, kick_actions, member_events, ticket_restricted_users};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::gateway::payload::incoming::{BanAdd, MemberAdd, MemberRemove};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{GuildMarker, UserMarker};
+use twilight_model::util::datetime::Timestamp;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+const MEMBER_ALERT_COLOR: u32 = 0xe67e22;
+
+pub async fn handle_member_add(
+	member_add: &MemberAdd,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let user_id = member_add.user.id;
+	let Some(guild_data) = record_member_event(member_add.guild_id, user_id, true, &db_connection_pool)? else {
+		return Ok(());
+	};
+	let Some(alert_channel) = guild_data.get_member_alert_channel() else {
+		return Ok(());
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_user_id = database_id_from_discord_id(user_id.get());
+	let Some(account_created_at) = datetime_from_id(user_id) else {
+		bail!("Invalid timestamp in joining user's ID: {}", user_id);
+	};
+
+	// Only actions since the user's previous join can explain why they left; anything older shows in the summary
+	let previous_join_time: Option<DateTime<Utc>> = member_events::table
+		.filter(
+			member_events::guild
+				.eq(guild_data.guild_id)
+				.and(member_events::user_id.eq(db_user_id))
+				.and(member_events::joined.eq(true)),
+		)
+		.order(member_events::event_time.desc())
+		.select(member_events::event_time)
+		.offset(1)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let since_previous_join = |time: &DateTime<Utc>| previous_join_time.is_none_or(|join_time| *time > join_time);
+
+	let mut alert_reasons: Vec<String> = Vec::new();
+
+	let last_kick_time: Option<DateTime<Utc>> = kick_actions::table
+		.filter(
+			kick_actions::guild
+				.eq(guild_data.guild_id)
+				.and(kick_actions::kicked_user.eq(db_user_id)),
+		)
+		.order(kick_actions::action_time.desc())
+		.select(kick_actions::action_time)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	if let Some(kick_time) = last_kick_time.filter(since_previous_join) {
+		alert_reasons.push(format!("Rejoined after being kicked {}", relative_time(kick_time)));
+	}
+
+	let latest_ban = latest_ban_action(&mut db_connection, guild_data.guild_id, db_user_id).into_diagnostic()?;
+	let latest_unban = latest_ban.filter(|action| !action.added && since_previous_join(&action.action_time));
+	if let Some(unban) = latest_unban {
+		alert_reasons.push(format!(
+			"Rejoined after being unbanned {}",
+			relative_time(unban.action_time)
+		));
+	}
+
+	let ticket_restriction_count: i64 = ticket_restricted_users::table
+		.filter(
+			ticket_restricted_users::guild_id
+				.eq(guild_data.guild_id)
+				.and(ticket_restricted_users::user_id.eq(db_user_id)),
+		)
+		.count()
+		.get_result(&mut db_connection)
+		.into_diagnostic()?;
+	if ticket_restriction_count > 0 {
+		alert_reasons.push(String::from("Is restricted from opening tickets"));
+	}
+
+	let new_account_age_days = guild_data
+		.new_account_age_days
+		.filter(|days| Utc::now() - account_created_at < Duration::days((*days).into()));
+	if let Some(days) = new_account_age_days {
+		alert_reasons.push(format!("Account is newer than {} days", days));
+	}
+
+	if alert_reasons.is_empty() {
+		return Ok(());
+	}
+
+	let summary = history_summary(&mut db_connection, guild_data.guild_id, user_id).into_diagnostic()?;
+	let reason_lines: Vec<String> = alert_reasons.iter().map(|reason| format!("- {}", reason)).collect();
+	let mut embed = EmbedBuilder::new()
+		.title("Member Joined")
+		.color(MEMBER_ALERT_COLOR)
+		.field(EmbedFieldBuilder::new("User", format!("{} ({})", user_id.mention(), user_id)).inline())
+		.field(EmbedFieldBuilder::new("Account Created", relative_time(account_created_at)).inline())
+		.field(EmbedFieldBuilder::new("Flagged Because", reason_lines.join("\n")))
+		.field(EmbedFieldBuilder::new(
+			"History",
+			format!(
+				"{}\n[View history]({})",
+				summary,
+				user_history_url(config, &guild_data, user_id)
+			),
+		));
+	if let Ok(timestamp) = Timestamp::from_micros(Utc::now().timestamp_micros()) {
+		embed = embed.timestamp(timestamp);
+	}
+
+	http_client
+		.create_message(alert_channel)
+		.embeds(&[embed.build()])
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+pub async fn handle_member_remove(
+	member_remove: &MemberRemove,
+	http_client: &Client,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let user_id = member_remove.user.id;
+	let Some(guild_data) = record_member_event(member_remove.guild_id, user_id, false, &db_connection_pool)? else {
+		return Ok(());
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	handle_ticket_user_departure(
+		http_client,
+		&mut db_connection,
+		&guild_data,
+		user_id,
+		TicketUserDeparture::Removed,
+	)
+	.await
+}
+
+pub async fn handle_member_ban(
+	ban_add: &BanAdd,
+	http_client: &Client,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let guild: Option<Guild> = guilds::table
+		.find(database_id_from_discord_id(ban_add.guild_id.get()))
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return Ok(());
+	};
+
+	handle_ticket_user_departure(
+		http_client,
+		&mut db_connection,
+		&guild,
+		ban_add.user.id,
+		TicketUserDeparture::Banned,
+	)
+	.await
+}
+
+/// Records a member joining or leaving a set-up guild. Returns the guild's data, or `None` if the guild isn't set up.
+fn record_member_event(
+	guild_id: Id<GuildMarker>,
+	user_id: Id<UserMarker>,
+	joined: bool,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<Option<Guild>> {
+	let Some(account_created_at) = datetime_from_id(user_id) else {
+		bail!("Invalid timestamp in member's user ID: {}", user_id);
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return Ok(None);
+	};
+
+	let new_event = MemberEvent {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		user_id: database_id_from_discord_id(user_id.get()),
+		joined,
+		event_time: Utc::now(),
+		account_created_at,
+	};
+	diesel::insert_into(member_events::table)
+		.values(&new_event)
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+
+	Ok(Some(guild))
+}
+
+fn relative_time(time: DateTime<Utc>) -> String {
+	MentionTimestamp::new(time.timestamp() as u64, Some(TimestampStyle::RelativeTime))
+		.mention()
+		.to_string()
+}
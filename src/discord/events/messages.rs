@@ -0,0 +1,158 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_MESSAGE_DELETE, ModLogActor, ModLogEntry, try_post_mod_log_entry};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::discord::utils::tracked_actions::is_action_tracked;
+use crate::model::{Guild, MessageDeleteAction, TrackedActionType, database_id_from_discord_id};
+use crate::schema::{guilds, message_delete_actions};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::guild::audit_log::AuditLogEntry;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ChannelMarker, UserMarker};
+
+pub async fn handle_message_delete(
+	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(target_user_id) = event_audit_entry.target_id else {
+		bail!("Message deletion has no message author: {:?}", event_audit_entry);
+	};
+	let target_user_id: Id<UserMarker> = target_user_id.cast();
+	let Some(channel_id) = event_audit_entry
+		.options
+		.as_ref()
+		.and_then(|options| options.channel_id)
+	else {
+		bail!("Message deletion has no channel: {:?}", event_audit_entry);
+	};
+
+	record_message_delete(
+		event_audit_entry,
+		Some(target_user_id),
+		channel_id,
+		false,
+		http_client,
+		config,
+		db_connection_pool,
+	)
+	.await
+}
+
+pub async fn handle_bulk_delete(
+	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(channel_id) = event_audit_entry.target_id else {
+		bail!("Bulk message deletion has no channel: {:?}", event_audit_entry);
+	};
+	let channel_id: Id<ChannelMarker> = channel_id.cast();
+
+	record_message_delete(
+		event_audit_entry,
+		None,
+		channel_id,
+		true,
+		http_client,
+		config,
+		db_connection_pool,
+	)
+	.await
+}
+
+async fn record_message_delete(
+	event_audit_entry: &AuditLogEntry,
+	target_user_id: Option<Id<UserMarker>>,
+	channel_id: Id<ChannelMarker>,
+	bulk: bool,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let Some(performing_user_id) = event_audit_entry.user_id else {
+		bail!("Message deletion has no performing user: {:?}", event_audit_entry);
+	};
+	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
+		bail!("Invalid timestamp with message deletion: {:?}", event_audit_entry);
+	};
+	let message_count = event_audit_entry
+		.options
+		.as_ref()
+		.and_then(|options| options.count)
+		.unwrap_or(1);
+	let message_count = i32::try_from(message_count).unwrap_or(i32::MAX);
+
+	let guild = database_id_from_discord_id(guild_id.get());
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
+	let guild_data = match db_guild_result {
+		Ok(Some(guild)) => guild,
+		Ok(None) => return Ok(()),
+		Err(error) => bail!(error),
+	};
+	if !is_action_tracked(&mut db_connection, guild, TrackedActionType::MessageDelete).into_diagnostic()? {
+		return Ok(());
+	}
+
+	let new_message_delete_action = MessageDeleteAction {
+		id: cuid2::create_id(),
+		guild,
+		performing_user: database_id_from_discord_id(performing_user_id.get()),
+		target_user: target_user_id.map(|user_id| database_id_from_discord_id(user_id.get())),
+		channel: database_id_from_discord_id(channel_id.get()),
+		message_count,
+		bulk,
+		action_time,
+		audit_log_entry: database_id_from_discord_id(event_audit_entry.id.get()),
+	};
+
+	let inserted_count = diesel::insert_into(message_delete_actions::table)
+		.values(&new_message_delete_action)
+		.on_conflict(message_delete_actions::audit_log_entry)
+		.do_nothing()
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+	if inserted_count == 0 {
+		return Ok(());
+	}
+
+	let title = match (bulk, message_count) {
+		(true, _) => "Messages Bulk Deleted",
+		(false, 1) => "Message Deleted",
+		(false, _) => "Messages Deleted",
+	};
+	let mod_log_entry = ModLogEntry {
+		title,
+		color: MOD_LOG_COLOR_MESSAGE_DELETE,
+		case_number: None,
+		target_user: target_user_id,
+		actor: ModLogActor::User(performing_user_id),
+		reason: None,
+		expires_at: None,
+		action_time,
+		details: vec![
+			("Channel", channel_id.mention().to_string()),
+			("Messages", message_count.to_string()),
+		],
+	};
+	try_post_mod_log_entry(http_client, config, &guild_data, &mod_log_entry).await;
+
+	Ok(())
+}
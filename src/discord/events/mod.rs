@@ -4,40 +4,157 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
+use crate::model::{VoiceActionType, database_id_from_discord_id};
+use crate::schema::guilds;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use miette::IntoDiagnostic;
 use twilight_http::client::Client;
 use twilight_model::guild::audit_log::{AuditLogChange, AuditLogEntry, AuditLogEventType};
 
 mod automod;
 mod bans;
+mod catch_up;
+mod escalations;
 mod kicks;
+mod members;
+mod messages;
+mod nicknames;
+mod roles;
 mod timeouts;
+mod voice;
+
+pub use automod::handle_automod_execution;
+pub use catch_up::catch_up_audit_logs;
+pub use members::{handle_member_add, handle_member_ban, handle_member_remove};
 
 pub async fn route_events(
 	event_audit_entry: &AuditLogEntry,
 	http_client: &Client,
+	config: &ConfigData,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
 	match event_audit_entry.action_type {
 		AuditLogEventType::AutoModerationBlockMessage => {
-			automod::handle_block(event_audit_entry, db_connection_pool).await?
+			automod::handle_block(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::AutoModerationFlagToChannel => {
+			automod::handle_flag(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
 		}
 		AuditLogEventType::AutoModerationUserCommunicationDisabled => {
-			automod::handle_timeout(event_audit_entry, db_connection_pool).await?
+			automod::handle_timeout(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::MemberBanAdd => {
+			bans::handle_ban(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::MemberBanRemove => {
+			bans::handle_unban(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::MemberDisconnect => {
+			voice::handle_voice_action(
+				event_audit_entry,
+				VoiceActionType::Disconnect,
+				http_client,
+				config,
+				db_connection_pool.clone(),
+			)
+			.await?
+		}
+		AuditLogEventType::MemberKick => {
+			kicks::handle_kick(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::MemberMove => {
+			voice::handle_voice_action(
+				event_audit_entry,
+				VoiceActionType::Move,
+				http_client,
+				config,
+				db_connection_pool.clone(),
+			)
+			.await?
+		}
+		AuditLogEventType::MemberRoleUpdate => {
+			roles::handle_role_update(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
 		}
-		AuditLogEventType::MemberBanAdd => bans::handle_ban(event_audit_entry, http_client, db_connection_pool).await?,
-		AuditLogEventType::MemberBanRemove => bans::handle_unban(event_audit_entry, db_connection_pool).await?,
-		AuditLogEventType::MemberKick => kicks::handle_kick(event_audit_entry, http_client, db_connection_pool).await?,
 		AuditLogEventType::MemberUpdate => {
 			for change in event_audit_entry.changes.iter() {
-				if let AuditLogChange::CommunicationDisabledUntil { new, .. } = change {
-					timeouts::handle_timeout_update(event_audit_entry, new, http_client, &db_connection_pool).await?
+				match change {
+					AuditLogChange::CommunicationDisabledUntil { new, .. } => {
+						timeouts::handle_timeout_update(
+							event_audit_entry,
+							new,
+							http_client,
+							config,
+							&db_connection_pool,
+						)
+						.await?
+					}
+					AuditLogChange::Nick { new, old } => {
+						nicknames::handle_nickname_change(
+							event_audit_entry,
+							old,
+							new,
+							http_client,
+							config,
+							&db_connection_pool,
+						)
+						.await?
+					}
+					_ => (),
 				}
 			}
 		}
+		AuditLogEventType::MessageBulkDelete => {
+			messages::handle_bulk_delete(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
+		AuditLogEventType::MessageDelete => {
+			messages::handle_message_delete(event_audit_entry, http_client, config, db_connection_pool.clone()).await?
+		}
 		_ => (),
 	}
 
+	if let Some((trigger_type, target_user_id)) = escalations::escalation_trigger(event_audit_entry) {
+		let escalation_result = escalations::evaluate_escalations(
+			event_audit_entry,
+			trigger_type,
+			target_user_id,
+			http_client,
+			config,
+			&db_connection_pool,
+		)
+		.await;
+		if let Err(error) = escalation_result {
+			tracing::warn!(source = ?error, "Failed to evaluate escalation rules");
+		}
+	}
+
+	record_processed_entry(event_audit_entry, &db_connection_pool)
+}
+
+/// Marks the audit log entry as the newest one processed for its guild, unless a newer one has already been processed
+fn record_processed_entry(
+	event_audit_entry: &AuditLogEntry,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let db_entry_id = database_id_from_discord_id(event_audit_entry.id.get());
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	diesel::update(guilds::table)
+		.filter(
+			guilds::guild_id.eq(db_guild_id).and(
+				guilds::last_audit_log_entry
+					.is_null()
+					.or(guilds::last_audit_log_entry.lt(db_entry_id)),
+			),
+		)
+		.set(guilds::last_audit_log_entry.eq(db_entry_id))
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+
 	Ok(())
 }
@@ -0,0 +1,110 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_MEMBER_UPDATE, ModLogActor, ModLogEntry, try_post_mod_log_entry};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::discord::utils::tracked_actions::is_action_tracked;
+use crate::model::{Guild, NicknameChangeAction, TrackedActionType, database_id_from_discord_id};
+use crate::schema::{guilds, nickname_change_actions};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::guild::audit_log::AuditLogEntry;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+pub async fn handle_nickname_change(
+	event_audit_entry: &AuditLogEntry,
+	old_nickname: &Option<String>,
+	new_nickname: &Option<String>,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let Some(performing_user_id) = event_audit_entry.user_id else {
+		bail!("Nickname change has no performing user: {:?}", event_audit_entry);
+	};
+	let Some(target_user_id) = event_audit_entry.target_id else {
+		bail!("Nickname change has no target user: {:?}", event_audit_entry);
+	};
+	let target_user_id: Id<UserMarker> = target_user_id.cast();
+
+	// Users changing their own nicknames aren't moderation actions
+	if performing_user_id == target_user_id {
+		return Ok(());
+	}
+
+	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
+		bail!("Invalid timestamp with nickname change: {:?}", event_audit_entry);
+	};
+
+	let guild = database_id_from_discord_id(guild_id.get());
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
+	let guild_data = match db_guild_result {
+		Ok(Some(guild)) => guild,
+		Ok(None) => return Ok(()),
+		Err(error) => bail!(error),
+	};
+	if !is_action_tracked(&mut db_connection, guild, TrackedActionType::NicknameChange).into_diagnostic()? {
+		return Ok(());
+	}
+
+	let new_nickname_change_action = NicknameChangeAction {
+		id: cuid2::create_id(),
+		guild,
+		performing_user: database_id_from_discord_id(performing_user_id.get()),
+		target_user: database_id_from_discord_id(target_user_id.get()),
+		old_nickname: old_nickname.clone(),
+		new_nickname: new_nickname.clone(),
+		action_time,
+		reason: event_audit_entry.reason.clone().unwrap_or_default(),
+		audit_log_entry: database_id_from_discord_id(event_audit_entry.id.get()),
+	};
+
+	let inserted_count = diesel::insert_into(nickname_change_actions::table)
+		.values(&new_nickname_change_action)
+		.on_conflict(nickname_change_actions::audit_log_entry)
+		.do_nothing()
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+	if inserted_count == 0 {
+		return Ok(());
+	}
+
+	let nickname_text = |nickname: &Option<String>| match nickname {
+		Some(nickname) => nickname.clone(),
+		None => String::from("*None*"),
+	};
+	let mod_log_entry = ModLogEntry {
+		title: if new_nickname.is_some() {
+			"Nickname Changed"
+		} else {
+			"Nickname Reset"
+		},
+		color: MOD_LOG_COLOR_MEMBER_UPDATE,
+		case_number: None,
+		target_user: Some(target_user_id),
+		actor: ModLogActor::User(performing_user_id),
+		reason: Some(&new_nickname_change_action.reason),
+		expires_at: None,
+		action_time,
+		details: vec![
+			("Old Nickname", nickname_text(old_nickname)),
+			("New Nickname", nickname_text(new_nickname)),
+		],
+	};
+	try_post_mod_log_entry(http_client, config, &guild_data, &mod_log_entry).await;
+
+	Ok(())
+}
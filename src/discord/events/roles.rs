@@ -0,0 +1,131 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_MEMBER_UPDATE, ModLogActor, ModLogEntry, try_post_mod_log_entry};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::discord::utils::tracked_actions::is_action_tracked;
+use crate::model::{Guild, RoleChangeAction, TrackedActionType, database_id_from_discord_id};
+use crate::schema::{guilds, role_change_actions};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::guild::audit_log::{AuditLogChange, AuditLogEntry};
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+pub async fn handle_role_update(
+	event_audit_entry: &AuditLogEntry,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let Some(performing_user_id) = event_audit_entry.user_id else {
+		bail!("Role update has no performing user: {:?}", event_audit_entry);
+	};
+	let Some(target_user_id) = event_audit_entry.target_id else {
+		bail!("Role update has no target user: {:?}", event_audit_entry);
+	};
+	let target_user_id: Id<UserMarker> = target_user_id.cast();
+
+	// Users picking their own roles (e.g. through onboarding) aren't moderation actions
+	if performing_user_id == target_user_id {
+		return Ok(());
+	}
+
+	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
+		bail!("Invalid timestamp with role update: {:?}", event_audit_entry);
+	};
+
+	let guild = database_id_from_discord_id(guild_id.get());
+	let performing_user = database_id_from_discord_id(performing_user_id.get());
+	let target_user = database_id_from_discord_id(target_user_id.get());
+	let audit_log_entry = database_id_from_discord_id(event_audit_entry.id.get());
+	let reason = event_audit_entry.reason.clone().unwrap_or_default();
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
+	let guild_data = match db_guild_result {
+		Ok(Some(guild)) => guild,
+		Ok(None) => return Ok(()),
+		Err(error) => bail!(error),
+	};
+	if !is_action_tracked(&mut db_connection, guild, TrackedActionType::RoleChange).into_diagnostic()? {
+		return Ok(());
+	}
+
+	let mut new_role_change_actions: Vec<RoleChangeAction> = Vec::new();
+	for change in event_audit_entry.changes.iter() {
+		let (roles, added) = match change {
+			AuditLogChange::RoleAdded { new, .. } => (new, true),
+			AuditLogChange::RoleRemoved { new, .. } => (new, false),
+			_ => continue,
+		};
+		for role in roles.iter() {
+			new_role_change_actions.push(RoleChangeAction {
+				id: cuid2::create_id(),
+				guild,
+				performing_user,
+				target_user,
+				role: database_id_from_discord_id(role.id.get()),
+				added,
+				action_time,
+				reason: reason.clone(),
+				audit_log_entry,
+			});
+		}
+	}
+	if new_role_change_actions.is_empty() {
+		return Ok(());
+	}
+
+	let inserted_count = diesel::insert_into(role_change_actions::table)
+		.values(&new_role_change_actions)
+		.on_conflict((role_change_actions::audit_log_entry, role_change_actions::role))
+		.do_nothing()
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+	if inserted_count == 0 {
+		return Ok(());
+	}
+
+	let role_list = |added: bool| -> Option<String> {
+		let roles: Vec<String> = new_role_change_actions
+			.iter()
+			.filter(|action| action.added == added)
+			.map(|action| action.get_role().mention().to_string())
+			.collect();
+		if roles.is_empty() { None } else { Some(roles.join(", ")) }
+	};
+	let mut details = Vec::new();
+	if let Some(added_roles) = role_list(true) {
+		details.push(("Roles Added", added_roles));
+	}
+	if let Some(removed_roles) = role_list(false) {
+		details.push(("Roles Removed", removed_roles));
+	}
+
+	let mod_log_entry = ModLogEntry {
+		title: "Roles Updated",
+		color: MOD_LOG_COLOR_MEMBER_UPDATE,
+		case_number: None,
+		target_user: Some(target_user_id),
+		actor: ModLogActor::User(performing_user_id),
+		reason: Some(&reason),
+		expires_at: None,
+		action_time,
+		details,
+	};
+	try_post_mod_log_entry(http_client, config, &guild_data, &mod_log_entry).await;
+
+	Ok(())
+}
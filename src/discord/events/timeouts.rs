@@ -4,14 +4,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
+use crate::discord::utils::action_reasons::provide_reason_components;
+use crate::discord::utils::cases::assign_case_number;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_TIMEOUT, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{COMMAND_ACTION_CLAIM_WINDOW, post_action_to_mod_log};
+use crate::discord::utils::reason_policy::{check_reason_policy, reason_complaint_message};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
 use crate::discord::utils::timestamp::{datetime_from_id, datetime_from_timestamp};
-use crate::model::{Guild, TimeoutAction, database_id_from_discord_id};
+use crate::model::{Guild, ModerationActionType, ServerRule, TimeoutAction, database_id_from_discord_id};
 use crate::schema::{guilds, timeout_actions};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::{IntoDiagnostic, bail};
 use twilight_http::client::Client;
-use twilight_mention::fmt::Mention;
 use twilight_model::channel::message::AllowedMentions;
 use twilight_model::guild::audit_log::AuditLogEntry;
 use twilight_model::id::Id;
@@ -22,6 +28,7 @@ pub async fn handle_timeout_update(
 	event_audit_entry: &AuditLogEntry,
 	expires_at: &Option<Timestamp>,
 	http_client: &Client,
+	config: &ConfigData,
 	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
 ) -> miette::Result<()> {
 	let Some(guild_id) = event_audit_entry.guild_id else {
@@ -60,6 +67,29 @@ pub async fn handle_timeout_update(
 		Err(error) => bail!(error),
 	};
 
+	if timeout_until.is_some() {
+		let claimed_count = diesel::update(timeout_actions::table)
+			.filter(
+				timeout_actions::guild
+					.eq(guild)
+					.and(timeout_actions::target_user.eq(target_user))
+					.and(timeout_actions::timeout_until.is_not_null())
+					.and(
+						timeout_actions::via_bot_command
+							.eq(true)
+							.or(timeout_actions::escalation_rule.is_not_null()),
+					)
+					.and(timeout_actions::audit_log_entry.is_null())
+					.and(timeout_actions::action_time.gt(action_time - COMMAND_ACTION_CLAIM_WINDOW)),
+			)
+			.set(timeout_actions::audit_log_entry.eq(database_id_from_discord_id(event_audit_entry.id.get())))
+			.execute(&mut db_connection)
+			.into_diagnostic()?;
+		if claimed_count > 0 {
+			return Ok(());
+		}
+	}
+
 	let new_timeout_action = TimeoutAction {
 		id: cuid2::create_id(),
 		guild,
@@ -68,28 +98,113 @@ pub async fn handle_timeout_update(
 		action_time,
 		timeout_until,
 		reason,
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: Some(database_id_from_discord_id(event_audit_entry.id.get())),
+		evidence: None,
+		via_bot_command: false,
+		escalation_rule: None,
 	};
 
-	diesel::insert_into(timeout_actions::table)
-		.values(new_timeout_action)
-		.execute(&mut db_connection)
-		.into_diagnostic()?;
+	let recorded: QueryResult<Option<(i32, Vec<ServerRule>)>> = db_connection.transaction(|db_connection| {
+		let inserted_count = diesel::insert_into(timeout_actions::table)
+			.values(&new_timeout_action)
+			.on_conflict(timeout_actions::audit_log_entry)
+			.do_nothing()
+			.execute(db_connection)?;
+		if inserted_count == 0 {
+			return Ok(None);
+		}
+
+		let case_number = assign_case_number(
+			db_connection,
+			guild,
+			ModerationActionType::Timeout,
+			&new_timeout_action.id,
+		)?;
+		let rules = if timeout_until.is_some() {
+			link_rules_from_reason(
+				db_connection,
+				guild,
+				ModerationActionType::Timeout,
+				&new_timeout_action.id,
+				action_time,
+				&new_timeout_action.reason,
+				None,
+			)?
+		} else {
+			Vec::new()
+		};
+
+		Ok(Some((case_number, rules)))
+	});
+	let Some((case_number, rules)) = recorded.into_diagnostic()? else {
+		return Ok(());
+	};
+
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: if timeout_until.is_some() {
+			"Timeout"
+		} else {
+			"Timeout Removed"
+		},
+		color: MOD_LOG_COLOR_TIMEOUT,
+		case_number: Some(case_number),
+		target_user: Some(target_user_id),
+		actor: ModLogActor::User(performing_user_id),
+		reason: Some(&new_timeout_action.reason),
+		expires_at: timeout_until,
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		&mut db_connection,
+		&guild_data,
+		ModerationActionType::Timeout,
+		&new_timeout_action.id,
+		&mod_log_entry,
+	)
+	.await;
 
 	if timeout_until.is_some() {
 		if let Some(complain_channel_id) = guild_data.get_action_reason_complain_channel() {
-			let complain_message = format!(
-				"{0}, {1} has been timed out (or the timeout duration was updated), but you didn't provide a reason. Please write a note about {1} as soon as you can.",
-				performing_user_id.mention(),
-				target_user_id.mention()
-			);
-			let mut allowed_mentions = AllowedMentions::default();
-			allowed_mentions.users.push(performing_user_id);
-			http_client
-				.create_message(complain_channel_id)
-				.content(&complain_message)
-				.allowed_mentions(Some(&allowed_mentions))
-				.await
-				.into_diagnostic()?;
+			let policy_failure = check_reason_policy(
+				&mut db_connection,
+				http_client,
+				guild_id,
+				performing_user_id,
+				&new_timeout_action.reason,
+			)
+			.await?;
+			if let Some(policy_failure) = policy_failure {
+				let complain_message = reason_complaint_message(
+					performing_user_id,
+					target_user_id,
+					"has been timed out (or the timeout duration was updated)",
+					&policy_failure,
+				);
+				let mut allowed_mentions = AllowedMentions::default();
+				allowed_mentions.users.push(performing_user_id);
+				http_client
+					.create_message(complain_channel_id)
+					.content(&complain_message)
+					.allowed_mentions(Some(&allowed_mentions))
+					.components(&provide_reason_components(
+						ModerationActionType::Timeout,
+						&new_timeout_action.id,
+					))
+					.await
+					.into_diagnostic()?;
+			}
 		}
 	}
 
@@ -0,0 +1,99 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_VOICE, ModLogActor, ModLogEntry, try_post_mod_log_entry};
+use crate::discord::utils::timestamp::datetime_from_id;
+use crate::discord::utils::tracked_actions::is_action_tracked;
+use crate::model::{Guild, TrackedActionType, VoiceAction, VoiceActionType, database_id_from_discord_id};
+use crate::schema::{guilds, voice_actions};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::guild::audit_log::AuditLogEntry;
+
+pub async fn handle_voice_action(
+	event_audit_entry: &AuditLogEntry,
+	action_type: VoiceActionType,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = event_audit_entry.guild_id else {
+		return Ok(());
+	};
+	let Some(performing_user_id) = event_audit_entry.user_id else {
+		bail!("Voice action has no performing user: {:?}", event_audit_entry);
+	};
+	let Some(action_time) = datetime_from_id(event_audit_entry.id) else {
+		bail!("Invalid timestamp with voice action: {:?}", event_audit_entry);
+	};
+	let options = event_audit_entry.options.as_ref();
+	let channel_id = options.and_then(|options| options.channel_id);
+	let member_count = options.and_then(|options| options.count).unwrap_or(1);
+	let member_count = i32::try_from(member_count).unwrap_or(i32::MAX);
+
+	let (tracked_action_type, title) = match action_type {
+		VoiceActionType::Disconnect => (TrackedActionType::VoiceDisconnect, "Voice Disconnect"),
+		VoiceActionType::Move => (TrackedActionType::VoiceMove, "Voice Move"),
+	};
+
+	let guild = database_id_from_discord_id(guild_id.get());
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let db_guild_result: QueryResult<Option<Guild>> = guilds::table.find(guild).first(&mut db_connection).optional();
+	let guild_data = match db_guild_result {
+		Ok(Some(guild)) => guild,
+		Ok(None) => return Ok(()),
+		Err(error) => bail!(error),
+	};
+	if !is_action_tracked(&mut db_connection, guild, tracked_action_type).into_diagnostic()? {
+		return Ok(());
+	}
+
+	let new_voice_action = VoiceAction {
+		id: cuid2::create_id(),
+		guild,
+		performing_user: database_id_from_discord_id(performing_user_id.get()),
+		action_type,
+		channel: channel_id.map(|channel_id| database_id_from_discord_id(channel_id.get())),
+		member_count,
+		action_time,
+		audit_log_entry: database_id_from_discord_id(event_audit_entry.id.get()),
+	};
+
+	let inserted_count = diesel::insert_into(voice_actions::table)
+		.values(&new_voice_action)
+		.on_conflict(voice_actions::audit_log_entry)
+		.do_nothing()
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+	if inserted_count == 0 {
+		return Ok(());
+	}
+
+	let mut details = vec![("Members", member_count.to_string())];
+	if let Some(channel_id) = channel_id {
+		details.push(("Moved To", channel_id.mention().to_string()));
+	}
+	let mod_log_entry = ModLogEntry {
+		title,
+		color: MOD_LOG_COLOR_VOICE,
+		case_number: None,
+		target_user: None,
+		actor: ModLogActor::User(performing_user_id),
+		reason: None,
+		expires_at: None,
+		action_time,
+		details,
+	};
+	try_post_mod_log_entry(http_client, config, &guild_data, &mod_log_entry).await;
+
+	Ok(())
+}
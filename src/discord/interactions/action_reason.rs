@@ -0,0 +1,249 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::action_reasons::{MAX_REASON_LENGTH, action_type_from_custom_id_name, amend_action_reason};
+use crate::discord::utils::mod_log::update_mod_log_reason;
+use crate::discord::utils::permissions::member_is_staff;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::channel::message::component::{ActionRow, Component, TextInput, TextInputStyle};
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+pub async fn route_action_reason_interaction(
+	interaction: &InteractionCreate,
+	interaction_data: &MessageComponentInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let (Some(action_id), Some(action_type)) = (custom_id_path.get(1), custom_id_path.get(2)) else {
+		bail!(
+			"Invalid custom ID for action reason: {} (parts: {:?})",
+			interaction_data.custom_id,
+			custom_id_path
+		);
+	};
+	if action_type_from_custom_id_name(action_type).is_none() {
+		bail!(
+			"Invalid action type for action reason (custom ID parts: {:?})",
+			custom_id_path
+		);
+	}
+
+	if !interaction_user_is_staff(interaction, http_client, application_id, db_connection_pool).await? {
+		return Ok(());
+	}
+
+	let reason_input = Component::TextInput(TextInput {
+		custom_id: String::from("reason"),
+		label: String::from("Reason"),
+		max_length: Some(MAX_REASON_LENGTH),
+		min_length: None,
+		placeholder: None,
+		required: Some(true),
+		style: TextInputStyle::Paragraph,
+		value: None,
+	});
+	let reason_input_row = Component::ActionRow(ActionRow {
+		components: vec![reason_input],
+	});
+	let response = InteractionResponseDataBuilder::new()
+		.custom_id(format!("action_reason/{}/{}", action_id, action_type))
+		.title("Provide Reason")
+		.components(vec![reason_input_row])
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::Modal,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+pub async fn route_action_reason_modal(
+	interaction: &InteractionCreate,
+	modal_data: &ModalInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let (Some(action_id), Some(action_type_name)) = (custom_id_path.get(1), custom_id_path.get(2)) else {
+		bail!(
+			"Invalid custom ID for action reason modal (parts: {:?})",
+			custom_id_path
+		);
+	};
+	let Some(action_type) = action_type_from_custom_id_name(action_type_name) else {
+		bail!(
+			"Invalid action type for action reason modal (custom ID parts: {:?})",
+			custom_id_path
+		);
+	};
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Action reason modal submitted outside of a guild");
+	};
+	let Some(amending_user) = interaction.author_id() else {
+		bail!("Action reason modal submitted by a non-user");
+	};
+
+	let mut reason: Option<String> = None;
+	for row in modal_data.components.iter() {
+		for component in row.components.iter() {
+			if component.custom_id.as_str() == "reason" {
+				reason = component.value.clone();
+			}
+		}
+	}
+
+	let interaction_client = http_client.interaction(application_id);
+	let Some(reason) = reason.filter(|reason| !reason.is_empty()) else {
+		let response = InteractionResponseDataBuilder::new()
+			.content("No reason was provided.")
+			.flags(MessageFlags::EPHEMERAL)
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	};
+
+	if !interaction_user_is_staff(interaction, http_client, application_id, db_connection_pool.clone()).await? {
+		return Ok(());
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let amended_action = amend_action_reason(
+		&mut db_connection,
+		database_id_from_discord_id(guild_id.get()),
+		action_type,
+		action_id,
+		&reason,
+		database_id_from_discord_id(amending_user.get()),
+		Utc::now(),
+	)
+	.into_diagnostic()?;
+
+	let Some(amended_action) = amended_action else {
+		let response = InteractionResponseDataBuilder::new()
+			.content("The action this reason is for no longer exists.")
+			.flags(MessageFlags::EPHEMERAL)
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	};
+
+	if let (Some(mod_log_channel), Some(mod_log_message)) =
+		(amended_action.mod_log_channel, amended_action.mod_log_message)
+	{
+		let update_result =
+			update_mod_log_reason(http_client, mod_log_channel, mod_log_message, &reason, amending_user).await;
+		if let Err(error) = update_result {
+			tracing::warn!(source = ?error, "Couldn't update the mod log entry for an amended action reason");
+		}
+	}
+
+	let resolved_message = format!(
+		"✅ {}'s {} of {} now has a reason, provided by {}: {}",
+		amended_action.moderator.mention(),
+		action_type.to_string().to_lowercase(),
+		amended_action.target_user.mention(),
+		amending_user.mention(),
+		reason
+	);
+	let response = InteractionResponseDataBuilder::new()
+		.content(resolved_message)
+		.allowed_mentions(AllowedMentions::default())
+		.components(Vec::new())
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::UpdateMessage,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+/// Checks whether the user who triggered the interaction is staff for the guild, responding to the interaction with
+/// an explanation if not.
+async fn interaction_user_is_staff(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<bool> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Action reason interaction used outside of a guild");
+	};
+	let Some(member) = interaction.member.as_ref() else {
+		bail!("Action reason interaction used by a non-member");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let response_content = match guild {
+		Some(guild) if member_is_staff(&guild, &member.roles) => return Ok(true),
+		Some(_) => "Only staff can provide reasons for moderation actions.",
+		None => NOT_SET_UP_FOR_GUILD,
+	};
+	let response = InteractionResponseDataBuilder::new()
+		.content(response_content)
+		.flags(MessageFlags::EPHEMERAL)
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(false)
+}
@@ -6,14 +6,16 @@
 
 use crate::discord::state::create_ticket::{BuiltInCategory, CreateTicketState, CreateTicketStates};
 use crate::discord::utils::invites::invite_code_from_url;
+use crate::discord::utils::partnerships::partnership_decision_components;
 use crate::discord::utils::tickets::{MAX_TICKET_TITLE_LENGTH, UserMessageAuthor, staff_message, user_message};
 use crate::discord::utils::timestamp::timestamp_from_id;
 use crate::model::{
-	CustomCategory, FormQuestion, Guild, PendingPartnership, Ticket, TicketMessage, TicketRestrictedUser,
+	CustomCategory, FormQuestion, Guild, Partnership, PendingPartnership, Ticket, TicketMessage, TicketRestrictedUser,
 	database_id_from_discord_id,
 };
 use crate::schema::{
-	custom_categories, form_questions, guilds, pending_partnerships, ticket_messages, ticket_restricted_users, tickets,
+	custom_categories, form_questions, guilds, partnerships, pending_partnerships, ticket_messages,
+	ticket_restricted_users, tickets,
 };
 use chrono::Utc;
 use diesel::prelude::*;
@@ -34,6 +36,7 @@ use twilight_model::channel::message::component::{
 	ActionRow, Button, ButtonStyle, Component, SelectMenu, SelectMenuOption, SelectMenuType, TextInput, TextInputStyle,
 };
 use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::invite::Invite;
 use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
 use twilight_model::id::Id;
 use twilight_model::id::marker::ApplicationMarker;
@@ -42,6 +45,9 @@ use type_map::concurrent::TypeMap;
 
 const TICKET_CREATION_EXPIRED: &str = "Ticket creation expired.";
 
+/// The value of the partnership select option for users who want to enter an invite instead of choosing a partnership
+const PARTNERSHIP_INVITE_OPTION: &str = "invite";
+
 pub async fn route_create_ticket_interaction(
 	interaction: &InteractionCreate,
 	interaction_data: &MessageComponentInteractionData,
@@ -82,6 +88,18 @@ pub async fn route_create_ticket_interaction(
 			)
 			.await?
 		}
+		"set_partnership" => {
+			set_partnership(
+				interaction,
+				interaction_data,
+				id,
+				http_client,
+				application_id,
+				db_connection_pool,
+				bot_state,
+			)
+			.await?
+		}
 		"start" => {
 			ensure!(id.is_empty(), "Unexpected ID when starting ticket creation");
 			create_ticket(interaction, http_client, application_id, db_connection_pool, bot_state).await?;
@@ -377,6 +395,8 @@ async fn set_category(
 		create_ticket_state.built_in_category = None;
 		create_ticket_state.custom_category_id = Some(category_id.clone());
 	}
+	create_ticket_state.partnership_id = None;
+	let selected_built_in_category = create_ticket_state.built_in_category;
 
 	drop(state);
 
@@ -411,7 +431,18 @@ async fn set_category(
 		return Ok(());
 	}
 
-	let updated_components = category_select_components(create_id, selectable_categories, false, Some(category_id));
+	let mut updated_components = category_select_components(create_id, selectable_categories, false, Some(category_id));
+	if let Some(BuiltInCategory::ExistingPartner) = selected_built_in_category {
+		let Some(user_id) = interaction.author_id() else {
+			bail!("Ticket creation interaction isn't from a user");
+		};
+		let db_user_id = database_id_from_discord_id(user_id.get());
+		let represented_partnerships = partnerships_represented_by_user(db_guild_id, db_user_id, &mut db_connection)?;
+		if !represented_partnerships.is_empty() {
+			let partnership_select = partnership_select_component(create_id, &represented_partnerships, None);
+			updated_components.insert(1, partnership_select);
+		}
+	}
 	let response = InteractionResponseDataBuilder::new()
 		.components(updated_components)
 		.build();
@@ -427,6 +458,152 @@ async fn set_category(
 	Ok(())
 }
 
+async fn set_partnership(
+	interaction: &InteractionCreate,
+	interaction_data: &MessageComponentInteractionData,
+	create_id: &str,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+	bot_state: Arc<RwLock<TypeMap>>,
+) -> miette::Result<()> {
+	let Some(selected_value) = interaction_data.values.first() else {
+		bail!("Missing partnership selection handling ticket creation event");
+	};
+	let partnership_id = if selected_value == PARTNERSHIP_INVITE_OPTION {
+		None
+	} else {
+		Some(selected_value.clone())
+	};
+
+	let interaction_client = http_client.interaction(application_id);
+	let state_updated = {
+		let mut state = bot_state.write().await;
+		let create_ticket_state = state
+			.get_mut::<CreateTicketStates>()
+			.and_then(|create_ticket_states| create_ticket_states.states.get_mut(create_id));
+		match create_ticket_state {
+			Some(create_ticket_state) => {
+				create_ticket_state.partnership_id = partnership_id;
+				true
+			}
+			None => false,
+		}
+	};
+	if !state_updated {
+		let response = InteractionResponseDataBuilder::new()
+			.content(TICKET_CREATION_EXPIRED)
+			.components(Vec::new())
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::UpdateMessage,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	}
+
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Ticket creation interaction moved outside guild");
+	};
+	let Some(user_id) = interaction.author_id() else {
+		bail!("Ticket creation interaction isn't from a user");
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let db_user_id = database_id_from_discord_id(user_id.get());
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		bail!("In ticket creation flow, guild is no longer set up");
+	};
+
+	let selectable_categories = selectable_categories_for_guild(&guild, &mut db_connection)?;
+	let category_id = format!("default/{}", BuiltInCategory::ExistingPartner.as_id());
+	let mut updated_components =
+		category_select_components(create_id, selectable_categories, false, Some(&category_id));
+	let represented_partnerships = partnerships_represented_by_user(db_guild_id, db_user_id, &mut db_connection)?;
+	let partnership_select =
+		partnership_select_component(create_id, &represented_partnerships, Some(selected_value.as_str()));
+	updated_components.insert(1, partnership_select);
+
+	let response = InteractionResponseDataBuilder::new()
+		.components(updated_components)
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::UpdateMessage,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+fn partnerships_represented_by_user(
+	db_guild_id: i64,
+	db_user_id: i64,
+	db_connection: &mut PgConnection,
+) -> miette::Result<Vec<Partnership>> {
+	partnerships::table
+		.filter(
+			partnerships::guild
+				.eq(db_guild_id)
+				.and(partnerships::representative.eq(db_user_id)),
+		)
+		.order(partnerships::partner_name.asc())
+		// Select menus hold up to 25 options, and one is taken by the option to enter an invite instead
+		.limit(24)
+		.load(db_connection)
+		.into_diagnostic()
+}
+
+fn partnership_select_component(
+	create_id: &str,
+	represented_partnerships: &[Partnership],
+	selected_value: Option<&str>,
+) -> Component {
+	let mut partnership_options: Vec<SelectMenuOption> = represented_partnerships
+		.iter()
+		.map(|partnership| SelectMenuOption {
+			default: Some(partnership.id.as_str()) == selected_value,
+			description: None,
+			emoji: None,
+			label: partnership.partner_name.clone(),
+			value: partnership.id.clone(),
+		})
+		.collect();
+	partnership_options.push(SelectMenuOption {
+		default: Some(PARTNERSHIP_INVITE_OPTION) == selected_value,
+		description: Some(String::from("Enter the server's invite URL instead")),
+		emoji: None,
+		label: String::from("Another server"),
+		value: String::from(PARTNERSHIP_INVITE_OPTION),
+	});
+	let partnership_select_menu = SelectMenu {
+		channel_types: None,
+		custom_id: format!("create_ticket/{}/set_partnership", create_id),
+		default_values: None,
+		disabled: false,
+		kind: SelectMenuType::Text,
+		max_values: None,
+		min_values: None,
+		options: Some(partnership_options),
+		placeholder: Some(String::from("Partner server")),
+	};
+	Component::ActionRow(ActionRow {
+		components: vec![Component::SelectMenu(partnership_select_menu)],
+	})
+}
+
 async fn confirm_category(
 	interaction: &InteractionCreate,
 	create_id: &str,
@@ -437,7 +614,7 @@ async fn confirm_category(
 ) -> miette::Result<()> {
 	let interaction_client = http_client.interaction(application_id);
 
-	let (selected_built_in_category, selected_custom_category) = {
+	let (selected_built_in_category, selected_custom_category, selected_partnership) = {
 		let state = bot_state.read().await;
 		let Some(create_ticket_states) = state.get::<CreateTicketStates>() else {
 			bail!("Confirming category when no ticket creation states have been created.");
@@ -460,6 +637,7 @@ async fn confirm_category(
 		(
 			create_ticket_state.built_in_category,
 			create_ticket_state.custom_category_id.clone(),
+			create_ticket_state.partnership_id.clone(),
 		)
 	};
 
@@ -493,8 +671,14 @@ async fn confirm_category(
 		_ => (String::new(), None),
 	};
 
+	let needs_invite = match selected_built_in_category {
+		Some(BuiltInCategory::NewPartner) => true,
+		Some(BuiltInCategory::ExistingPartner) => selected_partnership.is_none(),
+		_ => false,
+	};
+
 	let mut components: Vec<Component> = Vec::new();
-	if let Some(BuiltInCategory::NewPartner) = selected_built_in_category {
+	if needs_invite {
 		let invite_input = Component::TextInput(TextInput {
 			custom_id: String::from("invite_url"),
 			label: String::from("Server invite URL"),
@@ -533,8 +717,8 @@ async fn confirm_category(
 				.order(form_questions::form_position.asc())
 				.load(&mut db_connection)
 				.into_diagnostic()?;
-			let max_separate_questions = if let Some(BuiltInCategory::NewPartner) = selected_built_in_category {
-				// This category has an extra field for the invite URL, which restricts the number of questions we can show as separate fields in the embed.
+			let max_separate_questions = if needs_invite {
+				// There's an extra field for the invite URL, which restricts the number of questions we can show as separate fields in the embed.
 				3
 			} else {
 				4
@@ -621,6 +805,90 @@ fn try_again_text(ticket_title: &str, ticket_message: &str) -> String {
 	)
 }
 
+/// Looks up the invite a user submitted with a partner ticket. If the invite can't be used, responds to the
+/// interaction explaining why and returns `None`.
+async fn get_submitted_invite(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	invite_url: &str,
+	ticket_title: &str,
+	ticket_message: &str,
+) -> miette::Result<Option<Invite>> {
+	let interaction_client = http_client.interaction(application_id);
+
+	let Some(invite_code) = invite_code_from_url(invite_url) else {
+		let response_message = format!(
+			"The invite URL you provided is not a valid invite.\n{}",
+			try_again_text(ticket_title, ticket_message)
+		);
+		let response = InteractionResponseDataBuilder::new()
+			.content(response_message)
+			.components(Vec::new())
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::UpdateMessage,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(None);
+	};
+
+	let invite_data = http_client.invite(&invite_code).with_expiration().await;
+	if let Err(invite_error) = &invite_data {
+		if let ErrorType::Response {
+			status: StatusCode::NOT_FOUND,
+			..
+		} = invite_error.kind()
+		{
+			let response_message = format!(
+				"Discord doesn't recognize that invite code.\n{}",
+				try_again_text(ticket_title, ticket_message)
+			);
+			let response = InteractionResponseDataBuilder::new()
+				.content(response_message)
+				.components(Vec::new())
+				.build();
+			let response = InteractionResponse {
+				kind: InteractionResponseType::UpdateMessage,
+				data: Some(response),
+			};
+			interaction_client
+				.create_response(interaction.id, &interaction.token, &response)
+				.await
+				.into_diagnostic()?;
+			return Ok(None);
+		}
+	}
+	let invite_data = invite_data.into_diagnostic()?;
+	let invite_data = invite_data.model().await.into_diagnostic()?;
+
+	if invite_data.guild.is_none() {
+		let response_message = format!(
+			"The invite you provided isn't for a guild.\n{}",
+			try_again_text(ticket_title, ticket_message)
+		);
+		let response = InteractionResponseDataBuilder::new()
+			.content(response_message)
+			.components(Vec::new())
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::UpdateMessage,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(None);
+	}
+
+	Ok(Some(invite_data))
+}
+
 struct QuestionAnswer {
 	question: String,
 	position: i32,
@@ -746,80 +1014,29 @@ async fn handle_message_modal_data(
 		create_ticket_state
 	};
 
-	let (ticket_message, invite_data) = match create_ticket_state.built_in_category {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Create ticket workflow moved outside of a guild");
+	};
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+
+	let (ticket_message, invite_data, partnership) = match create_ticket_state.built_in_category {
 		Some(BuiltInCategory::NewPartner) => {
 			let Some(invite_url) = &invite_url else {
 				bail!("Invite URL not entered on new partner ticket");
 			};
-
-			let Some(invite_code) = invite_code_from_url(invite_url) else {
-				let response_message = format!(
-					"The invite URL you provided is not a valid invite.\n{}",
-					try_again_text(&ticket_title, &ticket_message)
-				);
-				let response = InteractionResponseDataBuilder::new()
-					.content(response_message)
-					.components(Vec::new())
-					.build();
-				let response = InteractionResponse {
-					kind: InteractionResponseType::UpdateMessage,
-					data: Some(response),
-				};
-				interaction_client
-					.create_response(interaction.id, &interaction.token, &response)
-					.await
-					.into_diagnostic()?;
+			let Some(invite_data) = get_submitted_invite(
+				interaction,
+				http_client,
+				application_id,
+				invite_url,
+				&ticket_title,
+				&ticket_message,
+			)
+			.await?
+			else {
 				return Ok(());
 			};
 
-			let invite_data = http_client.invite(&invite_code).with_expiration().await;
-			if let Err(invite_error) = &invite_data {
-				if let ErrorType::Response {
-					status: StatusCode::NOT_FOUND,
-					..
-				} = invite_error.kind()
-				{
-					let response_message = format!(
-						"Discord doesn't recognize that invite code.\n{}",
-						try_again_text(&ticket_title, &ticket_message)
-					);
-					let response = InteractionResponseDataBuilder::new()
-						.content(response_message)
-						.components(Vec::new())
-						.build();
-					let response = InteractionResponse {
-						kind: InteractionResponseType::UpdateMessage,
-						data: Some(response),
-					};
-					interaction_client
-						.create_response(interaction.id, &interaction.token, &response)
-						.await
-						.into_diagnostic()?;
-					return Ok(());
-				}
-			}
-			let invite_data = invite_data.into_diagnostic()?;
-			let invite_data = invite_data.model().await.into_diagnostic()?;
-
-			if invite_data.guild.is_none() {
-				let response_message = format!(
-					"The invite you provided isn't for a guild.\n{}",
-					try_again_text(&ticket_title, &ticket_message)
-				);
-				let response = InteractionResponseDataBuilder::new()
-					.content(response_message)
-					.components(Vec::new())
-					.build();
-				let response = InteractionResponse {
-					kind: InteractionResponseType::UpdateMessage,
-					data: Some(response),
-				};
-				interaction_client
-					.create_response(interaction.id, &interaction.token, &response)
-					.await
-					.into_diagnostic()?;
-				return Ok(());
-			}
 			if invite_data.expires_at.is_some() {
 				let response_message = format!(
 					"The invite you provided expires, but partnership invites must be permanent.\n{}",
@@ -843,15 +1060,61 @@ async fn handle_message_modal_data(
 			(
 				format!("**Partner invite URL**: {}\n\n{}", invite_url, ticket_message),
 				Some(invite_data),
+				None,
 			)
 		}
-		_ => (ticket_message, None),
-	};
+		Some(BuiltInCategory::ExistingPartner) => {
+			let partnership: Option<Partnership> = match (&create_ticket_state.partnership_id, &invite_url) {
+				(Some(partnership_id), _) => partnerships::table
+					.filter(
+						partnerships::id
+							.eq(partnership_id)
+							.and(partnerships::guild.eq(db_guild_id)),
+					)
+					.first(&mut db_connection)
+					.optional()
+					.into_diagnostic()?,
+				(None, Some(invite_url)) => {
+					let Some(invite_data) = get_submitted_invite(
+						interaction,
+						http_client,
+						application_id,
+						invite_url,
+						&ticket_title,
+						&ticket_message,
+					)
+					.await?
+					else {
+						return Ok(());
+					};
+					let Some(partner_guild) = invite_data.guild else {
+						bail!("Submitted invite has no guild after being checked for one");
+					};
+					partnerships::table
+						.filter(
+							partnerships::guild.eq(db_guild_id).and(
+								partnerships::partner_guild.eq(database_id_from_discord_id(partner_guild.id.get())),
+							),
+						)
+						.order(partnerships::approved_at.desc())
+						.first(&mut db_connection)
+						.optional()
+						.into_diagnostic()?
+				}
+				(None, None) => bail!("Neither a partnership nor an invite URL was entered on existing partner ticket"),
+			};
 
-	let Some(guild_id) = interaction.guild_id else {
-		bail!("Create ticket workflow moved outside of a guild");
+			let ticket_message = match (&partnership, &invite_url) {
+				(Some(partnership), _) => {
+					format!("**Partner server**: {}\n\n{}", partnership.partner_name, ticket_message)
+				}
+				(None, Some(invite_url)) => format!("**Partner invite URL**: {}\n\n{}", invite_url, ticket_message),
+				(None, None) => ticket_message,
+			};
+			(ticket_message, None, partnership.map(|partnership| partnership.id))
+		}
+		_ => (ticket_message, None, None),
 	};
-	let db_guild_id = database_id_from_discord_id(guild_id.get());
 	let Some(interaction_member) = &interaction.member else {
 		bail!("Interaction isn't from a user");
 	};
@@ -960,6 +1223,13 @@ async fn handle_message_modal_data(
 		.await
 		.into_diagnostic()?;
 
+	let pending_partnership_id = cuid2::create_id();
+	let staff_ticket_components = if invite_data.is_some() {
+		partnership_decision_components(&pending_partnership_id)
+	} else {
+		Vec::new()
+	};
+
 	let staff_ticket_title = format!("{} [{}]", ticket_title, interaction_user.name);
 	let mut staff_ticket_message = http_client
 		.create_forum_thread(staff_channel_id, &staff_ticket_title)
@@ -969,7 +1239,8 @@ async fn handle_message_modal_data(
 	}
 	staff_ticket_message = staff_ticket_message
 		.embeds(&staff_ticket_message_data.embeds)
-		.allowed_mentions(Some(&staff_ticket_message_data.allowed_mentions));
+		.allowed_mentions(Some(&staff_ticket_message_data.allowed_mentions))
+		.components(&staff_ticket_components);
 	let staff_ticket_thread_future = staff_ticket_message.into_future();
 
 	let user_ticket_author = UserMessageAuthor::User(interaction_user.name.clone());
@@ -1067,6 +1338,7 @@ async fn handle_message_modal_data(
 		staff_thread: db_staff_thread_id,
 		user_thread: db_user_thread_id,
 		closed_at: None,
+		partnership,
 	};
 	let new_ticket_message = TicketMessage {
 		id: cuid2::create_id(),
@@ -1078,7 +1350,7 @@ async fn handle_message_modal_data(
 		user_message: Some(db_user_message_id),
 	};
 	let pending_partnership = invite_data.map(|invite_data| PendingPartnership {
-		id: cuid2::create_id(),
+		id: pending_partnership_id,
 		guild: db_guild_id,
 		partner_guild: database_id_from_discord_id(invite_data.guild.unwrap().id.get()),
 		invite_code: invite_data.code,
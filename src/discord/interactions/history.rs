@@ -0,0 +1,102 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::history::{HistoryFilter, history_page};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::channel::message::AllowedMentions;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+pub async fn route_history_interaction(
+	interaction: &InteractionCreate,
+	interaction_data: &MessageComponentInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(user_id) = custom_id_path.get(1).and_then(|id| id.parse::<Id<UserMarker>>().ok()) else {
+		bail!(
+			"Invalid user in custom ID for history interaction: {}",
+			interaction_data.custom_id
+		);
+	};
+
+	let (filter, page) = match custom_id_path.get(2).map(|s| s.as_str()) {
+		Some("filter") => {
+			let filter = interaction_data
+				.values
+				.first()
+				.and_then(|value| HistoryFilter::from_name(value));
+			let Some(filter) = filter else {
+				bail!("Invalid filter selected for history: {:?}", interaction_data.values);
+			};
+			(filter, 0)
+		}
+		Some("page") => {
+			let filter = custom_id_path.get(3).and_then(|name| HistoryFilter::from_name(name));
+			let page = custom_id_path.get(4).and_then(|page| page.parse::<usize>().ok());
+			let (Some(filter), Some(page)) = (filter, page) else {
+				bail!(
+					"Invalid page in custom ID for history interaction: {}",
+					interaction_data.custom_id
+				);
+			};
+			(filter, page)
+		}
+		_ => bail!(
+			"Unexpected history interaction encountered: {}",
+			interaction_data.custom_id
+		),
+	};
+
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("History interaction used outside of a guild");
+	};
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let guild: Option<Guild> = guilds::table
+		.find(db_guild_id)
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let response = match guild {
+		Some(_) => {
+			let page = history_page(&mut db_connection, db_guild_id, user_id, filter, page).into_diagnostic()?;
+			InteractionResponseDataBuilder::new()
+				.content(page.content)
+				.components(page.components)
+				.allowed_mentions(AllowedMentions::default())
+				.build()
+		}
+		None => InteractionResponseDataBuilder::new()
+			.content(NOT_SET_UP_FOR_GUILD)
+			.components(Vec::new())
+			.build(),
+	};
+	let response = InteractionResponse {
+		kind: InteractionResponseType::UpdateMessage,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::config::ConfigData;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use miette::bail;
@@ -17,10 +18,15 @@ use twilight_model::id::Id;
 use twilight_model::id::marker::ApplicationMarker;
 use type_map::concurrent::TypeMap;
 
+mod action_reason;
 mod create_ticket;
+mod history;
+mod note;
+mod partnership;
 mod reply;
 mod settings;
 mod setup;
+mod warn;
 
 pub async fn route_interaction(
 	interaction: &InteractionCreate,
@@ -33,6 +39,17 @@ pub async fn route_interaction(
 	let custom_id_path: Vec<String> = interaction_data.custom_id.split('/').map(|s| s.to_string()).collect();
 
 	match custom_id_path.first().map(|s| s.as_str()) {
+		Some("action_reason") => {
+			action_reason::route_action_reason_interaction(
+				interaction,
+				interaction_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		Some("create_ticket") => {
 			create_ticket::route_create_ticket_interaction(
 				interaction,
@@ -45,6 +62,28 @@ pub async fn route_interaction(
 			)
 			.await
 		}
+		Some("history") => {
+			history::route_history_interaction(
+				interaction,
+				interaction_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		Some("partnership") => {
+			partnership::route_partnership_interaction(
+				interaction,
+				interaction_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		Some("settings") => {
 			settings::route_settings_interaction(
 				interaction,
@@ -81,6 +120,7 @@ pub async fn route_modal_submit(
 	interaction: &InteractionCreate,
 	modal_data: &ModalInteractionData,
 	http_client: &Client,
+	config: &ConfigData,
 	application_id: Id<ApplicationMarker>,
 	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
 	bot_state: Arc<RwLock<TypeMap>>,
@@ -88,6 +128,17 @@ pub async fn route_modal_submit(
 	let custom_id_path: Vec<String> = modal_data.custom_id.split('/').map(|s| s.to_string()).collect();
 
 	match custom_id_path.first().map(|s| s.as_str()) {
+		Some("action_reason") => {
+			action_reason::route_action_reason_modal(
+				interaction,
+				modal_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		Some("create_ticket") => {
 			create_ticket::route_create_ticket_modal(
 				interaction,
@@ -100,6 +151,28 @@ pub async fn route_modal_submit(
 			)
 			.await
 		}
+		Some("note") => {
+			note::route_note_modal(
+				interaction,
+				modal_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		Some("partnership") => {
+			partnership::route_partnership_modal(
+				interaction,
+				modal_data,
+				&custom_id_path,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		Some("reply") => {
 			reply::route_reply_modal(
 				interaction,
@@ -124,6 +197,18 @@ pub async fn route_modal_submit(
 			)
 			.await
 		}
+		Some("warn") => {
+			warn::route_warn_modal(
+				interaction,
+				modal_data,
+				&custom_id_path,
+				http_client,
+				config,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
 		_ => bail!(
 			"Unexpected modal response encountered: {}\n{:?}",
 			modal_data.custom_id,
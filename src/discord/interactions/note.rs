@@ -0,0 +1,188 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::interactions::respond_with_message;
+use crate::model::{Ticket, UserNote, UserNoteEdit, database_id_from_discord_id};
+use crate::schema::{tickets, user_note_edits, user_notes};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+
+pub async fn route_note_modal(
+	interaction: &InteractionCreate,
+	modal_data: &ModalInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(id) = custom_id_path.get(1) else {
+		bail!("Invalid custom ID for note (parts: {:?})", custom_id_path);
+	};
+	let Some(action) = custom_id_path.get(2) else {
+		bail!("Invalid custom ID for note (parts: {:?})", custom_id_path);
+	};
+
+	let mut body: Option<String> = None;
+	for row in modal_data.components.iter() {
+		for component in row.components.iter() {
+			if component.custom_id.as_str() == "body" {
+				body = component.value.clone();
+			}
+		}
+	}
+	let Some(body) = body.filter(|body| !body.is_empty()) else {
+		return respond_with_message(
+			interaction,
+			http_client,
+			application_id,
+			String::from("The note wasn't saved: the note text is required."),
+			true,
+		)
+		.await;
+	};
+
+	match action.as_str() {
+		"add" => {
+			let Ok(user_id) = id.parse::<Id<UserMarker>>() else {
+				bail!("Invalid user ID for note addition (parts: {:?})", custom_id_path);
+			};
+			add_note(
+				interaction,
+				user_id,
+				body,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"edit" => edit_note(interaction, id, body, http_client, application_id, db_connection_pool).await,
+		_ => bail!(
+			"Invalid action for note: {} (custom ID parts: {:?})",
+			action,
+			custom_id_path
+		),
+	}
+}
+
+async fn add_note(
+	interaction: &InteractionCreate,
+	user_id: Id<UserMarker>,
+	body: String,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note modal was submitted outside of a guild");
+	};
+	let Some(author) = interaction.author_id() else {
+		bail!("Note modal was submitted by a non-user");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let db_user_id = database_id_from_discord_id(user_id.get());
+
+	let ticket: Option<Ticket> = match interaction.channel.as_ref() {
+		Some(channel) => {
+			let db_channel_id = database_id_from_discord_id(channel.id.get());
+			tickets::table
+				.filter(
+					tickets::staff_thread
+						.eq(db_channel_id)
+						.and(tickets::with_user.eq(db_user_id)),
+				)
+				.first(&mut db_connection)
+				.optional()
+				.into_diagnostic()?
+		}
+		None => None,
+	};
+
+	let note = UserNote {
+		id: cuid2::create_id(),
+		guild: db_guild_id,
+		target_user: db_user_id,
+		author: database_id_from_discord_id(author.get()),
+		created_at: Utc::now(),
+		body,
+		action_type: None,
+		action_id: None,
+		ticket: ticket.map(|ticket| ticket.id),
+	};
+	diesel::insert_into(user_notes::table)
+		.values(&note)
+		.execute(&mut db_connection)
+		.into_diagnostic()?;
+
+	let content = format!("Added note `{}` for {}.", note.id, user_id.mention());
+	respond_with_message(interaction, http_client, application_id, content, true).await
+}
+
+async fn edit_note(
+	interaction: &InteractionCreate,
+	note_id: &str,
+	body: String,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Note modal was submitted outside of a guild");
+	};
+	let Some(editor) = interaction.author_id() else {
+		bail!("Note modal was submitted by a non-user");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+
+	let edit_result: QueryResult<Option<UserNote>> = db_connection.transaction(|db_connection| {
+		let note: Option<UserNote> = user_notes::table
+			.find(note_id)
+			.filter(user_notes::guild.eq(db_guild_id))
+			.for_update()
+			.first(db_connection)
+			.optional()?;
+		let Some(note) = note else {
+			return Ok(None);
+		};
+		if note.body == body {
+			return Ok(Some(note));
+		}
+
+		let edit = UserNoteEdit {
+			id: cuid2::create_id(),
+			note: note.id.clone(),
+			editor: database_id_from_discord_id(editor.get()),
+			edit_time: Utc::now(),
+			previous_body: note.body,
+		};
+		diesel::insert_into(user_note_edits::table)
+			.values(edit)
+			.execute(db_connection)?;
+		let note: UserNote = diesel::update(user_notes::table)
+			.filter(user_notes::id.eq(note_id))
+			.set(user_notes::body.eq(&body))
+			.get_result(db_connection)?;
+		Ok(Some(note))
+	});
+
+	let content = match edit_result.into_diagnostic()? {
+		Some(note) => format!("Updated note `{}` for {}.", note.id, note.get_target_user().mention()),
+		None => format!("The note `{}` no longer exists, so it couldn't be edited.", note_id),
+	};
+	respond_with_message(interaction, http_client, application_id, content, true).await
+}
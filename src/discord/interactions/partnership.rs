@@ -0,0 +1,508 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::partner_list::update_partner_list_message;
+use crate::discord::utils::permissions::member_is_staff;
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::tickets::{UserMessageAuthor, staff_message, user_message};
+use crate::discord::utils::timestamp::{datetime_from_id, timestamp_from_id};
+use crate::model::{
+	DeclinedPartnership, Guild, Partnership, PendingPartnership, Ticket, TicketMessage, database_id_from_discord_id,
+};
+use crate::schema::{declined_partnerships, guilds, partnerships, pending_partnerships, ticket_messages, tickets};
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_http::error::ErrorType;
+use twilight_http::response::StatusCode;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::interaction::message_component::MessageComponentInteractionData;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::channel::message::component::{ActionRow, Component, TextInput, TextInputStyle};
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_model::user::User;
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+const MAX_DECLINE_REASON_LENGTH: u16 = 1000;
+const PARTNERSHIP_ALREADY_DECIDED: &str = "This partnership request has already been approved or declined.";
+
+pub async fn route_partnership_interaction(
+	interaction: &InteractionCreate,
+	interaction_data: &MessageComponentInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let (Some(pending_partnership_id), Some(action)) = (custom_id_path.get(1), custom_id_path.get(2)) else {
+		bail!(
+			"Invalid custom ID for partnership decision: {} (parts: {:?})",
+			interaction_data.custom_id,
+			custom_id_path
+		);
+	};
+
+	match action.as_str() {
+		"approve" => {
+			approve_partnership(
+				interaction,
+				pending_partnership_id,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		"decline" => {
+			show_decline_modal(
+				interaction,
+				pending_partnership_id,
+				http_client,
+				application_id,
+				db_connection_pool,
+			)
+			.await
+		}
+		_ => bail!(
+			"Invalid action for partnership decision: {} (custom ID parts: {:?})",
+			action,
+			custom_id_path
+		),
+	}
+}
+
+pub async fn route_partnership_modal(
+	interaction: &InteractionCreate,
+	modal_data: &ModalInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let (Some(pending_partnership_id), Some(action)) = (custom_id_path.get(1), custom_id_path.get(2)) else {
+		bail!("Invalid custom ID for partnership modal (parts: {:?})", custom_id_path);
+	};
+	if action != "decline" {
+		bail!(
+			"Invalid action for partnership modal: {} (custom ID parts: {:?})",
+			action,
+			custom_id_path
+		);
+	}
+
+	let mut reason: Option<String> = None;
+	for row in modal_data.components.iter() {
+		for component in row.components.iter() {
+			if component.custom_id.as_str() == "reason" {
+				reason = component.value.clone();
+			}
+		}
+	}
+
+	let interaction_client = http_client.interaction(application_id);
+	let Some(reason) = reason.filter(|reason| !reason.is_empty()) else {
+		let response = InteractionResponseDataBuilder::new()
+			.content("No reason was provided.")
+			.flags(MessageFlags::EPHEMERAL)
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	};
+	let Some(decliner) = interaction.author() else {
+		bail!("Partnership decline submitted by a non-user");
+	};
+
+	if !interaction_user_is_staff(interaction, http_client, application_id, db_connection_pool.clone()).await? {
+		return Ok(());
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let Some((pending_partnership, ticket)) =
+		get_pending_partnership(interaction, pending_partnership_id, &mut db_connection)?
+	else {
+		respond_already_decided(interaction, http_client, application_id).await?;
+		return Ok(());
+	};
+
+	let declined_at = datetime_from_id(interaction.id).unwrap_or_else(Utc::now);
+	let declined_partnership = DeclinedPartnership {
+		id: cuid2::create_id(),
+		guild: pending_partnership.guild,
+		partner_guild: pending_partnership.partner_guild,
+		invite_code: pending_partnership.invite_code.clone(),
+		representative: ticket.with_user,
+		ticket: ticket.id.clone(),
+		declined_by: database_id_from_discord_id(decliner.id.get()),
+		declined_at,
+		reason: reason.clone(),
+	};
+	let recorded = db_connection
+		.transaction(|db_connection| {
+			let removed_count =
+				diesel::delete(pending_partnerships::table.find(&pending_partnership.id)).execute(db_connection)?;
+			if removed_count == 0 {
+				return Ok(false);
+			}
+			diesel::insert_into(declined_partnerships::table)
+				.values(declined_partnership)
+				.execute(db_connection)?;
+			Ok::<bool, diesel::result::Error>(true)
+		})
+		.into_diagnostic()?;
+	if !recorded {
+		respond_already_decided(interaction, http_client, application_id).await?;
+		return Ok(());
+	}
+
+	let response = InteractionResponseDataBuilder::new()
+		.content(format!("❌ Partnership declined by {}.", decliner.id.mention()))
+		.allowed_mentions(AllowedMentions::default())
+		.components(Vec::new())
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::UpdateMessage,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	let notice = format!("Your partnership request has been declined.\n**Reason**: {}", reason);
+	notify_ticket_user(interaction, http_client, &mut db_connection, &ticket, decliner, &notice).await
+}
+
+async fn approve_partnership(
+	interaction: &InteractionCreate,
+	pending_partnership_id: &str,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(approver) = interaction.author() else {
+		bail!("Partnership approved by a non-user");
+	};
+
+	if !interaction_user_is_staff(interaction, http_client, application_id, db_connection_pool.clone()).await? {
+		return Ok(());
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let Some((pending_partnership, ticket)) =
+		get_pending_partnership(interaction, pending_partnership_id, &mut db_connection)?
+	else {
+		respond_already_decided(interaction, http_client, application_id).await?;
+		return Ok(());
+	};
+
+	let interaction_client = http_client.interaction(application_id);
+	let invite = match http_client.invite(&pending_partnership.invite_code).with_counts().await {
+		Ok(response) => Some(response.model().await.into_diagnostic()?),
+		Err(error) => {
+			let invite_not_found = matches!(
+				error.kind(),
+				ErrorType::Response {
+					status: StatusCode::NOT_FOUND,
+					..
+				}
+			);
+			if !invite_not_found {
+				return Err(error).into_diagnostic();
+			}
+			None
+		}
+	};
+	let partner_guild = invite.as_ref().and_then(|invite| {
+		invite
+			.guild
+			.as_ref()
+			.filter(|guild| guild.id == pending_partnership.get_partner_guild())
+	});
+	let Some(partner_guild) = partner_guild else {
+		let response = InteractionResponseDataBuilder::new()
+			.content("The invite submitted with this request no longer leads to the partner server. Ask the requester for a new invite, or decline the request.")
+			.flags(MessageFlags::EPHEMERAL)
+			.build();
+		let response = InteractionResponse {
+			kind: InteractionResponseType::ChannelMessageWithSource,
+			data: Some(response),
+		};
+		interaction_client
+			.create_response(interaction.id, &interaction.token, &response)
+			.await
+			.into_diagnostic()?;
+		return Ok(());
+	};
+	let member_count = invite
+		.as_ref()
+		.and_then(|invite| invite.approximate_member_count)
+		.unwrap_or_default();
+
+	let guild: Guild = guilds::table
+		.find(pending_partnership.guild)
+		.first(&mut db_connection)
+		.into_diagnostic()?;
+
+	let approved_at = datetime_from_id(interaction.id).unwrap_or_else(Utc::now);
+	let partnership = Partnership {
+		id: cuid2::create_id(),
+		guild: pending_partnership.guild,
+		partner_guild: pending_partnership.partner_guild,
+		partner_name: partner_guild.name.clone(),
+		partner_icon: partner_guild.icon.map(|icon| icon.to_string()),
+		member_count: member_count as i64,
+		invite_code: pending_partnership.invite_code.clone(),
+		representative: ticket.with_user,
+		ticket: ticket.id.clone(),
+		approved_by: database_id_from_discord_id(approver.id.get()),
+		approved_at,
+		active: true,
+		tcn_push_pending: guild.tcn_partner_integration,
+	};
+	let partner_name = partnership.partner_name.clone();
+	let recorded = db_connection
+		.transaction(|db_connection| {
+			let removed_count =
+				diesel::delete(pending_partnerships::table.find(&pending_partnership.id)).execute(db_connection)?;
+			if removed_count == 0 {
+				return Ok(false);
+			}
+			diesel::insert_into(partnerships::table)
+				.values(partnership)
+				.execute(db_connection)?;
+			Ok::<bool, diesel::result::Error>(true)
+		})
+		.into_diagnostic()?;
+	if !recorded {
+		respond_already_decided(interaction, http_client, application_id).await?;
+		return Ok(());
+	}
+
+	let response = InteractionResponseDataBuilder::new()
+		.content(format!(
+			"✅ Partnership with **{}** approved by {}.",
+			partner_name,
+			approver.id.mention()
+		))
+		.allowed_mentions(AllowedMentions::default())
+		.components(Vec::new())
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::UpdateMessage,
+		data: Some(response),
+	};
+	interaction_client
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	if let Err(error) = update_partner_list_message(http_client, &guild, &mut db_connection).await {
+		tracing::warn!(source = ?error, "Failed to update the partner list after approving a partnership");
+	}
+
+	let notice = format!(
+		"Your partnership request for **{}** has been approved. Welcome aboard!",
+		partner_name
+	);
+	notify_ticket_user(interaction, http_client, &mut db_connection, &ticket, approver, &notice).await
+}
+
+async fn show_decline_modal(
+	interaction: &InteractionCreate,
+	pending_partnership_id: &str,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	if !interaction_user_is_staff(interaction, http_client, application_id, db_connection_pool.clone()).await? {
+		return Ok(());
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	if get_pending_partnership(interaction, pending_partnership_id, &mut db_connection)?.is_none() {
+		respond_already_decided(interaction, http_client, application_id).await?;
+		return Ok(());
+	}
+
+	let reason_input = Component::TextInput(TextInput {
+		custom_id: String::from("reason"),
+		label: String::from("Reason (shown to the requester)"),
+		max_length: Some(MAX_DECLINE_REASON_LENGTH),
+		min_length: None,
+		placeholder: None,
+		required: Some(true),
+		style: TextInputStyle::Paragraph,
+		value: None,
+	});
+	let reason_input_row = Component::ActionRow(ActionRow {
+		components: vec![reason_input],
+	});
+	let response = InteractionResponseDataBuilder::new()
+		.custom_id(format!("partnership/{}/decline", pending_partnership_id))
+		.title("Decline Partnership")
+		.components(vec![reason_input_row])
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::Modal,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+/// Gets the pending partnership and the ticket it was requested in, if the partnership is still pending in the guild
+/// in which the interaction occurred
+fn get_pending_partnership(
+	interaction: &InteractionCreate,
+	pending_partnership_id: &str,
+	db_connection: &mut PgConnection,
+) -> miette::Result<Option<(PendingPartnership, Ticket)>> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Partnership interaction used outside of a guild");
+	};
+	let pending: Option<(PendingPartnership, Ticket)> = pending_partnerships::table
+		.inner_join(tickets::table)
+		.filter(pending_partnerships::id.eq(pending_partnership_id))
+		.filter(pending_partnerships::guild.eq(database_id_from_discord_id(guild_id.get())))
+		.first(db_connection)
+		.optional()
+		.into_diagnostic()?;
+	Ok(pending)
+}
+
+async fn respond_already_decided(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+) -> miette::Result<()> {
+	let response = InteractionResponseDataBuilder::new()
+		.content(PARTNERSHIP_ALREADY_DECIDED)
+		.flags(MessageFlags::EPHEMERAL)
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
+
+/// Posts the outcome of a partnership request to both ends of its ticket, recording it as a ticket message
+async fn notify_ticket_user(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	db_connection: &mut PgConnection,
+	ticket: &Ticket,
+	staff_user: &User,
+	notice: &str,
+) -> miette::Result<()> {
+	let timestamp = timestamp_from_id(interaction.id).into_diagnostic()?;
+	let staff_message_data = staff_message(&staff_user.name, notice, timestamp).into_diagnostic()?;
+	let user_message_data = user_message(
+		UserMessageAuthor::Staff,
+		ticket.get_with_user(),
+		true,
+		notice,
+		timestamp,
+	)
+	.into_diagnostic()?;
+
+	let staff_message_future = staff_message_data
+		.set_create_message_data(http_client.create_message(ticket.get_staff_thread()))
+		.into_future();
+	let user_message_future = user_message_data
+		.set_create_message_data(http_client.create_message(ticket.get_user_thread()))
+		.into_future();
+	let (staff_message_result, user_message_result) = tokio::join!(staff_message_future, user_message_future);
+	let staff_message = staff_message_result
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let user_message = user_message_result.into_diagnostic()?.model().await.into_diagnostic()?;
+
+	let ticket_message = TicketMessage {
+		id: cuid2::create_id(),
+		ticket: ticket.id.clone(),
+		author: database_id_from_discord_id(staff_user.id.get()),
+		send_time: datetime_from_id(interaction.id).unwrap_or_else(Utc::now),
+		body: notice.to_string(),
+		staff_message: database_id_from_discord_id(staff_message.id.get()),
+		user_message: Some(database_id_from_discord_id(user_message.id.get())),
+	};
+	diesel::insert_into(ticket_messages::table)
+		.values(ticket_message)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+/// Checks whether the user who triggered the interaction is staff for the guild, responding to the interaction with
+/// an explanation if not.
+async fn interaction_user_is_staff(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<bool> {
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Partnership interaction used outside of a guild");
+	};
+	let Some(member) = interaction.member.as_ref() else {
+		bail!("Partnership interaction used by a non-member");
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let guild: Option<Guild> = guilds::table
+		.find(database_id_from_discord_id(guild_id.get()))
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+
+	let response_content = match guild {
+		Some(guild) if member_is_staff(&guild, &member.roles) => return Ok(true),
+		Some(_) => "Only staff can decide on partnership requests.",
+		None => NOT_SET_UP_FOR_GUILD,
+	};
+	let response = InteractionResponseDataBuilder::new()
+		.content(response_content)
+		.flags(MessageFlags::EPHEMERAL)
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+
+	Ok(false)
+}
@@ -0,0 +1,106 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, defer_moderation_response, moderation_refusal, send_moderation_response,
+};
+use crate::discord::utils::setup::NOT_SET_UP_FOR_GUILD;
+use crate::discord::utils::warnings::give_warning;
+use crate::model::{Guild, database_id_from_discord_id};
+use crate::schema::guilds;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, UserMarker};
+
+pub async fn route_warn_modal(
+	interaction: &InteractionCreate,
+	modal_data: &ModalInteractionData,
+	custom_id_path: &[String],
+	http_client: &Client,
+	config: &ConfigData,
+	application_id: Id<ApplicationMarker>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let (Some(user_id), Some(action)) = (custom_id_path.get(1), custom_id_path.get(2)) else {
+		bail!("Invalid custom ID for warning (parts: {:?})", custom_id_path);
+	};
+	if action != "give" {
+		bail!(
+			"Invalid action for warning: {} (custom ID parts: {:?})",
+			action,
+			custom_id_path
+		);
+	}
+	let Ok(target_user) = user_id.parse::<Id<UserMarker>>() else {
+		bail!("Invalid user ID for warning (parts: {:?})", custom_id_path);
+	};
+	let Some(guild_id) = interaction.guild_id else {
+		bail!("Warning modal was submitted outside of a guild");
+	};
+	let (Some(moderator), Some(moderator_id)) = (interaction.member.as_ref(), interaction.author_id()) else {
+		bail!("Warning modal was submitted by a non-member");
+	};
+
+	let mut reason = String::new();
+	let mut evidence: Option<String> = None;
+	for row in modal_data.components.iter() {
+		for component in row.components.iter() {
+			let value = component.value.as_deref().map(str::trim).unwrap_or_default();
+			match component.custom_id.as_str() {
+				"reason" => reason = value.to_string(),
+				"evidence" => evidence = Some(value.to_string()).filter(|evidence| !evidence.is_empty()),
+				_ => (),
+			}
+		}
+	}
+	let options = ModerationCommandOptions {
+		target_user,
+		reason,
+		evidence,
+		rule_number: None,
+		dm_user: true,
+	};
+
+	defer_moderation_response(interaction, http_client, application_id).await?;
+
+	if options.reason.is_empty() {
+		let content = "The user wasn't warned: a reason is required.";
+		return send_moderation_response(interaction, http_client, application_id, content).await;
+	}
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let guild: Option<Guild> = guilds::table
+		.find(database_id_from_discord_id(guild_id.get()))
+		.first(&mut db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return send_moderation_response(interaction, http_client, application_id, NOT_SET_UP_FOR_GUILD).await;
+	};
+
+	let refusal = moderation_refusal(
+		&mut db_connection,
+		http_client,
+		guild_id,
+		moderator_id,
+		moderator,
+		&options,
+		"warn",
+	)
+	.await?;
+	if let Some(refusal) = refusal {
+		return send_moderation_response(interaction, http_client, application_id, &refusal).await;
+	}
+
+	let content = give_warning(&mut db_connection, http_client, config, &guild, moderator_id, &options).await?;
+	send_moderation_response(interaction, http_client, application_id, &content).await
+}
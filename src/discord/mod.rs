@@ -9,6 +9,7 @@ mod connection;
 mod events;
 mod incoming_messages;
 mod interactions;
+mod scheduler;
 mod state;
 pub mod utils;
 
@@ -0,0 +1,60 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::utils::partner_invites::check_partner_invites;
+use super::utils::tcn::{pull_network_data, push_pending_partnerships};
+use super::utils::temporary_bans::lift_expired_bans;
+use crate::config::ConfigData;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use twilight_http::client::Client;
+
+/// How often the scheduler checks for work that has come due
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the partner network's shared partner and ban data is pulled
+const NETWORK_PULL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Runs the bot's scheduled work for as long as the bot is running.
+///
+/// Everything the scheduler acts on is stored in the database, so work that came due while the bot was offline is
+/// handled on the first check after startup.
+pub async fn run_scheduler(
+	http_client: Arc<Client>,
+	config: Arc<ConfigData>,
+	db_connection_pool: Pool<ConnectionManager<PgConnection>>,
+) {
+	let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+	interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+	let mut last_network_pull: Option<Instant> = None;
+
+	loop {
+		interval.tick().await;
+
+		if let Err(error) = lift_expired_bans(&http_client, &config, &db_connection_pool).await {
+			tracing::error!(source = ?error, "Failed to lift expired temporary bans");
+		}
+
+		if let Err(error) = check_partner_invites(&http_client, &db_connection_pool).await {
+			tracing::error!(source = ?error, "Failed to check partner invites");
+		}
+
+		if let Err(error) = push_pending_partnerships(&config, &db_connection_pool).await {
+			tracing::error!(source = ?error, "Failed to push partnerships to the partner network");
+		}
+
+		let network_pull_due = last_network_pull.is_none_or(|last_pull| last_pull.elapsed() >= NETWORK_PULL_INTERVAL);
+		if network_pull_due {
+			last_network_pull = Some(Instant::now());
+			if let Err(error) = pull_network_data(&config, &db_connection_pool).await {
+				tracing::error!(source = ?error, "Failed to pull data from the partner network");
+			}
+		}
+	}
+}
@@ -85,6 +85,9 @@ pub struct CreateTicketStates {
 pub struct CreateTicketState {
 	pub built_in_category: Option<BuiltInCategory>,
 	pub custom_category_id: Option<String>,
+	/// For existing partner tickets, the partnership the user chose from those they represent. If the user didn't
+	/// choose one, they're asked for an invite instead.
+	pub partnership_id: Option<String>,
 }
 
 pub fn new_ticket_button() -> Component {
@@ -0,0 +1,178 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::rules::link_rules_from_reason;
+use crate::model::{BanAction, KickAction, ModerationActionType, TimeoutAction, WarnAction};
+use crate::schema::{ban_actions, kick_actions, timeout_actions, warn_actions};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use twilight_model::channel::message::component::{ActionRow, Button, ButtonStyle, Component};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+
+/// Gets the name used to refer to an action type in custom IDs
+pub fn action_type_custom_id_name(action_type: ModerationActionType) -> &'static str {
+	match action_type {
+		ModerationActionType::Automod => "automod",
+		ModerationActionType::Ban => "ban",
+		ModerationActionType::Kick => "kick",
+		ModerationActionType::Timeout => "timeout",
+		ModerationActionType::Warn => "warn",
+	}
+}
+
+/// Parses an action type from the name used in custom IDs
+pub fn action_type_from_custom_id_name(name: &str) -> Option<ModerationActionType> {
+	match name {
+		"automod" => Some(ModerationActionType::Automod),
+		"ban" => Some(ModerationActionType::Ban),
+		"kick" => Some(ModerationActionType::Kick),
+		"timeout" => Some(ModerationActionType::Timeout),
+		"warn" => Some(ModerationActionType::Warn),
+		_ => None,
+	}
+}
+
+/// Generates the components to attach to a missing reason complaint so staff can provide the reason later
+pub fn provide_reason_components(action_type: ModerationActionType, action_id: &str) -> Vec<Component> {
+	let provide_button = Button {
+		custom_id: Some(format!(
+			"action_reason/{}/{}",
+			action_id,
+			action_type_custom_id_name(action_type)
+		)),
+		disabled: false,
+		emoji: None,
+		label: Some(String::from("Provide reason")),
+		style: ButtonStyle::Primary,
+		url: None,
+		sku_id: None,
+	};
+	let provide_button_row = Component::ActionRow(ActionRow {
+		components: vec![Component::Button(provide_button)],
+	});
+	vec![provide_button_row]
+}
+
+/// The users involved with an action whose reason was amended, along with where the action was logged
+pub struct AmendedAction {
+	pub moderator: Id<UserMarker>,
+	pub target_user: Id<UserMarker>,
+	pub mod_log_channel: Option<Id<ChannelMarker>>,
+	pub mod_log_message: Option<Id<MessageMarker>>,
+	pub action_time: DateTime<Utc>,
+}
+
+/// The longest reason Discord accepts in the audit log; amended reasons are held to the same limit.
+pub const MAX_REASON_LENGTH: u16 = 512;
+
+/// Saves a reason provided after an action took place, keeping the original reason from the audit log.
+///
+/// Returns [None] if no matching action exists in the guild.
+pub fn amend_action_reason(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	action_type: ModerationActionType,
+	action_id: &str,
+	reason: &str,
+	db_amended_by: i64,
+	amended_at: DateTime<Utc>,
+) -> QueryResult<Option<AmendedAction>> {
+	let amended_action = match action_type {
+		ModerationActionType::Automod => None,
+		ModerationActionType::Ban => {
+			let action: Option<BanAction> = diesel::update(ban_actions::table)
+				.filter(ban_actions::id.eq(action_id).and(ban_actions::guild.eq(db_guild_id)))
+				.set((
+					ban_actions::amended_reason.eq(reason),
+					ban_actions::reason_amended_by.eq(db_amended_by),
+					ban_actions::reason_amended_at.eq(amended_at),
+				))
+				.get_result(db_connection)
+				.optional()?;
+			action.map(|action| AmendedAction {
+				moderator: action.get_banning_user(),
+				target_user: action.get_banned_user(),
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				action_time: action.action_time,
+			})
+		}
+		ModerationActionType::Kick => {
+			let action: Option<KickAction> = diesel::update(kick_actions::table)
+				.filter(kick_actions::id.eq(action_id).and(kick_actions::guild.eq(db_guild_id)))
+				.set((
+					kick_actions::amended_reason.eq(reason),
+					kick_actions::reason_amended_by.eq(db_amended_by),
+					kick_actions::reason_amended_at.eq(amended_at),
+				))
+				.get_result(db_connection)
+				.optional()?;
+			action.map(|action| AmendedAction {
+				moderator: action.get_kicking_user(),
+				target_user: action.get_kicked_user(),
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				action_time: action.action_time,
+			})
+		}
+		ModerationActionType::Timeout => {
+			let action: Option<TimeoutAction> = diesel::update(timeout_actions::table)
+				.filter(
+					timeout_actions::id
+						.eq(action_id)
+						.and(timeout_actions::guild.eq(db_guild_id)),
+				)
+				.set((
+					timeout_actions::amended_reason.eq(reason),
+					timeout_actions::reason_amended_by.eq(db_amended_by),
+					timeout_actions::reason_amended_at.eq(amended_at),
+				))
+				.get_result(db_connection)
+				.optional()?;
+			action.map(|action| AmendedAction {
+				moderator: action.get_performing_user(),
+				target_user: action.get_target_user(),
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				action_time: action.action_time,
+			})
+		}
+		ModerationActionType::Warn => {
+			let action: Option<WarnAction> = diesel::update(warn_actions::table)
+				.filter(warn_actions::id.eq(action_id).and(warn_actions::guild.eq(db_guild_id)))
+				.set((
+					warn_actions::amended_reason.eq(reason),
+					warn_actions::reason_amended_by.eq(db_amended_by),
+					warn_actions::reason_amended_at.eq(amended_at),
+				))
+				.get_result(db_connection)
+				.optional()?;
+			action.map(|action| AmendedAction {
+				moderator: action.get_warning_user(),
+				target_user: action.get_warned_user(),
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				action_time: action.action_time,
+			})
+		}
+	};
+
+	// Rules referenced in the new reason are linked alongside any the action already had
+	if let Some(amended_action) = &amended_action {
+		link_rules_from_reason(
+			db_connection,
+			db_guild_id,
+			action_type,
+			action_id,
+			amended_action.action_time,
+			reason,
+			None,
+		)?;
+	}
+
+	Ok(amended_action)
+}
@@ -0,0 +1,359 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_BAN, MOD_LOG_COLOR_UNBAN, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{
+	NewActionRecord, audit_log_reason, perform_recorded_action, post_action_to_mod_log,
+};
+use crate::discord::utils::temporary_bans::latest_ban_action;
+use crate::model::{
+	BanAction, BanSharePolicy, Guild, ModerationActionType, SharedBan, TrustedGuild, database_id_from_discord_id,
+};
+use crate::schema::{guilds, shared_bans, trusted_guilds};
+use chrono::Utc;
+use diesel::prelude::*;
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::util::datetime::Timestamp;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+/// Gets the guilds that trust the given guild and are trusted by it in return, which are the guilds its bans are
+/// shared with
+pub fn mutually_trusting_guilds(db_connection: &mut PgConnection, db_guild_id: i64) -> QueryResult<Vec<TrustedGuild>> {
+	let trusted_by_origin = trusted_guilds::table
+		.filter(trusted_guilds::guild.eq(db_guild_id))
+		.select(trusted_guilds::trusted_guild);
+	trusted_guilds::table
+		.filter(
+			trusted_guilds::trusted_guild
+				.eq(db_guild_id)
+				.and(trusted_guilds::guild.eq_any(trusted_by_origin)),
+		)
+		.load(db_connection)
+}
+
+/// Shares a recorded ban or unban with every guild that has a mutual trust link with the guild it happened in. Each
+/// receiving guild handles it according to its own policy.
+///
+/// Actions the bot applied because of a shared ban aren't shared again, so a ban only ever reaches the guilds that
+/// directly trust the guild where it happened.
+pub async fn share_ban_action(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+	ban_action: &BanAction,
+) -> miette::Result<()> {
+	if ban_action.shared_ban.is_some() {
+		return Ok(());
+	}
+
+	let receiving_guilds = mutually_trusting_guilds(db_connection, ban_action.guild).into_diagnostic()?;
+	if receiving_guilds.is_empty() {
+		return Ok(());
+	}
+
+	let origin_guild_name = http_client
+		.guild(ban_action.get_guild())
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?
+		.name;
+	let bot_user = http_client
+		.current_user()
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	for trust in receiving_guilds.iter() {
+		let share_result = share_with_guild(
+			http_client,
+			config,
+			db_connection,
+			ban_action,
+			trust,
+			&origin_guild_name,
+			bot_user.id,
+		)
+		.await;
+		if let Err(error) = share_result {
+			tracing::warn!(
+				source = ?error,
+				ban = %ban_action.id,
+				target_guild = %trust.get_guild(),
+				"Failed to share a ban with a trusted guild"
+			);
+		}
+	}
+
+	Ok(())
+}
+
+async fn share_with_guild(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+	ban_action: &BanAction,
+	trust: &TrustedGuild,
+	origin_guild_name: &str,
+	bot_user_id: Id<UserMarker>,
+) -> miette::Result<()> {
+	let target_guild: Option<Guild> = guilds::table
+		.find(trust.guild)
+		.first(db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(target_guild) = target_guild else {
+		return Ok(());
+	};
+
+	if !ban_action.added {
+		// Unbans only matter to guilds that were told about a ban of the user from the same guild
+		let previous_share: Option<SharedBan> = shared_bans::table
+			.filter(
+				shared_bans::target_guild
+					.eq(trust.guild)
+					.and(shared_bans::origin_guild.eq(ban_action.guild))
+					.and(shared_bans::banned_user.eq(ban_action.banned_user)),
+			)
+			.order(shared_bans::shared_at.desc())
+			.first(db_connection)
+			.optional()
+			.into_diagnostic()?;
+		if !previous_share.is_some_and(|share| share.added) {
+			return Ok(());
+		}
+	}
+
+	let shared_ban = SharedBan {
+		id: cuid2::create_id(),
+		origin_action: ban_action.id.clone(),
+		origin_guild: ban_action.guild,
+		target_guild: trust.guild,
+		banned_user: ban_action.banned_user,
+		added: ban_action.added,
+		reason: ban_action.current_reason().to_string(),
+		policy: trust.policy,
+		alert_channel: None,
+		alert_message: None,
+		shared_at: Utc::now(),
+	};
+	diesel::insert_into(shared_bans::table)
+		.values(&shared_ban)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	let applied = match trust.policy {
+		BanSharePolicy::Ban => {
+			apply_shared_ban(
+				http_client,
+				config,
+				db_connection,
+				&target_guild,
+				&shared_ban,
+				origin_guild_name,
+				bot_user_id,
+			)
+			.await?
+		}
+		BanSharePolicy::Alert => false,
+	};
+	if applied {
+		return Ok(());
+	}
+
+	let alert_location = post_shared_ban_alert(http_client, &target_guild, &shared_ban, origin_guild_name).await?;
+	if let Some((alert_channel, alert_message)) = alert_location {
+		diesel::update(shared_bans::table)
+			.filter(shared_bans::id.eq(&shared_ban.id))
+			.set((
+				shared_bans::alert_channel.eq(Some(alert_channel)),
+				shared_bans::alert_message.eq(Some(alert_message)),
+			))
+			.execute(db_connection)
+			.into_diagnostic()?;
+	}
+
+	Ok(())
+}
+
+/// Applies a shared ban or unban in the target guild. Returns whether the guild's bans now match the shared action; if
+/// they don't, staff should be alerted instead.
+async fn apply_shared_ban(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+	target_guild: &Guild,
+	shared_ban: &SharedBan,
+	origin_guild_name: &str,
+	bot_user_id: Id<UserMarker>,
+) -> miette::Result<bool> {
+	let latest_action =
+		latest_ban_action(db_connection, shared_ban.target_guild, shared_ban.banned_user).into_diagnostic()?;
+	let currently_banned = latest_action.as_ref().is_some_and(|action| action.added);
+	if shared_ban.added {
+		if currently_banned {
+			return Ok(true);
+		}
+	} else {
+		// Only lift bans that came from the same guild; a ban staff here applied themselves is left for them to review
+		let Some(latest_action) = latest_action.filter(|action| action.added) else {
+			return Ok(true);
+		};
+		let Some(latest_shared_ban_id) = latest_action.shared_ban.as_ref() else {
+			return Ok(false);
+		};
+		let latest_origin: i64 = shared_bans::table
+			.find(latest_shared_ban_id)
+			.select(shared_bans::origin_guild)
+			.first(db_connection)
+			.into_diagnostic()?;
+		if latest_origin != shared_ban.origin_guild {
+			return Ok(false);
+		}
+	}
+
+	let guild_id = shared_ban.get_target_guild();
+	let banned_user_id = shared_ban.get_banned_user();
+	let action_time = Utc::now();
+	let new_ban_action = BanAction {
+		id: cuid2::create_id(),
+		guild: shared_ban.target_guild,
+		banning_user: database_id_from_discord_id(bot_user_id.get()),
+		banned_user: shared_ban.banned_user,
+		added: shared_ban.added,
+		action_time,
+		reason: shared_ban.reason.clone(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: None,
+		evidence: None,
+		via_bot_command: false,
+		escalation_rule: None,
+		expires_at: None,
+		original_ban: None,
+		shared_ban: Some(shared_ban.id.clone()),
+	};
+	let reason = audit_log_reason(origin_guild_name, &shared_ban.reason);
+	let ban = async {
+		if shared_ban.added {
+			http_client.create_ban(guild_id, banned_user_id).reason(&reason).await
+		} else {
+			http_client.delete_ban(guild_id, banned_user_id).reason(&reason).await
+		}
+	};
+	let ban_result = perform_recorded_action(db_connection, NewActionRecord::Ban(&new_ban_action), ban).await?;
+	let case_number = match ban_result {
+		Ok(case_number) => case_number,
+		Err(error) => {
+			tracing::warn!(source = ?error, guild = %guild_id, "Failed to apply a shared ban; alerting staff instead");
+			return Ok(false);
+		}
+	};
+	let (title, color) = if shared_ban.added {
+		("Ban", MOD_LOG_COLOR_BAN)
+	} else {
+		("Unban", MOD_LOG_COLOR_UNBAN)
+	};
+	let mod_log_entry = ModLogEntry {
+		title,
+		color,
+		case_number: Some(case_number),
+		target_user: Some(banned_user_id),
+		actor: ModLogActor::TrustedGuild {
+			guild_name: origin_guild_name.to_string(),
+		},
+		reason: Some(&new_ban_action.reason),
+		expires_at: None,
+		action_time,
+		details: vec![(
+			"Shared From",
+			format!("{} ({})", origin_guild_name, shared_ban.get_origin_guild()),
+		)],
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		db_connection,
+		target_guild,
+		ModerationActionType::Ban,
+		&new_ban_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	Ok(true)
+}
+
+/// Posts an alert about a shared ban or unban to the target guild's mod log channel, if it has one. Returns the raw
+/// database IDs of the channel and message.
+async fn post_shared_ban_alert(
+	http_client: &Client,
+	target_guild: &Guild,
+	shared_ban: &SharedBan,
+	origin_guild_name: &str,
+) -> miette::Result<Option<(i64, i64)>> {
+	let Some(mod_log_channel) = target_guild.get_mod_log_channel() else {
+		return Ok(None);
+	};
+
+	let banned_user_id = shared_ban.get_banned_user();
+	let (title, color) = if shared_ban.added {
+		("Ban in Trusted Server", MOD_LOG_COLOR_BAN)
+	} else {
+		("Unban in Trusted Server", MOD_LOG_COLOR_UNBAN)
+	};
+	let reason = if shared_ban.reason.is_empty() {
+		String::from("*No reason provided*")
+	} else {
+		shared_ban.reason.clone()
+	};
+	let mut embed = EmbedBuilder::new()
+		.title(title)
+		.color(color)
+		.field(EmbedFieldBuilder::new("User", format!("{} ({})", banned_user_id.mention(), banned_user_id)).inline())
+		.field(
+			EmbedFieldBuilder::new(
+				"Server",
+				format!("{} ({})", origin_guild_name, shared_ban.get_origin_guild()),
+			)
+			.inline(),
+		)
+		.field(EmbedFieldBuilder::new("Reason", reason));
+	if shared_ban.policy == BanSharePolicy::Ban {
+		embed = embed.field(EmbedFieldBuilder::new(
+			"Not Applied",
+			"This couldn't be applied automatically, so it needs to be reviewed by hand.",
+		));
+	}
+	if let Ok(timestamp) = Timestamp::from_micros(shared_ban.shared_at.timestamp_micros()) {
+		embed = embed.timestamp(timestamp);
+	}
+
+	let message = http_client
+		.create_message(mod_log_channel)
+		.embeds(&[embed.build()])
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	Ok(Some((
+		database_id_from_discord_id(mod_log_channel.get()),
+		database_id_from_discord_id(message.id.get()),
+	)))
+}
@@ -0,0 +1,196 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::web_urls::guild_web_url;
+use crate::model::{
+	AutomodAction, BanAction, Guild, KickAction, ModerationActionType, ModerationCase, TimeoutAction, WarnAction,
+};
+use crate::schema::{
+	automod_actions, ban_actions, guilds, kick_actions, moderation_cases, timeout_actions, warn_actions,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+
+/// Gives a moderation action the next case number in its guild.
+///
+/// The guild's counter is read and incremented in a single statement, which holds a lock on the guild row until the
+/// case is recorded, so actions handled at the same time always get different numbers.
+pub fn assign_case_number(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	action_type: ModerationActionType,
+	action_id: &str,
+) -> QueryResult<i32> {
+	db_connection.transaction(|db_connection| {
+		let case_number: i32 = diesel::update(guilds::table.find(db_guild_id))
+			.set(guilds::last_case_number.eq(guilds::last_case_number + 1))
+			.returning(guilds::last_case_number)
+			.get_result(db_connection)?;
+		let new_case = ModerationCase {
+			guild: db_guild_id,
+			case_number,
+			action_type,
+			action_id: action_id.to_string(),
+		};
+		diesel::insert_into(moderation_cases::table)
+			.values(&new_case)
+			.execute(db_connection)?;
+		Ok(case_number)
+	})
+}
+
+/// Gets the case number given to an action, if it has one
+pub fn case_number_for_action(
+	db_connection: &mut PgConnection,
+	action_type: ModerationActionType,
+	action_id: &str,
+) -> QueryResult<Option<i32>> {
+	moderation_cases::table
+		.filter(
+			moderation_cases::action_type
+				.eq(action_type)
+				.and(moderation_cases::action_id.eq(action_id)),
+		)
+		.select(moderation_cases::case_number)
+		.first(db_connection)
+		.optional()
+}
+
+/// Gets the URL of the web page for a case
+pub fn case_url(config: &ConfigData, guild: &Guild, case_number: i32) -> String {
+	guild_web_url(config, guild, &format!("staff/case/{}", case_number))
+}
+
+/// The details of a case, gathered from whichever table holds its action
+pub struct CaseDetails {
+	pub case: ModerationCase,
+	/// A short description of what happened (e.g. "Ban" or "Timeout Removed")
+	pub title: &'static str,
+	pub target_user: Id<UserMarker>,
+	/// The user who performed the action, or [None] if automod did
+	pub moderator: Option<Id<UserMarker>>,
+	pub action_time: DateTime<Utc>,
+	pub reason: String,
+	pub amended_reason: Option<String>,
+	pub evidence: Option<String>,
+	pub expires_at: Option<DateTime<Utc>>,
+	pub mod_log_channel: Option<Id<ChannelMarker>>,
+	pub mod_log_message: Option<Id<MessageMarker>>,
+}
+
+impl CaseDetails {
+	/// The most up-to-date reason for the action, preferring a reason provided after the fact
+	pub fn current_reason(&self) -> &str {
+		self.amended_reason.as_deref().unwrap_or(&self.reason)
+	}
+}
+
+/// Looks up a case by its number in a guild
+pub fn find_case(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	case_number: i32,
+) -> QueryResult<Option<CaseDetails>> {
+	let case: Option<ModerationCase> = moderation_cases::table
+		.find((db_guild_id, case_number))
+		.first(db_connection)
+		.optional()?;
+	let Some(case) = case else {
+		return Ok(None);
+	};
+
+	let details = match case.action_type {
+		ModerationActionType::Automod => {
+			let action: AutomodAction = automod_actions::table.find(&case.action_id).first(db_connection)?;
+			CaseDetails {
+				title: action.action_type.title(),
+				target_user: action.get_target_user(),
+				moderator: None,
+				action_time: action.action_time,
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				reason: action.reason,
+				amended_reason: None,
+				evidence: None,
+				expires_at: None,
+				case,
+			}
+		}
+		ModerationActionType::Ban => {
+			let action: BanAction = ban_actions::table.find(&case.action_id).first(db_connection)?;
+			CaseDetails {
+				title: if action.added { "Ban" } else { "Unban" },
+				target_user: action.get_banned_user(),
+				moderator: Some(action.get_banning_user()),
+				action_time: action.action_time,
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				reason: action.reason,
+				amended_reason: action.amended_reason,
+				evidence: action.evidence,
+				expires_at: action.expires_at,
+				case,
+			}
+		}
+		ModerationActionType::Kick => {
+			let action: KickAction = kick_actions::table.find(&case.action_id).first(db_connection)?;
+			CaseDetails {
+				title: "Kick",
+				target_user: action.get_kicked_user(),
+				moderator: Some(action.get_kicking_user()),
+				action_time: action.action_time,
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				reason: action.reason,
+				amended_reason: action.amended_reason,
+				evidence: action.evidence,
+				expires_at: None,
+				case,
+			}
+		}
+		ModerationActionType::Timeout => {
+			let action: TimeoutAction = timeout_actions::table.find(&case.action_id).first(db_connection)?;
+			CaseDetails {
+				title: if action.timeout_until.is_some() {
+					"Timeout"
+				} else {
+					"Timeout Removed"
+				},
+				target_user: action.get_target_user(),
+				moderator: Some(action.get_performing_user()),
+				action_time: action.action_time,
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				reason: action.reason,
+				amended_reason: action.amended_reason,
+				evidence: action.evidence,
+				expires_at: action.timeout_until,
+				case,
+			}
+		}
+		ModerationActionType::Warn => {
+			let action: WarnAction = warn_actions::table.find(&case.action_id).first(db_connection)?;
+			CaseDetails {
+				title: "Warning",
+				target_user: action.get_warned_user(),
+				moderator: Some(action.get_warning_user()),
+				action_time: action.action_time,
+				mod_log_channel: action.get_mod_log_channel(),
+				mod_log_message: action.get_mod_log_message(),
+				reason: action.reason,
+				amended_reason: action.amended_reason,
+				evidence: action.evidence,
+				expires_at: action.expires_at,
+				case,
+			}
+		}
+	};
+
+	Ok(Some(details))
+}
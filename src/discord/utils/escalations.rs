@@ -0,0 +1,36 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{EscalationActionType, EscalationRule};
+
+/// Describes a duration given in minutes using the largest whole unit
+pub fn describe_minutes(minutes: i32) -> String {
+	let (amount, unit) = if minutes % (60 * 24) == 0 {
+		(minutes / (60 * 24), "day")
+	} else if minutes % 60 == 0 {
+		(minutes / 60, "hour")
+	} else {
+		(minutes, "minute")
+	};
+	if amount == 1 {
+		format!("1 {}", unit)
+	} else {
+		format!("{} {}s", amount, unit)
+	}
+}
+
+/// Describes an escalation rule for staff (e.g. "3 automod blocks within 30 days → 1 day timeout")
+pub fn describe_escalation_rule(rule: &EscalationRule) -> String {
+	let trigger = match rule.window_days {
+		Some(days) => format!("{} {} within {} days", rule.threshold, rule.trigger_type, days),
+		None => format!("{} {}", rule.threshold, rule.trigger_type),
+	};
+	let action = match (rule.action_type, rule.timeout_minutes) {
+		(EscalationActionType::Timeout, Some(minutes)) => format!("{} timeout", describe_minutes(minutes)),
+		(action_type, _) => action_type.to_string(),
+	};
+	format!("{} → {}", trigger, action)
+}
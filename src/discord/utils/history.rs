@@ -0,0 +1,403 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{
+	AutomodAction, BanAction, KickAction, Ticket, TimeoutAction, WarnAction, database_id_from_discord_id,
+};
+use crate::schema::{
+	automod_actions, ban_actions, kick_actions, moderation_cases, ticket_messages, tickets, timeout_actions,
+	warn_actions,
+};
+use chrono::{DateTime, Utc};
+use diesel::dsl::min;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::channel::message::component::{
+	ActionRow, Button, ButtonStyle, Component, SelectMenu, SelectMenuOption, SelectMenuType,
+};
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+/// How many history entries are shown on each page
+const HISTORY_PAGE_SIZE: usize = 8;
+
+/// How much of a reason is shown in a history entry before it's cut off
+const HISTORY_REASON_LENGTH: usize = 120;
+
+/// The kinds of history entries that can be shown
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HistoryFilter {
+	All,
+	Bans,
+	Kicks,
+	Timeouts,
+	Warnings,
+	Automod,
+	Tickets,
+}
+
+impl HistoryFilter {
+	const ALL_FILTERS: [Self; 7] = [
+		Self::All,
+		Self::Bans,
+		Self::Kicks,
+		Self::Timeouts,
+		Self::Warnings,
+		Self::Automod,
+		Self::Tickets,
+	];
+
+	/// Gets the name used to refer to the filter in command options and custom IDs
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::All => "all",
+			Self::Bans => "bans",
+			Self::Kicks => "kicks",
+			Self::Timeouts => "timeouts",
+			Self::Warnings => "warnings",
+			Self::Automod => "automod",
+			Self::Tickets => "tickets",
+		}
+	}
+
+	/// Parses a filter from its name
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL_FILTERS.into_iter().find(|filter| filter.name() == name)
+	}
+
+	/// Gets the name of the filter to show to users
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::All => "Everything",
+			Self::Bans => "Bans",
+			Self::Kicks => "Kicks",
+			Self::Timeouts => "Timeouts",
+			Self::Warnings => "Warnings",
+			Self::Automod => "AutoMod",
+			Self::Tickets => "Tickets",
+		}
+	}
+
+	/// Gets the choices to offer for a command option that picks a filter
+	pub fn command_choices() -> Vec<(&'static str, &'static str)> {
+		Self::ALL_FILTERS
+			.iter()
+			.map(|filter| (filter.label(), filter.name()))
+			.collect()
+	}
+
+	fn includes(&self, other: Self) -> bool {
+		*self == Self::All || *self == other
+	}
+}
+
+/// A single entry in a user's history
+struct HistoryEntry {
+	time: DateTime<Utc>,
+	title: &'static str,
+	/// For moderation actions, the action's ID, used to find its case number
+	action_id: Option<String>,
+	details: String,
+}
+
+/// Describes who took an action and why for a history entry
+fn describe_action(moderator: Option<Id<UserMarker>>, reason: &str) -> String {
+	let mut description = String::new();
+	if let Some(moderator) = moderator {
+		description = format!(" by {}", moderator.mention());
+	}
+	if !reason.is_empty() {
+		let mut reason_chars = reason.chars();
+		let shortened_reason: String = reason_chars.by_ref().take(HISTORY_REASON_LENGTH).collect();
+		if reason_chars.next().is_some() {
+			description = format!("{}: {}…", description, shortened_reason);
+		} else {
+			description = format!("{}: {}", description, shortened_reason);
+		}
+	}
+	description
+}
+
+/// Loads the entries in a user's history that match the filter, newest first
+fn load_history(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	user_id: Id<UserMarker>,
+	filter: HistoryFilter,
+) -> QueryResult<Vec<HistoryEntry>> {
+	let db_user_id = database_id_from_discord_id(user_id.get());
+
+	let mut entries: Vec<HistoryEntry> = Vec::new();
+
+	if filter.includes(HistoryFilter::Bans) {
+		let actions: Vec<BanAction> = ban_actions::table
+			.filter(
+				ban_actions::guild
+					.eq(db_guild_id)
+					.and(ban_actions::banned_user.eq(db_user_id)),
+			)
+			.load(db_connection)?;
+		for action in actions {
+			let title = if action.added { "Ban" } else { "Unban" };
+			entries.push(HistoryEntry {
+				time: action.action_time,
+				title,
+				details: describe_action(Some(action.get_banning_user()), action.current_reason()),
+				action_id: Some(action.id),
+			});
+		}
+	}
+
+	if filter.includes(HistoryFilter::Kicks) {
+		let actions: Vec<KickAction> = kick_actions::table
+			.filter(
+				kick_actions::guild
+					.eq(db_guild_id)
+					.and(kick_actions::kicked_user.eq(db_user_id)),
+			)
+			.load(db_connection)?;
+		for action in actions {
+			entries.push(HistoryEntry {
+				time: action.action_time,
+				title: "Kick",
+				details: describe_action(Some(action.get_kicking_user()), action.current_reason()),
+				action_id: Some(action.id),
+			});
+		}
+	}
+
+	if filter.includes(HistoryFilter::Timeouts) {
+		let actions: Vec<TimeoutAction> = timeout_actions::table
+			.filter(
+				timeout_actions::guild
+					.eq(db_guild_id)
+					.and(timeout_actions::target_user.eq(db_user_id)),
+			)
+			.load(db_connection)?;
+		for action in actions {
+			let title = if action.timeout_until.is_some() {
+				"Timeout"
+			} else {
+				"Timeout Removed"
+			};
+			entries.push(HistoryEntry {
+				time: action.action_time,
+				title,
+				details: describe_action(Some(action.get_performing_user()), action.current_reason()),
+				action_id: Some(action.id),
+			});
+		}
+	}
+
+	if filter.includes(HistoryFilter::Warnings) {
+		let actions: Vec<WarnAction> = warn_actions::table
+			.filter(
+				warn_actions::guild
+					.eq(db_guild_id)
+					.and(warn_actions::warned_user.eq(db_user_id)),
+			)
+			.load(db_connection)?;
+		for action in actions {
+			entries.push(HistoryEntry {
+				time: action.action_time,
+				title: "Warning",
+				details: describe_action(Some(action.get_warning_user()), action.current_reason()),
+				action_id: Some(action.id),
+			});
+		}
+	}
+
+	if filter.includes(HistoryFilter::Automod) {
+		let actions: Vec<AutomodAction> = automod_actions::table
+			.filter(
+				automod_actions::guild
+					.eq(db_guild_id)
+					.and(automod_actions::target_user.eq(db_user_id)),
+			)
+			.load(db_connection)?;
+		for action in actions {
+			let title = action.action_type.title();
+			entries.push(HistoryEntry {
+				time: action.action_time,
+				title,
+				details: describe_action(None, &action.rule_name),
+				action_id: Some(action.id),
+			});
+		}
+	}
+
+	if filter.includes(HistoryFilter::Tickets) {
+		let user_tickets: Vec<Ticket> = tickets::table
+			.filter(tickets::guild.eq(db_guild_id).and(tickets::with_user.eq(db_user_id)))
+			.load(db_connection)?;
+		let ticket_ids: Vec<&str> = user_tickets.iter().map(|ticket| ticket.id.as_str()).collect();
+		// Tickets don't store when they were opened, so the first message stands in for it
+		let opened_times: HashMap<String, Option<DateTime<Utc>>> = ticket_messages::table
+			.filter(ticket_messages::ticket.eq_any(&ticket_ids))
+			.group_by(ticket_messages::ticket)
+			.select((ticket_messages::ticket, min(ticket_messages::send_time)))
+			.load::<(String, Option<DateTime<Utc>>)>(db_connection)?
+			.into_iter()
+			.collect();
+		for ticket in user_tickets {
+			let opened_at = opened_times.get(&ticket.id).copied().flatten();
+			let Some(time) = opened_at.or(ticket.closed_at) else {
+				continue;
+			};
+			let title = if ticket.closed_at.is_some() {
+				"Ticket (closed)"
+			} else {
+				"Ticket (open)"
+			};
+			entries.push(HistoryEntry {
+				time,
+				title,
+				action_id: None,
+				details: format!(": {} in {}", ticket.title, ticket.get_staff_thread().mention()),
+			});
+		}
+	}
+
+	entries.sort_by(|a, b| b.time.cmp(&a.time));
+	Ok(entries)
+}
+
+/// Summarizes a user's history as a count of each kind of entry, with the kind seen most recently first
+pub fn history_summary(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	user_id: Id<UserMarker>,
+) -> QueryResult<String> {
+	let entries = load_history(db_connection, db_guild_id, user_id, HistoryFilter::All)?;
+	if entries.is_empty() {
+		return Ok(String::from("Nothing has been recorded."));
+	}
+
+	let mut counts: Vec<(&'static str, usize)> = Vec::new();
+	for entry in entries.iter() {
+		match counts.iter_mut().find(|(title, _)| *title == entry.title) {
+			Some((_, count)) => *count += 1,
+			None => counts.push((entry.title, 1)),
+		}
+	}
+	let summary: Vec<String> = counts
+		.iter()
+		.map(|(title, count)| format!("{}: {}", title, count))
+		.collect();
+	Ok(summary.join(", "))
+}
+
+/// A page of a user's history, ready to be sent as a message
+pub struct HistoryPage {
+	pub content: String,
+	pub components: Vec<Component>,
+}
+
+/// Builds a page of a user's moderation history, with controls to change the filter and page
+pub fn history_page(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	user_id: Id<UserMarker>,
+	filter: HistoryFilter,
+	page: usize,
+) -> QueryResult<HistoryPage> {
+	let entries = load_history(db_connection, db_guild_id, user_id, filter)?;
+	let page_count = entries.len().div_ceil(HISTORY_PAGE_SIZE).max(1);
+	let page = page.min(page_count - 1);
+
+	let mut lines = vec![format!(
+		"Moderation history for {} ({}), page {} of {}",
+		user_id.mention(),
+		filter.label(),
+		page + 1,
+		page_count
+	)];
+	if entries.is_empty() {
+		lines.push(String::from("Nothing has been recorded."));
+	}
+	let page_entries: Vec<&HistoryEntry> = entries
+		.iter()
+		.skip(page * HISTORY_PAGE_SIZE)
+		.take(HISTORY_PAGE_SIZE)
+		.collect();
+	// Action IDs are unique across all action types, so they're enough to find each action's case
+	let page_action_ids: Vec<&str> = page_entries
+		.iter()
+		.filter_map(|entry| entry.action_id.as_deref())
+		.collect();
+	let case_numbers: HashMap<String, i32> = moderation_cases::table
+		.filter(moderation_cases::action_id.eq_any(&page_action_ids))
+		.select((moderation_cases::action_id, moderation_cases::case_number))
+		.load::<(String, i32)>(db_connection)?
+		.into_iter()
+		.collect();
+	for entry in page_entries {
+		let time = MentionTimestamp::new(entry.time.timestamp() as u64, Some(TimestampStyle::ShortDate));
+		let case_number = entry.action_id.as_ref().and_then(|id| case_numbers.get(id));
+		let line = match case_number {
+			Some(case_number) => format!(
+				"- {} **{}** (case {}){}",
+				time.mention(),
+				entry.title,
+				case_number,
+				entry.details
+			),
+			None => format!("- {} **{}**{}", time.mention(), entry.title, entry.details),
+		};
+		lines.push(line);
+	}
+
+	let filter_options: Vec<SelectMenuOption> = HistoryFilter::ALL_FILTERS
+		.iter()
+		.map(|option| SelectMenuOption {
+			default: *option == filter,
+			description: None,
+			emoji: None,
+			label: String::from(option.label()),
+			value: String::from(option.name()),
+		})
+		.collect();
+	let filter_select = SelectMenu {
+		channel_types: None,
+		custom_id: format!("history/{}/filter", user_id),
+		default_values: None,
+		disabled: false,
+		kind: SelectMenuType::Text,
+		max_values: None,
+		min_values: None,
+		options: Some(filter_options),
+		placeholder: Some(String::from("Show")),
+	};
+	let page_button = |label: &str, target_page: usize, disabled: bool| {
+		Component::Button(Button {
+			custom_id: Some(format!("history/{}/page/{}/{}", user_id, filter.name(), target_page)),
+			disabled,
+			emoji: None,
+			label: Some(String::from(label)),
+			style: ButtonStyle::Secondary,
+			url: None,
+			sku_id: None,
+		})
+	};
+	let previous_button = page_button("Previous", page.saturating_sub(1), page == 0);
+	let next_button = page_button("Next", page + 1, page + 1 >= page_count);
+
+	let components = vec![
+		Component::ActionRow(ActionRow {
+			components: vec![Component::SelectMenu(filter_select)],
+		}),
+		Component::ActionRow(ActionRow {
+			components: vec![previous_button, next_button],
+		}),
+	];
+
+	Ok(HistoryPage {
+		content: lines.join("\n"),
+		components,
+	})
+}
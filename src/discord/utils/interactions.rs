@@ -0,0 +1,40 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::ApplicationMarker;
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+/// Responds to an interaction with a text message. Mentions in the message don't ping anyone.
+pub async fn respond_with_message(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	content: String,
+	ephemeral: bool,
+) -> miette::Result<()> {
+	let mut response = InteractionResponseDataBuilder::new()
+		.content(content)
+		.allowed_mentions(AllowedMentions::default());
+	if ephemeral {
+		response = response.flags(MessageFlags::EPHEMERAL);
+	}
+	let response = InteractionResponse {
+		kind: InteractionResponseType::ChannelMessageWithSource,
+		data: Some(response.build()),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
@@ -4,9 +4,28 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub mod action_reasons;
+pub mod ban_sharing;
+pub mod cases;
+pub mod escalations;
+pub mod history;
+pub mod interactions;
 pub mod invites;
+pub mod mod_log;
+pub mod moderation;
+pub mod partner_invites;
+pub mod partner_list;
+pub mod partnerships;
 pub mod permissions;
+pub mod reason_policy;
+pub mod rules;
 pub mod setup;
+pub mod tcn;
+pub mod temporary_bans;
+pub mod ticket_departures;
 pub mod tickets;
 pub mod timestamp;
+pub mod tracked_actions;
 pub mod users;
+pub mod warnings;
+pub mod web_urls;
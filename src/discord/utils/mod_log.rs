@@ -0,0 +1,264 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::cases::case_url;
+use crate::discord::utils::web_urls::guild_web_url;
+use crate::model::Guild;
+use chrono::{DateTime, Utc};
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::channel::message::embed::EmbedField;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ChannelMarker, MessageMarker, UserMarker};
+use twilight_model::util::datetime::Timestamp;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+const REASON_FIELD_NAME: &str = "Reason";
+const ORIGINAL_REASON_FIELD_NAME: &str = "Original Reason";
+const DURATION_FIELD_NAME: &str = "Duration";
+
+/// Who performed a logged moderation action
+pub enum ModLogActor {
+	/// A user (which may be a bot) performed the action
+	User(Id<UserMarker>),
+	/// Discord's AutoMod performed the action based on the named rule
+	AutoMod { rule_name: String },
+	/// The bot performed the action because of an escalation rule, described in the variant
+	Escalation { rule_description: String },
+	/// The bot performed the action because a trusted guild, named in the variant, shared it
+	TrustedGuild { guild_name: String },
+}
+
+/// The data for a moderation action to post to the mod log
+pub struct ModLogEntry<'a> {
+	/// The title of the entry (e.g. "Ban")
+	pub title: &'a str,
+	/// The color of the embed
+	pub color: u32,
+	/// The case number given to the action, if it's a moderation action that gets one
+	pub case_number: Option<i32>,
+	/// The user the action was taken against, if the action was taken against a single known user
+	pub target_user: Option<Id<UserMarker>>,
+	/// Who performed the action
+	pub actor: ModLogActor,
+	/// The reason for the action, if the action type can have a reason
+	pub reason: Option<&'a str>,
+	/// When the action expires, if it does
+	pub expires_at: Option<DateTime<Utc>>,
+	/// When the action took place
+	pub action_time: DateTime<Utc>,
+	/// Any additional fields specific to the type of action, as (name, value) pairs
+	pub details: Vec<(&'a str, String)>,
+}
+
+pub const MOD_LOG_COLOR_BAN: u32 = 0xed4245;
+pub const MOD_LOG_COLOR_UNBAN: u32 = 0x57f287;
+pub const MOD_LOG_COLOR_KICK: u32 = 0xe67e22;
+pub const MOD_LOG_COLOR_TIMEOUT: u32 = 0xfee75c;
+pub const MOD_LOG_COLOR_WARN: u32 = 0xf1c40f;
+pub const MOD_LOG_COLOR_AUTOMOD: u32 = 0x5865f2;
+pub const MOD_LOG_COLOR_MEMBER_UPDATE: u32 = 0x3498db;
+pub const MOD_LOG_COLOR_MESSAGE_DELETE: u32 = 0x992d22;
+pub const MOD_LOG_COLOR_VOICE: u32 = 0x95a5a6;
+pub const MOD_LOG_COLOR_STAFF_REVIEW: u32 = 0x9b59b6;
+
+/// Gets the URL of the web page showing a user's history in the guild
+pub fn user_history_url(config: &ConfigData, guild: &Guild, user_id: Id<UserMarker>) -> String {
+	guild_web_url(config, guild, &format!("staff/user/{}", user_id))
+}
+
+fn reason_text(reason: &str) -> String {
+	if reason.is_empty() {
+		String::from("*No reason provided*")
+	} else {
+		reason.to_string()
+	}
+}
+
+fn expiry_text(expires_at: DateTime<Utc>) -> String {
+	let expiry = expires_at.timestamp() as u64;
+	format!(
+		"Until {} ({})",
+		MentionTimestamp::new(expiry, Some(TimestampStyle::LongDateTime)).mention(),
+		MentionTimestamp::new(expiry, Some(TimestampStyle::RelativeTime)).mention()
+	)
+}
+
+/// Posts an entry to the guild's mod log channel, if one is set up.
+///
+/// Returns the channel and message IDs of the posted entry so the entry can be updated later.
+pub async fn post_mod_log_entry(
+	http_client: &Client,
+	config: &ConfigData,
+	guild: &Guild,
+	entry: &ModLogEntry<'_>,
+) -> miette::Result<Option<(Id<ChannelMarker>, Id<MessageMarker>)>> {
+	let Some(mod_log_channel) = guild.get_mod_log_channel() else {
+		return Ok(None);
+	};
+
+	let actor = match &entry.actor {
+		ModLogActor::User(user_id) => user_id.mention().to_string(),
+		ModLogActor::AutoMod { rule_name } => format!("AutoMod (rule: {})", rule_name),
+		ModLogActor::Escalation { rule_description } => format!("Escalation rule ({})", rule_description),
+		ModLogActor::TrustedGuild { guild_name } => format!("Shared by {}", guild_name),
+	};
+	let mut embed = EmbedBuilder::new().color(entry.color);
+	embed = match entry.case_number {
+		Some(case_number) => embed
+			.title(format!("Case {}: {}", case_number, entry.title))
+			.url(case_url(config, guild, case_number)),
+		None => embed.title(entry.title),
+	};
+	if let Some(target_user) = entry.target_user {
+		embed = embed
+			.field(EmbedFieldBuilder::new("User", format!("{} ({})", target_user.mention(), target_user)).inline());
+	}
+	embed = embed.field(EmbedFieldBuilder::new("Moderator", actor).inline());
+	for (name, value) in entry.details.iter() {
+		embed = embed.field(EmbedFieldBuilder::new(*name, value));
+	}
+	if let Some(reason) = entry.reason {
+		embed = embed.field(EmbedFieldBuilder::new(REASON_FIELD_NAME, reason_text(reason)));
+	}
+	if let Some(expires_at) = entry.expires_at {
+		embed = embed.field(EmbedFieldBuilder::new(DURATION_FIELD_NAME, expiry_text(expires_at)));
+	}
+	if let Some(target_user) = entry.target_user {
+		let history_link = format!("[View history]({})", user_history_url(config, guild, target_user));
+		embed = embed.field(EmbedFieldBuilder::new("History", history_link));
+	}
+	if let Ok(timestamp) = Timestamp::from_micros(entry.action_time.timestamp_micros()) {
+		embed = embed.timestamp(timestamp);
+	}
+	let embed = embed.build();
+
+	let message = http_client
+		.create_message(mod_log_channel)
+		.embeds(&[embed])
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	Ok(Some((mod_log_channel, message.id)))
+}
+
+/// Posts an entry to the guild's mod log channel like [post_mod_log_entry], but logs problems instead of returning
+/// them so they don't stop the rest of an action from being handled
+pub async fn try_post_mod_log_entry(
+	http_client: &Client,
+	config: &ConfigData,
+	guild: &Guild,
+	entry: &ModLogEntry<'_>,
+) -> Option<(Id<ChannelMarker>, Id<MessageMarker>)> {
+	match post_mod_log_entry(http_client, config, guild, entry).await {
+		Ok(location) => location,
+		Err(error) => {
+			tracing::warn!(source = ?error, entry = entry.title, "Failed to post an entry to the mod log");
+			None
+		}
+	}
+}
+
+/// Updates the reason on a previously-posted mod log entry, keeping the original reason visible
+pub async fn update_mod_log_reason(
+	http_client: &Client,
+	channel_id: Id<ChannelMarker>,
+	message_id: Id<MessageMarker>,
+	amended_reason: &str,
+	amended_by: Id<UserMarker>,
+) -> miette::Result<()> {
+	let message = http_client
+		.message(channel_id, message_id)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let Some(mut embed) = message.embeds.into_iter().next() else {
+		return Ok(());
+	};
+
+	let new_reason = format!("{}\n(provided by {})", amended_reason, amended_by.mention());
+	let original_reason_index = embed
+		.fields
+		.iter()
+		.position(|field| field.name == ORIGINAL_REASON_FIELD_NAME);
+	let reason_index = embed.fields.iter().position(|field| field.name == REASON_FIELD_NAME);
+	match (reason_index, original_reason_index) {
+		(Some(index), Some(_)) => embed.fields[index].value = new_reason,
+		(Some(index), None) => {
+			let original_reason = std::mem::replace(&mut embed.fields[index].value, new_reason);
+			embed.fields.insert(
+				index + 1,
+				EmbedField {
+					inline: false,
+					name: String::from(ORIGINAL_REASON_FIELD_NAME),
+					value: original_reason,
+				},
+			);
+		}
+		(None, _) => embed.fields.push(EmbedField {
+			inline: false,
+			name: String::from(REASON_FIELD_NAME),
+			value: new_reason,
+		}),
+	}
+
+	http_client
+		.update_message(channel_id, message_id)
+		.embeds(Some(&[embed]))
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+/// Updates when the action on a previously-posted mod log entry expires, noting who changed it
+pub async fn update_mod_log_expiry(
+	http_client: &Client,
+	channel_id: Id<ChannelMarker>,
+	message_id: Id<MessageMarker>,
+	expires_at: Option<DateTime<Utc>>,
+	changed_by: Id<UserMarker>,
+) -> miette::Result<()> {
+	let message = http_client
+		.message(channel_id, message_id)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let Some(mut embed) = message.embeds.into_iter().next() else {
+		return Ok(());
+	};
+
+	let new_duration = match expires_at {
+		Some(expires_at) => format!("{}\n(changed by {})", expiry_text(expires_at), changed_by.mention()),
+		None => format!("Permanent\n(changed by {})", changed_by.mention()),
+	};
+	match embed.fields.iter().position(|field| field.name == DURATION_FIELD_NAME) {
+		Some(index) => embed.fields[index].value = new_duration,
+		None => embed.fields.push(EmbedField {
+			inline: false,
+			name: String::from(DURATION_FIELD_NAME),
+			value: new_duration,
+		}),
+	}
+
+	http_client
+		.update_message(channel_id, message_id)
+		.embeds(Some(&[embed]))
+		.await
+		.into_diagnostic()?;
+
+	Ok(())
+}
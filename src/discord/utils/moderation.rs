@@ -0,0 +1,474 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::cases::assign_case_number;
+use crate::discord::utils::mod_log::{ModLogEntry, try_post_mod_log_entry};
+use crate::discord::utils::permissions::moderator_outranks_target;
+use crate::discord::utils::reason_policy::check_reason_policy;
+use crate::model::{BanAction, Guild, KickAction, ModerationActionType, TimeoutAction, database_id_from_discord_id};
+use crate::schema::{automod_actions, ban_actions, kick_actions, timeout_actions, warn_actions};
+use chrono::TimeDelta;
+use diesel::prelude::*;
+use miette::{IntoDiagnostic, bail};
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::application::command::CommandOption;
+use twilight_model::application::interaction::application_command::{CommandDataOption, CommandOptionValue};
+use twilight_model::channel::message::{AllowedMentions, MessageFlags};
+use twilight_model::gateway::payload::incoming::InteractionCreate;
+use twilight_model::guild::PartialMember;
+use twilight_model::http::interaction::{InteractionResponse, InteractionResponseType};
+use twilight_model::id::Id;
+use twilight_model::id::marker::{ApplicationMarker, ChannelMarker, GuildMarker, MessageMarker, UserMarker};
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::command::{BooleanBuilder, IntegerBuilder, StringBuilder, UserBuilder};
+
+/// The longest reason accepted by the moderation commands. This is shorter than Discord's audit log reason limit to
+/// leave room for the moderator's name, since the audit log shows the bot as having performed the action.
+pub const MAX_COMMAND_REASON_LENGTH: u16 = 400;
+
+/// The longest list of evidence links accepted by the moderation commands
+pub const MAX_EVIDENCE_LENGTH: u16 = 1000;
+
+/// The longest audit log reason Discord accepts
+const MAX_AUDIT_LOG_REASON_LENGTH: usize = 512;
+
+/// How long after the bot records an action it takes itself (through a moderation command, an escalation rule, or an
+/// expiring temporary ban) the action's audit log entry can be matched to the record
+pub const COMMAND_ACTION_CLAIM_WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+/// The prefixes used by links to Discord messages
+const MESSAGE_LINK_PREFIXES: [&str; 4] = [
+	"https://discord.com/channels/",
+	"https://ptb.discord.com/channels/",
+	"https://canary.discord.com/channels/",
+	"https://discordapp.com/channels/",
+];
+
+pub fn user_option(description: &str) -> CommandOption {
+	UserBuilder::new("user", description).required(true).build()
+}
+
+pub fn reason_option(description: &str) -> CommandOption {
+	StringBuilder::new("reason", description)
+		.max_length(MAX_COMMAND_REASON_LENGTH)
+		.required(true)
+		.build()
+}
+
+pub fn evidence_option() -> CommandOption {
+	StringBuilder::new(
+		"evidence",
+		"Links to messages that show why the action was taken, separated by spaces",
+	)
+	.max_length(MAX_EVIDENCE_LENGTH)
+	.build()
+}
+
+pub fn rule_number_option() -> CommandOption {
+	IntegerBuilder::new("rule_number", "The number of the server rule that was broken")
+		.min_value(1)
+		.build()
+}
+
+pub fn dm_user_option() -> CommandOption {
+	BooleanBuilder::new(
+		"dm_user",
+		"Whether to send the user a DM about the action (defaults to yes)",
+	)
+	.build()
+}
+
+/// The options shared by all of the moderation commands
+pub struct ModerationCommandOptions {
+	pub target_user: Id<UserMarker>,
+	pub reason: String,
+	pub evidence: Option<String>,
+	/// The number of the server rule the moderator chose as having been broken
+	pub rule_number: Option<i32>,
+	pub dm_user: bool,
+}
+
+impl ModerationCommandOptions {
+	/// Parses the shared options from a moderation command, also returning the options specific to the command
+	pub fn parse<'a>(
+		command_name: &str,
+		options: &'a [CommandDataOption],
+	) -> miette::Result<(Self, Vec<&'a CommandDataOption>)> {
+		let mut target_user: Option<Id<UserMarker>> = None;
+		let mut reason: Option<String> = None;
+		let mut evidence: Option<String> = None;
+		let mut rule_number: Option<i64> = None;
+		let mut dm_user = true;
+		let mut other_options: Vec<&CommandDataOption> = Vec::new();
+		for option in options.iter() {
+			match (option.name.as_str(), &option.value) {
+				("user", CommandOptionValue::User(user)) => target_user = Some(*user),
+				("reason", CommandOptionValue::String(value)) => reason = Some(value.trim().to_string()),
+				("evidence", CommandOptionValue::String(value)) => evidence = Some(value.trim().to_string()),
+				("rule_number", CommandOptionValue::Integer(value)) => rule_number = Some(*value),
+				("dm_user", CommandOptionValue::Boolean(value)) => dm_user = *value,
+				_ => other_options.push(option),
+			}
+		}
+
+		let Some(target_user) = target_user else {
+			bail!("Command data is malformed; `/{}` requires a user", command_name);
+		};
+		let Some(reason) = reason else {
+			bail!("Command data is malformed; `/{}` requires a reason", command_name);
+		};
+		let evidence = evidence.filter(|evidence| !evidence.is_empty());
+		let rule_number = rule_number.and_then(|number| i32::try_from(number).ok());
+
+		Ok((
+			Self {
+				target_user,
+				reason,
+				evidence,
+				rule_number,
+				dm_user,
+			},
+			other_options,
+		))
+	}
+}
+
+/// Checks whether a string is a link to a Discord message
+fn is_message_link(link: &str) -> bool {
+	let Some(path) = MESSAGE_LINK_PREFIXES
+		.iter()
+		.find_map(|prefix| link.strip_prefix(prefix))
+	else {
+		return false;
+	};
+	let parts: Vec<&str> = path.split('/').collect();
+	parts.len() == 3
+		&& parts
+			.iter()
+			.all(|part| !part.is_empty() && part.bytes().all(|c| c.is_ascii_digit()))
+}
+
+/// Checks whether the moderator may take the action as requested, returning a message explaining why not if they can't.
+///
+/// The action verb is used in the explanation (e.g. "ban").
+pub async fn moderation_refusal(
+	db_connection: &mut PgConnection,
+	http_client: &Client,
+	guild_id: Id<GuildMarker>,
+	moderator_id: Id<UserMarker>,
+	moderator: &PartialMember,
+	options: &ModerationCommandOptions,
+	action_verb: &str,
+) -> miette::Result<Option<String>> {
+	if options.target_user == moderator_id {
+		return Ok(Some(format!("You can't {} yourself.", action_verb)));
+	}
+
+	if let Some(evidence) = &options.evidence {
+		if !evidence.split_whitespace().all(is_message_link) {
+			return Ok(Some(String::from(
+				"Evidence must be one or more message links separated by spaces.",
+			)));
+		}
+	}
+
+	let policy_failure =
+		check_reason_policy(db_connection, http_client, guild_id, moderator_id, &options.reason).await?;
+	if let Some(policy_failure) = policy_failure {
+		return Ok(Some(format!("Your reason can't be used because {}.", policy_failure)));
+	}
+
+	if !moderator_outranks_target(
+		guild_id,
+		moderator_id,
+		&moderator.roles,
+		options.target_user,
+		http_client,
+	)
+	.await?
+	{
+		return Ok(Some(format!(
+			"You can't {} {} because their highest role isn't below yours.",
+			action_verb,
+			options.target_user.mention()
+		)));
+	}
+
+	Ok(None)
+}
+
+/// Generates the reason to give Discord for the audit log, crediting the moderator who used the command
+pub fn audit_log_reason(moderator_name: &str, reason: &str) -> String {
+	let audit_log_reason = format!("{}: {}", moderator_name, reason);
+	audit_log_reason.chars().take(MAX_AUDIT_LOG_REASON_LENGTH).collect()
+}
+
+/// Sends a user a DM telling them about an action taken against them.
+///
+/// The action description should describe what happened to the user (e.g. "banned from"), and it'll be followed by the
+/// guild name. Returns whether the DM was sent; users can block DMs, so failure is expected.
+pub async fn dm_user_about_action(
+	http_client: &Client,
+	guild_id: Id<GuildMarker>,
+	user_id: Id<UserMarker>,
+	action_description: &str,
+	reason: &str,
+) -> bool {
+	let send_result = dm_user_from_guild(http_client, guild_id, user_id, |guild_name| {
+		format!(
+			"You have been {} **{}**.\nReason: {}",
+			action_description, guild_name, reason
+		)
+	})
+	.await;
+
+	match send_result {
+		Ok(()) => true,
+		Err(error) => {
+			tracing::info!(source = ?error, "Couldn't DM a user about a moderation action");
+			false
+		}
+	}
+}
+
+/// Tells a user who was sent a DM by [dm_user_about_action] before the action was taken that the action failed, so
+/// they weren't left with a false notice
+pub async fn dm_user_about_failed_action(
+	http_client: &Client,
+	guild_id: Id<GuildMarker>,
+	user_id: Id<UserMarker>,
+	action_description: &str,
+) {
+	let send_result = dm_user_from_guild(http_client, guild_id, user_id, |guild_name| {
+		format!(
+			"Please disregard the previous message: you have not been {} **{}**.",
+			action_description, guild_name
+		)
+	})
+	.await;
+
+	if let Err(error) = send_result {
+		tracing::warn!(source = ?error, "Couldn't correct a DM about a moderation action that failed");
+	}
+}
+
+/// Sends a user a DM about a guild, building the message from the guild's name
+async fn dm_user_from_guild(
+	http_client: &Client,
+	guild_id: Id<GuildMarker>,
+	user_id: Id<UserMarker>,
+	message: impl FnOnce(&str) -> String,
+) -> miette::Result<()> {
+	let guild = http_client
+		.guild(guild_id)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let channel = http_client
+		.create_private_channel(user_id)
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	http_client
+		.create_message(channel.id)
+		.content(&message(&guild.name))
+		.allowed_mentions(Some(&AllowedMentions::default()))
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
+
+/// Describes whether the user was sent a DM, for the moderator's confirmation message
+pub fn dm_result_text(dm_sent: Option<bool>) -> &'static str {
+	match dm_sent {
+		Some(true) => " They were sent a DM about it.",
+		Some(false) => " They couldn't be sent a DM about it.",
+		None => "",
+	}
+}
+
+/// Acknowledges the interaction, giving the command time to take the action before responding
+pub async fn defer_moderation_response(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+) -> miette::Result<()> {
+	let response = InteractionResponseDataBuilder::new()
+		.flags(MessageFlags::EPHEMERAL)
+		.build();
+	let response = InteractionResponse {
+		kind: InteractionResponseType::DeferredChannelMessageWithSource,
+		data: Some(response),
+	};
+	http_client
+		.interaction(application_id)
+		.create_response(interaction.id, &interaction.token, &response)
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
+
+/// Sends the response to a moderation command after it was deferred
+pub async fn send_moderation_response(
+	interaction: &InteractionCreate,
+	http_client: &Client,
+	application_id: Id<ApplicationMarker>,
+	content: &str,
+) -> miette::Result<()> {
+	http_client
+		.interaction(application_id)
+		.update_response(&interaction.token)
+		.content(Some(content))
+		.allowed_mentions(Some(&AllowedMentions::default()))
+		.await
+		.into_diagnostic()?;
+	Ok(())
+}
+
+/// The record of a moderation action the bot is about to take itself
+pub enum NewActionRecord<'a> {
+	Ban(&'a BanAction),
+	Kick(&'a KickAction),
+	Timeout(&'a TimeoutAction),
+}
+
+impl NewActionRecord<'_> {
+	fn action_type(&self) -> ModerationActionType {
+		match self {
+			Self::Ban(_) => ModerationActionType::Ban,
+			Self::Kick(_) => ModerationActionType::Kick,
+			Self::Timeout(_) => ModerationActionType::Timeout,
+		}
+	}
+
+	fn id(&self) -> &str {
+		match self {
+			Self::Ban(action) => &action.id,
+			Self::Kick(action) => &action.id,
+			Self::Timeout(action) => &action.id,
+		}
+	}
+
+	fn guild(&self) -> i64 {
+		match self {
+			Self::Ban(action) => action.guild,
+			Self::Kick(action) => action.guild,
+			Self::Timeout(action) => action.guild,
+		}
+	}
+
+	fn insert(&self, db_connection: &mut PgConnection) -> QueryResult<usize> {
+		match self {
+			Self::Ban(action) => diesel::insert_into(ban_actions::table)
+				.values(*action)
+				.execute(db_connection),
+			Self::Kick(action) => diesel::insert_into(kick_actions::table)
+				.values(*action)
+				.execute(db_connection),
+			Self::Timeout(action) => diesel::insert_into(timeout_actions::table)
+				.values(*action)
+				.execute(db_connection),
+		}
+	}
+
+	fn delete(&self, db_connection: &mut PgConnection) -> QueryResult<usize> {
+		match self {
+			Self::Ban(action) => diesel::delete(ban_actions::table.find(&action.id)).execute(db_connection),
+			Self::Kick(action) => diesel::delete(kick_actions::table.find(&action.id)).execute(db_connection),
+			Self::Timeout(action) => diesel::delete(timeout_actions::table.find(&action.id)).execute(db_connection),
+		}
+	}
+}
+
+/// Records a moderation action the bot is taking itself, takes it, and gives it a case number.
+///
+/// The record is created before the action is taken so the audit log handler can find it when the action comes
+/// through. If Discord refuses the action, the record is removed again and Discord's error is returned inside the
+/// outer result; otherwise, the action's case number is returned.
+pub async fn perform_recorded_action<T>(
+	db_connection: &mut PgConnection,
+	record: NewActionRecord<'_>,
+	action: impl IntoFuture<Output = Result<T, twilight_http::Error>>,
+) -> miette::Result<Result<i32, twilight_http::Error>> {
+	record.insert(db_connection).into_diagnostic()?;
+
+	if let Err(error) = action.await {
+		record.delete(db_connection).into_diagnostic()?;
+		return Ok(Err(error));
+	}
+
+	let case_number =
+		assign_case_number(db_connection, record.guild(), record.action_type(), record.id()).into_diagnostic()?;
+	Ok(Ok(case_number))
+}
+
+/// Records where an action's mod log entry was posted, so the entry can be updated when the action changes
+pub fn record_mod_log_location(
+	db_connection: &mut PgConnection,
+	action_type: ModerationActionType,
+	action_id: &str,
+	(channel_id, message_id): (Id<ChannelMarker>, Id<MessageMarker>),
+) -> QueryResult<usize> {
+	let channel_id = database_id_from_discord_id(channel_id.get());
+	let message_id = database_id_from_discord_id(message_id.get());
+	match action_type {
+		ModerationActionType::Automod => diesel::update(automod_actions::table.find(action_id))
+			.set((
+				automod_actions::mod_log_channel.eq(channel_id),
+				automod_actions::mod_log_message.eq(message_id),
+			))
+			.execute(db_connection),
+		ModerationActionType::Ban => diesel::update(ban_actions::table.find(action_id))
+			.set((
+				ban_actions::mod_log_channel.eq(channel_id),
+				ban_actions::mod_log_message.eq(message_id),
+			))
+			.execute(db_connection),
+		ModerationActionType::Kick => diesel::update(kick_actions::table.find(action_id))
+			.set((
+				kick_actions::mod_log_channel.eq(channel_id),
+				kick_actions::mod_log_message.eq(message_id),
+			))
+			.execute(db_connection),
+		ModerationActionType::Timeout => diesel::update(timeout_actions::table.find(action_id))
+			.set((
+				timeout_actions::mod_log_channel.eq(channel_id),
+				timeout_actions::mod_log_message.eq(message_id),
+			))
+			.execute(db_connection),
+		ModerationActionType::Warn => diesel::update(warn_actions::table.find(action_id))
+			.set((
+				warn_actions::mod_log_channel.eq(channel_id),
+				warn_actions::mod_log_message.eq(message_id),
+			))
+			.execute(db_connection),
+	}
+}
+
+/// Posts a recorded moderation action to the guild's mod log, if it has one, and records where the entry was posted.
+/// Mod log problems are logged rather than returned so they don't stop the rest of the action from being handled.
+pub async fn post_action_to_mod_log(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+	guild: &Guild,
+	action_type: ModerationActionType,
+	action_id: &str,
+	entry: &ModLogEntry<'_>,
+) {
+	let Some(mod_log_location) = try_post_mod_log_entry(http_client, config, guild, entry).await else {
+		return;
+	};
+	if let Err(error) = record_mod_log_location(db_connection, action_type, action_id, mod_log_location) {
+		tracing::warn!(source = ?error, action = %action_id, "Failed to record an action's mod log entry");
+	}
+}
@@ -0,0 +1,210 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::timestamp::datetime_from_timestamp;
+use crate::model::{Guild, PartnerInviteCheck, PartnerInviteStatus, Partnership, database_id_from_discord_id};
+use crate::schema::{guilds, partner_invite_checks, partnerships};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_http::error::ErrorType;
+use twilight_http::response::StatusCode;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::id::Id;
+use twilight_model::id::marker::MessageMarker;
+use twilight_model::util::datetime::Timestamp;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+/// How long to wait before checking the same partner's invite again
+const INVITE_CHECK_INTERVAL: TimeDelta = TimeDelta::hours(24);
+
+/// The most invites checked each time the scheduler runs, so a large partner list is spread out over time instead of
+/// being fetched from Discord all at once
+const INVITE_CHECKS_PER_RUN: i64 = 10;
+
+const INVALID_INVITE_COLOR: u32 = 0xed4245;
+const EXPIRING_INVITE_COLOR: u32 = 0xfee75c;
+
+/// Checks the invites of partnerships that haven't been checked recently, alerting staff about invites that newly stop
+/// working or start expiring
+pub async fn check_partner_invites(
+	http_client: &Client,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let recently_checked_after = Utc::now() - INVITE_CHECK_INTERVAL;
+	let due_partnerships: Vec<Partnership> = partnerships::table
+		.left_join(
+			partner_invite_checks::table.on(partner_invite_checks::partnership
+				.eq(partnerships::id)
+				.and(partner_invite_checks::checked_at.gt(recently_checked_after))),
+		)
+		.filter(partnerships::active.eq(true).and(partner_invite_checks::id.is_null()))
+		.select(partnerships::all_columns)
+		.order(partnerships::approved_at.asc())
+		.limit(INVITE_CHECKS_PER_RUN)
+		.load(&mut db_connection)
+		.into_diagnostic()?;
+
+	for partnership in due_partnerships.iter() {
+		let check_result = check_partner_invite(partnership, http_client, &mut db_connection).await;
+		if let Err(error) = check_result {
+			tracing::warn!(source = ?error, partnership = %partnership.id, "Failed to check a partner invite");
+		}
+	}
+
+	Ok(())
+}
+
+async fn check_partner_invite(
+	partnership: &Partnership,
+	http_client: &Client,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let (status, expires_at) = match http_client.invite(&partnership.invite_code).with_expiration().await {
+		Ok(response) => {
+			let invite = response.model().await.into_diagnostic()?;
+			let leads_to_partner = invite
+				.guild
+				.as_ref()
+				.is_some_and(|guild| guild.id == partnership.get_partner_guild());
+			match (leads_to_partner, invite.expires_at) {
+				(false, _) => (PartnerInviteStatus::Invalid, None),
+				(true, Some(expires_at)) => (PartnerInviteStatus::Expiring, datetime_from_timestamp(&expires_at)),
+				(true, None) => (PartnerInviteStatus::Valid, None),
+			}
+		}
+		Err(error) => {
+			let invite_not_found = matches!(
+				error.kind(),
+				ErrorType::Response {
+					status: StatusCode::NOT_FOUND,
+					..
+				}
+			);
+			if !invite_not_found {
+				return Err(error).into_diagnostic();
+			}
+			(PartnerInviteStatus::Invalid, None)
+		}
+	};
+
+	// Staff only need to hear about a problem once; later checks that find the same problem are recorded quietly.
+	// A problem only counts as heard about once an alert is actually posted, so problems found while there's no alert
+	// channel are still alerted after one is set up.
+	let alert_message = if status != PartnerInviteStatus::Valid
+		&& !problem_already_alerted(partnership, status, db_connection).into_diagnostic()?
+	{
+		let guild: Guild = guilds::table
+			.find(partnership.guild)
+			.first(db_connection)
+			.into_diagnostic()?;
+		post_invite_alert(http_client, &guild, partnership, status, expires_at).await?
+	} else {
+		None
+	};
+
+	let check = PartnerInviteCheck {
+		id: cuid2::create_id(),
+		partnership: partnership.id.clone(),
+		checked_at: Utc::now(),
+		status,
+		expires_at,
+		alert_message: alert_message.map(|message_id| database_id_from_discord_id(message_id.get())),
+	};
+	diesel::insert_into(partner_invite_checks::table)
+		.values(check)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	Ok(())
+}
+
+/// Checks whether an alert was posted about the given problem since the partnership's invite last had another status
+fn problem_already_alerted(
+	partnership: &Partnership,
+	status: PartnerInviteStatus,
+	db_connection: &mut PgConnection,
+) -> QueryResult<bool> {
+	let last_other_status_check: Option<DateTime<Utc>> = partner_invite_checks::table
+		.filter(
+			partner_invite_checks::partnership
+				.eq(&partnership.id)
+				.and(partner_invite_checks::status.ne(status)),
+		)
+		.order(partner_invite_checks::checked_at.desc())
+		.select(partner_invite_checks::checked_at)
+		.first(db_connection)
+		.optional()?;
+
+	let mut alerts_query = partner_invite_checks::table
+		.filter(
+			partner_invite_checks::partnership
+				.eq(&partnership.id)
+				.and(partner_invite_checks::status.eq(status))
+				.and(partner_invite_checks::alert_message.is_not_null()),
+		)
+		.into_boxed();
+	if let Some(checked_at) = last_other_status_check {
+		alerts_query = alerts_query.filter(partner_invite_checks::checked_at.gt(checked_at));
+	}
+	let alert_count: i64 = alerts_query.count().get_result(db_connection)?;
+	Ok(alert_count > 0)
+}
+
+async fn post_invite_alert(
+	http_client: &Client,
+	guild: &Guild,
+	partnership: &Partnership,
+	status: PartnerInviteStatus,
+	expires_at: Option<DateTime<Utc>>,
+) -> miette::Result<Option<Id<MessageMarker>>> {
+	let Some(alert_channel) = guild.get_partner_invite_alert_channel() else {
+		return Ok(None);
+	};
+
+	let (title, color) = match status {
+		PartnerInviteStatus::Invalid => ("Partner invite no longer works", INVALID_INVITE_COLOR),
+		_ => ("Partner invite expires", EXPIRING_INVITE_COLOR),
+	};
+	let mut embed = EmbedBuilder::new()
+		.title(title)
+		.color(color)
+		.field(EmbedFieldBuilder::new(
+			"Partner",
+			format!("{} ({})", partnership.partner_name, partnership.get_partner_guild()),
+		))
+		.field(EmbedFieldBuilder::new(
+			"Invite",
+			format!("https://discord.gg/{}", partnership.invite_code),
+		))
+		.field(
+			EmbedFieldBuilder::new("Representative", partnership.get_representative().mention().to_string()).inline(),
+		);
+	if let Some(expires_at) = expires_at {
+		let expiry = MentionTimestamp::new(expires_at.timestamp() as u64, Some(TimestampStyle::RelativeTime));
+		embed = embed.field(EmbedFieldBuilder::new("Expires", expiry.mention().to_string()).inline());
+	}
+	if let Ok(timestamp) = Timestamp::from_micros(Utc::now().timestamp_micros()) {
+		embed = embed.timestamp(timestamp);
+	}
+	let embed = embed.build();
+
+	let message = http_client
+		.create_message(alert_channel)
+		.embeds(&[embed])
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	Ok(Some(message.id))
+}
@@ -0,0 +1,111 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{Guild, Partnership, database_id_from_discord_id};
+use crate::schema::{guilds, partnerships};
+use diesel::prelude::*;
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_http::error::ErrorType;
+use twilight_http::response::StatusCode;
+use twilight_model::channel::message::Embed;
+use twilight_util::builder::embed::EmbedBuilder;
+
+/// The longest an embed description may be
+const MAX_LIST_LENGTH: usize = 4096;
+
+const PARTNER_LIST_COLOR: u32 = 0x5865f2;
+
+/// Gets the guild's active partnerships, in the order they're listed
+pub fn active_partnerships(guild: &Guild, db_connection: &mut PgConnection) -> QueryResult<Vec<Partnership>> {
+	partnerships::table
+		.filter(
+			partnerships::guild
+				.eq(guild.guild_id)
+				.and(partnerships::active.eq(true)),
+		)
+		.order(partnerships::partner_name.asc())
+		.load(db_connection)
+}
+
+/// Makes the embed listing the guild's active partners
+pub fn partner_list_embed(partners: &[Partnership]) -> Embed {
+	let mut description = String::new();
+	for partnership in partners.iter() {
+		let line = format!(
+			"• [{}](https://discord.gg/{})\n",
+			partnership.partner_name, partnership.invite_code
+		);
+		if description.len() + line.len() > MAX_LIST_LENGTH {
+			break;
+		}
+		description.push_str(&line);
+	}
+	if description.is_empty() {
+		description = String::from("We don't have any partners right now.");
+	}
+
+	EmbedBuilder::new()
+		.title("Our Partners")
+		.color(PARTNER_LIST_COLOR)
+		.description(description)
+		.build()
+}
+
+/// Brings the guild's partner list message up to date with its active partnerships.
+///
+/// If the message was deleted, a new one is posted and recorded in its place. Guilds without a partner list channel
+/// are left alone.
+pub async fn update_partner_list_message(
+	http_client: &Client,
+	guild: &Guild,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let Some(list_channel) = guild.get_partner_list_channel() else {
+		return Ok(());
+	};
+
+	let partners = active_partnerships(guild, db_connection).into_diagnostic()?;
+	let embed = partner_list_embed(&partners);
+
+	if let Some(list_message) = guild.get_partner_list_message() {
+		let update_result = http_client
+			.update_message(list_channel, list_message)
+			.embeds(Some(&[embed.clone()]))
+			.await;
+		match update_result {
+			Ok(_) => return Ok(()),
+			Err(error) => {
+				let message_not_found = matches!(
+					error.kind(),
+					ErrorType::Response {
+						status: StatusCode::NOT_FOUND,
+						..
+					}
+				);
+				if !message_not_found {
+					return Err(error).into_diagnostic();
+				}
+			}
+		}
+	}
+
+	let new_message = http_client
+		.create_message(list_channel)
+		.embeds(&[embed])
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	diesel::update(guilds::table)
+		.filter(guilds::guild_id.eq(guild.guild_id))
+		.set(guilds::partner_list_message.eq(Some(database_id_from_discord_id(new_message.id.get()))))
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	Ok(())
+}
@@ -0,0 +1,32 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use twilight_model::channel::message::component::{ActionRow, Button, ButtonStyle, Component};
+
+/// Generates the buttons staff use to approve or decline a pending partnership
+pub fn partnership_decision_components(pending_partnership_id: &str) -> Vec<Component> {
+	let approve_button = Component::Button(Button {
+		custom_id: Some(format!("partnership/{}/approve", pending_partnership_id)),
+		disabled: false,
+		emoji: None,
+		label: Some(String::from("Approve partnership")),
+		style: ButtonStyle::Success,
+		url: None,
+		sku_id: None,
+	});
+	let decline_button = Component::Button(Button {
+		custom_id: Some(format!("partnership/{}/decline", pending_partnership_id)),
+		disabled: false,
+		emoji: None,
+		label: Some(String::from("Decline")),
+		style: ButtonStyle::Danger,
+		url: None,
+		sku_id: None,
+	});
+	vec![Component::ActionRow(ActionRow {
+		components: vec![approve_button, decline_button],
+	})]
+}
@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::model::Guild;
 use miette::IntoDiagnostic;
 use std::collections::HashMap;
 use twilight_http::client::Client;
@@ -11,7 +12,7 @@ use twilight_http::error::ErrorType;
 use twilight_http::response::StatusCode;
 use twilight_model::guild::Permissions;
 use twilight_model::id::Id;
-use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker};
+use twilight_model::id::marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker};
 use twilight_util::permission_calculator::PermissionCalculator;
 
 /// The list of permissions that the bot must have in the ticket channel in order to function.
@@ -32,6 +33,59 @@ pub fn ticket_channel_missing_permissions_message(channel_mention: impl std::fmt
 	)
 }
 
+/// Checks whether a member with the given roles is staff (or an admin) for the guild.
+pub fn member_is_staff(guild: &Guild, member_roles: &[Id<RoleMarker>]) -> bool {
+	member_roles.contains(&guild.get_staff_role()) || member_roles.contains(&guild.get_admin_role())
+}
+
+/// Checks whether a moderator ranks above the target in the guild's role hierarchy, as Discord requires when moderators
+/// act on members themselves. Users who aren't members of the guild can always be acted on.
+pub async fn moderator_outranks_target(
+	guild_id: Id<GuildMarker>,
+	moderator_id: Id<UserMarker>,
+	moderator_roles: &[Id<RoleMarker>],
+	target_id: Id<UserMarker>,
+	http_client: &Client,
+) -> miette::Result<bool> {
+	let guild_future = http_client.guild(guild_id).into_future();
+	let guild_roles_future = http_client.roles(guild_id).into_future();
+	let target_member_future = http_client.guild_member(guild_id, target_id).into_future();
+	let (guild, guild_roles, target_member) = tokio::join!(guild_future, guild_roles_future, target_member_future);
+
+	let guild = guild.into_diagnostic()?.model().await.into_diagnostic()?;
+	if target_id == guild.owner_id {
+		return Ok(false);
+	}
+	if moderator_id == guild.owner_id {
+		return Ok(true);
+	}
+
+	let target_member = match target_member {
+		Ok(response) => response.model().await.into_diagnostic()?,
+		Err(error) => {
+			if let ErrorType::Response { status, .. } = error.kind() {
+				if *status == StatusCode::NOT_FOUND {
+					return Ok(true);
+				}
+			}
+			return Err(error).into_diagnostic();
+		}
+	};
+	let guild_roles = guild_roles.into_diagnostic()?.models().await.into_diagnostic()?;
+
+	let role_positions: HashMap<Id<RoleMarker>, i64> =
+		guild_roles.iter().map(|role| (role.id, role.position)).collect();
+	let highest_position = |roles: &[Id<RoleMarker>]| {
+		roles
+			.iter()
+			.filter_map(|role_id| role_positions.get(role_id))
+			.copied()
+			.max()
+			.unwrap_or(0)
+	};
+	Ok(highest_position(moderator_roles) > highest_position(&target_member.roles))
+}
+
 /// Gets the list of permissions the bot has in the passed-in channel. The channel ID must reference a channel on the passed-in guild.
 pub async fn channel_permissions(
 	guild_id: Id<GuildMarker>,
@@ -0,0 +1,231 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{ReasonPolicy, ReasonPolicyExemptRole, database_id_from_discord_id};
+use crate::schema::{reason_policies, reason_policy_exempt_roles};
+use diesel::prelude::*;
+use miette::IntoDiagnostic;
+use regex::Regex;
+use std::fmt;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{GuildMarker, RoleMarker, UserMarker};
+
+/// The check of the reason policy that a reason failed
+#[derive(Debug)]
+pub enum ReasonPolicyFailure {
+	/// No reason was provided at all
+	Missing,
+	/// The reason was shorter than the minimum length
+	TooShort { minimum_length: i32 },
+	/// The reason didn't match the required pattern
+	PatternMismatch { pattern: String },
+}
+
+impl fmt::Display for ReasonPolicyFailure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Missing => write!(f, "no reason was provided"),
+			Self::TooShort { minimum_length } => write!(
+				f,
+				"the reason is shorter than the minimum length of {} characters",
+				minimum_length
+			),
+			Self::PatternMismatch { pattern } => {
+				write!(f, "the reason doesn't match the required pattern `{}`", pattern)
+			}
+		}
+	}
+}
+
+/// Checks an action reason against the guild's reason policy.
+///
+/// If the guild has no policy set up, reasons are only required to be present.
+pub async fn check_reason_policy(
+	db_connection: &mut PgConnection,
+	http_client: &Client,
+	guild_id: Id<GuildMarker>,
+	moderator_id: Id<UserMarker>,
+	reason: &str,
+) -> miette::Result<Option<ReasonPolicyFailure>> {
+	let db_guild_id = database_id_from_discord_id(guild_id.get());
+	let policy: Option<ReasonPolicy> = reason_policies::table
+		.find(db_guild_id)
+		.first(db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let exempt_roles: Vec<ReasonPolicyExemptRole> = reason_policy_exempt_roles::table
+		.filter(reason_policy_exempt_roles::guild.eq(db_guild_id))
+		.load(db_connection)
+		.into_diagnostic()?;
+
+	let exempt_bots = policy.as_ref().map(|policy| policy.exempt_bots).unwrap_or(false);
+	let exempt_role_ids: Vec<Id<RoleMarker>> = exempt_roles.iter().map(|role| role.get_role()).collect();
+	if exempt_bots || !exempt_role_ids.is_empty() {
+		match http_client.guild_member(guild_id, moderator_id).await {
+			Ok(response) => {
+				let member = response.model().await.into_diagnostic()?;
+				if moderator_is_exempt(exempt_bots, &exempt_role_ids, member.user.bot, &member.roles) {
+					return Ok(None);
+				}
+			}
+			Err(error) => {
+				tracing::warn!(source = ?error, "Couldn't get moderator member data to check reason policy exemptions")
+			}
+		}
+	}
+
+	Ok(evaluate_reason(policy.as_ref(), reason))
+}
+
+/// Checks whether a moderator is exempt from the reason policy, either by being a bot when bots are exempt or by having
+/// an exempt role
+fn moderator_is_exempt(
+	exempt_bots: bool,
+	exempt_roles: &[Id<RoleMarker>],
+	moderator_is_bot: bool,
+	moderator_roles: &[Id<RoleMarker>],
+) -> bool {
+	(exempt_bots && moderator_is_bot) || exempt_roles.iter().any(|role| moderator_roles.contains(role))
+}
+
+/// Checks a reason against a reason policy, or only for presence if there's no policy
+fn evaluate_reason(policy: Option<&ReasonPolicy>, reason: &str) -> Option<ReasonPolicyFailure> {
+	let reason = reason.trim();
+	if reason.is_empty() {
+		return Some(ReasonPolicyFailure::Missing);
+	}
+
+	let policy = policy?;
+
+	if (reason.chars().count() as i64) < i64::from(policy.minimum_length) {
+		return Some(ReasonPolicyFailure::TooShort {
+			minimum_length: policy.minimum_length,
+		});
+	}
+
+	if let Some(pattern) = policy.required_pattern.as_ref() {
+		match Regex::new(pattern) {
+			Ok(regex) => {
+				if !regex.is_match(reason) {
+					return Some(ReasonPolicyFailure::PatternMismatch {
+						pattern: pattern.clone(),
+					});
+				}
+			}
+			Err(error) => tracing::warn!(source = ?error, "Guild's reason policy has an invalid required pattern"),
+		}
+	}
+
+	None
+}
+
+/// Generates the message to post to the complaint channel when an action's reason fails the reason policy.
+///
+/// The action description should describe what happened to the target (e.g. "has been banned").
+pub fn reason_complaint_message(
+	moderator_id: Id<UserMarker>,
+	target_user_id: Id<UserMarker>,
+	action_description: &str,
+	failure: &ReasonPolicyFailure,
+) -> String {
+	match failure {
+		ReasonPolicyFailure::Missing => format!(
+			"{}, {} {}, but you didn't provide a reason. Please provide a reason as soon as you can.",
+			moderator_id.mention(),
+			target_user_id.mention(),
+			action_description
+		),
+		_ => format!(
+			"{}, {} {}, but the reason you provided doesn't meet this server's reason policy: {}. Please provide a new reason as soon as you can.",
+			moderator_id.mention(),
+			target_user_id.mention(),
+			action_description,
+			failure
+		),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn policy(minimum_length: i32, required_pattern: Option<&str>) -> ReasonPolicy {
+		ReasonPolicy {
+			guild: 1,
+			minimum_length,
+			required_pattern: required_pattern.map(String::from),
+			exempt_bots: false,
+		}
+	}
+
+	#[test]
+	fn blank_reasons_are_missing() {
+		assert!(matches!(evaluate_reason(None, ""), Some(ReasonPolicyFailure::Missing)));
+		assert!(matches!(
+			evaluate_reason(Some(&policy(0, None)), "  \n\t"),
+			Some(ReasonPolicyFailure::Missing)
+		));
+	}
+
+	#[test]
+	fn any_reason_passes_without_a_policy() {
+		assert!(evaluate_reason(None, "x").is_none());
+	}
+
+	#[test]
+	fn minimum_length_counts_characters() {
+		let policy = policy(5, None);
+		assert!(matches!(
+			evaluate_reason(Some(&policy), "spam"),
+			Some(ReasonPolicyFailure::TooShort { minimum_length: 5 })
+		));
+		// Five characters, but more than five bytes
+		assert!(evaluate_reason(Some(&policy), "ßpäm!").is_none());
+		assert!(matches!(
+			evaluate_reason(Some(&policy), "  ßpä  "),
+			Some(ReasonPolicyFailure::TooShort { .. })
+		));
+	}
+
+	#[test]
+	fn required_pattern_must_match() {
+		let policy = policy(0, Some(r"^R\d+"));
+		assert!(evaluate_reason(Some(&policy), "R4: spam").is_none());
+		assert!(matches!(
+			evaluate_reason(Some(&policy), "spam"),
+			Some(ReasonPolicyFailure::PatternMismatch { pattern }) if pattern == r"^R\d+"
+		));
+	}
+
+	#[test]
+	fn invalid_pattern_is_ignored() {
+		let policy = policy(0, Some("(unclosed"));
+		assert!(evaluate_reason(Some(&policy), "spam").is_none());
+	}
+
+	#[test]
+	fn bots_are_exempt_only_when_configured() {
+		assert!(moderator_is_exempt(true, &[], true, &[]));
+		assert!(!moderator_is_exempt(false, &[], true, &[]));
+		assert!(!moderator_is_exempt(true, &[], false, &[]));
+	}
+
+	#[test]
+	fn exempt_roles_exempt_their_members() {
+		let exempt_role = Id::new(10);
+		let other_role = Id::new(11);
+		assert!(moderator_is_exempt(
+			false,
+			&[exempt_role],
+			false,
+			&[other_role, exempt_role]
+		));
+		assert!(!moderator_is_exempt(false, &[exempt_role], false, &[other_role]));
+		assert!(!moderator_is_exempt(false, &[], false, &[other_role]));
+	}
+}
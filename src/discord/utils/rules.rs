@@ -0,0 +1,186 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{ActionRule, ModerationActionType, ServerRule};
+use crate::schema::{action_rules, server_rules};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+/// The longest title a server rule can have
+pub const MAX_RULE_TITLE_LENGTH: u16 = 100;
+
+/// The longest description a server rule can have
+pub const MAX_RULE_DESCRIPTION_LENGTH: u16 = 1000;
+
+/// Gets the rule numbers referenced at the start of a reason.
+///
+/// References come before a colon and can list several rules, so "R4: spam", "Rule 4: spam", and "R4, R7: spam" are
+/// all recognized. If the text before the colon isn't entirely rule references, the reason doesn't reference any
+/// rules.
+pub fn parse_rule_references(reason: &str) -> Vec<i32> {
+	let Some((prefix, _)) = reason.split_once(':') else {
+		return Vec::new();
+	};
+
+	let mut rule_numbers: Vec<i32> = Vec::new();
+	for reference in prefix.split([',', '/', '+', '&']) {
+		let reference = reference.trim().to_lowercase();
+		let number = reference
+			.strip_prefix("rule")
+			.or_else(|| reference.strip_prefix('r'))
+			.and_then(|number| number.trim().parse::<i32>().ok())
+			.filter(|number| *number > 0);
+		let Some(number) = number else {
+			return Vec::new();
+		};
+		if !rule_numbers.contains(&number) {
+			rule_numbers.push(number);
+		}
+	}
+	rule_numbers
+}
+
+/// Records that an action was taken for breaking the guild's rules with the given numbers.
+///
+/// Numbers that don't match a rule in use are ignored. Returns the rules that were linked to the action.
+pub fn link_action_rules(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	action_type: ModerationActionType,
+	action_id: &str,
+	action_time: DateTime<Utc>,
+	rule_numbers: &[i32],
+) -> QueryResult<Vec<ServerRule>> {
+	if rule_numbers.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let rules: Vec<ServerRule> = server_rules::table
+		.filter(
+			server_rules::guild
+				.eq(db_guild_id)
+				.and(server_rules::active.eq(true))
+				.and(server_rules::rule_number.eq_any(rule_numbers)),
+		)
+		.order(server_rules::rule_number.asc())
+		.load(db_connection)?;
+	let new_action_rules: Vec<ActionRule> = rules
+		.iter()
+		.map(|rule| ActionRule {
+			action_type,
+			action_id: action_id.to_string(),
+			rule: rule.id.clone(),
+			action_time,
+		})
+		.collect();
+	diesel::insert_into(action_rules::table)
+		.values(&new_action_rules)
+		.on_conflict_do_nothing()
+		.execute(db_connection)?;
+
+	Ok(rules)
+}
+
+/// Links an action to the rules referenced in its reason, along with a rule the moderator chose explicitly, if any
+pub fn link_rules_from_reason(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	action_type: ModerationActionType,
+	action_id: &str,
+	action_time: DateTime<Utc>,
+	reason: &str,
+	chosen_rule: Option<i32>,
+) -> QueryResult<Vec<ServerRule>> {
+	let mut rule_numbers = parse_rule_references(reason);
+	if let Some(chosen_rule) = chosen_rule.filter(|rule| !rule_numbers.contains(rule)) {
+		rule_numbers.push(chosen_rule);
+	}
+	link_action_rules(
+		db_connection,
+		db_guild_id,
+		action_type,
+		action_id,
+		action_time,
+		&rule_numbers,
+	)
+}
+
+/// Gets the rules an action was taken for breaking
+pub fn rules_for_action(
+	db_connection: &mut PgConnection,
+	action_type: ModerationActionType,
+	action_id: &str,
+) -> QueryResult<Vec<ServerRule>> {
+	action_rules::table
+		.inner_join(server_rules::table)
+		.filter(
+			action_rules::action_type
+				.eq(action_type)
+				.and(action_rules::action_id.eq(action_id)),
+		)
+		.order(server_rules::rule_number.asc())
+		.select(server_rules::all_columns)
+		.load(db_connection)
+}
+
+/// Describes a list of rules, one per line, for showing in Discord
+pub fn describe_rules(rules: &[ServerRule]) -> String {
+	let lines: Vec<String> = rules
+		.iter()
+		.map(|rule| format!("Rule {}: {}", rule.rule_number, rule.title))
+		.collect();
+	lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn single_references() {
+		assert_eq!(parse_rule_references("R4: spam"), vec![4]);
+		assert_eq!(parse_rule_references("r4: spam"), vec![4]);
+		assert_eq!(parse_rule_references("Rule 4: spam"), vec![4]);
+		assert_eq!(parse_rule_references("rule4: spam"), vec![4]);
+	}
+
+	#[test]
+	fn multiple_references() {
+		assert_eq!(parse_rule_references("R4, R7: spam"), vec![4, 7]);
+		assert_eq!(parse_rule_references("R4/Rule 7 + r2 & R9: spam"), vec![4, 7, 2, 9]);
+	}
+
+	#[test]
+	fn repeated_references_are_listed_once() {
+		assert_eq!(parse_rule_references("R4, r4: spam"), vec![4]);
+	}
+
+	#[test]
+	fn reference_without_text_after_it() {
+		assert_eq!(parse_rule_references("Rule 4:"), vec![4]);
+	}
+
+	#[test]
+	fn references_need_a_colon() {
+		assert!(parse_rule_references("R4 spam").is_empty());
+		assert!(parse_rule_references("R4").is_empty());
+	}
+
+	#[test]
+	fn anything_else_before_the_colon_means_no_references() {
+		assert!(parse_rule_references("Spam: R4").is_empty());
+		assert!(parse_rule_references("R4 spam: again").is_empty());
+		assert!(parse_rule_references("R4, spam: again").is_empty());
+		assert!(parse_rule_references(": spam").is_empty());
+		assert!(parse_rule_references("See https://example.com").is_empty());
+	}
+
+	#[test]
+	fn rule_numbers_must_be_positive() {
+		assert!(parse_rule_references("R0: spam").is_empty());
+		assert!(parse_rule_references("R-1: spam").is_empty());
+	}
+}
@@ -0,0 +1,257 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::{ConfigData, TcnArgs};
+use crate::model::{Partnership, TcnBan, TcnPartner, database_id_from_discord_id};
+use crate::schema::{guilds, partnerships, tcn_bans, tcn_partners};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::IntoDiagnostic;
+use reqwest::{Client as ApiClient, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use twilight_model::id::Id;
+use twilight_model::id::marker::{GuildMarker, UserMarker};
+
+/// How many rows are inserted per statement when storing pulled network data, keeping each statement well under
+/// Postgres's limit on bind parameters
+const INSERT_CHUNK_SIZE: usize = 1000;
+
+/// How long a request to the network can take before it's abandoned, so an unresponsive network can't hold up the
+/// scheduled tasks that talk to it
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The data sent to the network when a partnership is approved or reactivated
+#[derive(Debug, Serialize)]
+pub struct NetworkPartnerUpdate<'a> {
+	pub name: &'a str,
+	pub invite_code: &'a str,
+}
+
+/// A guild in the partner network
+#[derive(Debug, Deserialize)]
+pub struct NetworkPartner {
+	pub guild_id: Id<GuildMarker>,
+	pub name: String,
+	pub invite_code: String,
+}
+
+/// A ban shared through the partner network
+#[derive(Debug, Deserialize)]
+pub struct NetworkBan {
+	pub id: String,
+	pub user_id: Id<UserMarker>,
+	pub guild_id: Id<GuildMarker>,
+	pub reason: String,
+	pub created_at: DateTime<Utc>,
+}
+
+/// A client for the partner network (TCN) API.
+///
+/// The API is expected to provide these endpoints, relative to the configured base URL and authenticated with the
+/// configured token as a bearer token:
+/// - `PUT /guilds/{guild_id}/partners/{partner_guild_id}` with a [NetworkPartnerUpdate] body records a partnership
+/// - `DELETE /guilds/{guild_id}/partners/{partner_guild_id}` removes a partnership
+/// - `GET /partners` lists the network's guilds as [NetworkPartner]s
+/// - `GET /bans` lists the bans shared through the network as [NetworkBan]s
+struct TcnClient<'a> {
+	api_client: ApiClient,
+	args: &'a TcnArgs,
+}
+
+impl<'a> TcnClient<'a> {
+	fn new(args: &'a TcnArgs) -> miette::Result<Self> {
+		let api_client = ApiClient::builder()
+			.timeout(REQUEST_TIMEOUT)
+			.build()
+			.into_diagnostic()?;
+		Ok(Self { api_client, args })
+	}
+
+	fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+		request.bearer_auth(&self.args.api_token)
+	}
+
+	fn partner_url(&self, partnership: &Partnership) -> String {
+		format!(
+			"{}/guilds/{}/partners/{}",
+			self.args.api_base_url,
+			partnership.get_guild(),
+			partnership.get_partner_guild()
+		)
+	}
+
+	async fn put_partner(&self, partnership: &Partnership) -> miette::Result<()> {
+		let update = NetworkPartnerUpdate {
+			name: &partnership.partner_name,
+			invite_code: &partnership.invite_code,
+		};
+		self.authorize(self.api_client.put(self.partner_url(partnership)))
+			.json(&update)
+			.send()
+			.await
+			.into_diagnostic()?
+			.error_for_status()
+			.into_diagnostic()?;
+		Ok(())
+	}
+
+	async fn remove_partner(&self, partnership: &Partnership) -> miette::Result<()> {
+		self.authorize(self.api_client.delete(self.partner_url(partnership)))
+			.send()
+			.await
+			.into_diagnostic()?
+			.error_for_status()
+			.into_diagnostic()?;
+		Ok(())
+	}
+
+	async fn partners(&self) -> miette::Result<Vec<NetworkPartner>> {
+		let url = format!("{}/partners", self.args.api_base_url);
+		self.authorize(self.api_client.get(url))
+			.send()
+			.await
+			.into_diagnostic()?
+			.error_for_status()
+			.into_diagnostic()?
+			.json()
+			.await
+			.into_diagnostic()
+	}
+
+	async fn bans(&self) -> miette::Result<Vec<NetworkBan>> {
+		let url = format!("{}/bans", self.args.api_base_url);
+		self.authorize(self.api_client.get(url))
+			.send()
+			.await
+			.into_diagnostic()?
+			.error_for_status()
+			.into_diagnostic()?
+			.json()
+			.await
+			.into_diagnostic()
+	}
+}
+
+/// Pushes partnership changes that haven't reached the partner network yet for guilds with TCN partner integration
+/// enabled. Partnerships that fail to push stay pending and are tried again the next time this runs.
+pub async fn push_pending_partnerships(
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(tcn_args) = config.tcn.as_ref() else {
+		return Ok(());
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let pending_partnerships: Vec<Partnership> = partnerships::table
+		.inner_join(guilds::table)
+		.filter(
+			partnerships::tcn_push_pending
+				.eq(true)
+				.and(guilds::tcn_partner_integration.eq(true)),
+		)
+		.select(partnerships::all_columns)
+		.load(&mut db_connection)
+		.into_diagnostic()?;
+	if pending_partnerships.is_empty() {
+		return Ok(());
+	}
+
+	let client = TcnClient::new(tcn_args)?;
+	for partnership in pending_partnerships.iter() {
+		let push_result = if partnership.active {
+			client.put_partner(partnership).await
+		} else {
+			client.remove_partner(partnership).await
+		};
+		if let Err(error) = push_result {
+			tracing::warn!(source = ?error, partnership = %partnership.id, "Failed to push a partnership to the partner network");
+			continue;
+		}
+
+		// Only clear the flag if the partnership wasn't changed again while the push was happening
+		diesel::update(partnerships::table)
+			.filter(
+				partnerships::id
+					.eq(&partnership.id)
+					.and(partnerships::active.eq(partnership.active)),
+			)
+			.set(partnerships::tcn_push_pending.eq(false))
+			.execute(&mut db_connection)
+			.into_diagnostic()?;
+	}
+
+	Ok(())
+}
+
+/// Replaces the stored copy of the partner network's guilds and shared bans with the network's current data. Nothing
+/// is pulled unless at least one guild has TCN partner integration enabled.
+pub async fn pull_network_data(
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let Some(tcn_args) = config.tcn.as_ref() else {
+		return Ok(());
+	};
+
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+	let integrated_guild_count: i64 = guilds::table
+		.filter(guilds::tcn_partner_integration.eq(true))
+		.count()
+		.get_result(&mut db_connection)
+		.into_diagnostic()?;
+	if integrated_guild_count == 0 {
+		return Ok(());
+	}
+
+	let client = TcnClient::new(tcn_args)?;
+	let network_partners = client.partners().await?;
+	let network_bans = client.bans().await?;
+
+	let synced_at = Utc::now();
+	let network_partners: Vec<TcnPartner> = network_partners
+		.into_iter()
+		.map(|partner| TcnPartner {
+			guild_id: database_id_from_discord_id(partner.guild_id.get()),
+			name: partner.name,
+			invite_code: partner.invite_code,
+			synced_at,
+		})
+		.collect();
+	let network_bans: Vec<TcnBan> = network_bans
+		.into_iter()
+		.map(|ban| TcnBan {
+			id: ban.id,
+			user_id: database_id_from_discord_id(ban.user_id.get()),
+			origin_guild: database_id_from_discord_id(ban.guild_id.get()),
+			reason: ban.reason,
+			banned_at: ban.created_at,
+			synced_at,
+		})
+		.collect();
+
+	db_connection
+		.transaction(|db_connection| {
+			diesel::delete(tcn_partners::table).execute(db_connection)?;
+			for partners_chunk in network_partners.chunks(INSERT_CHUNK_SIZE) {
+				diesel::insert_into(tcn_partners::table)
+					.values(partners_chunk)
+					.execute(db_connection)?;
+			}
+			diesel::delete(tcn_bans::table).execute(db_connection)?;
+			for bans_chunk in network_bans.chunks(INSERT_CHUNK_SIZE) {
+				diesel::insert_into(tcn_bans::table)
+					.values(bans_chunk)
+					.execute(db_connection)?;
+			}
+			Ok::<(), diesel::result::Error>(())
+		})
+		.into_diagnostic()?;
+
+	Ok(())
+}
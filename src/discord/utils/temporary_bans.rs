@@ -0,0 +1,221 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_UNBAN, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{NewActionRecord, perform_recorded_action, post_action_to_mod_log};
+use crate::model::{BanAction, Guild, ModerationActionType, database_id_from_discord_id};
+use crate::schema::{ban_actions, guilds};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_http::error::ErrorType;
+use twilight_http::request::AuditLogReason;
+use twilight_http::response::StatusCode;
+use twilight_mention::fmt::Mention;
+use twilight_mention::timestamp::{Timestamp as MentionTimestamp, TimestampStyle};
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+/// The longest a temporary ban can last, in days
+pub const MAX_BAN_DURATION_DAYS: i64 = 3650;
+
+/// The reason given for unbans that happen because a temporary ban expired
+const EXPIRED_BAN_REASON: &str = "Temporary ban expired";
+
+/// Gets the most recent ban action recorded for a user, which tells whether the user is currently banned
+pub fn latest_ban_action(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	db_user_id: i64,
+) -> QueryResult<Option<BanAction>> {
+	ban_actions::table
+		.filter(
+			ban_actions::guild
+				.eq(db_guild_id)
+				.and(ban_actions::banned_user.eq(db_user_id)),
+		)
+		.order(ban_actions::action_time.desc())
+		.first(db_connection)
+		.optional()
+}
+
+/// Gets the temporary bans that are still in place, soonest expiry first. The results can be limited to a single guild
+/// and to bans that expire by a given time.
+///
+/// A ban only counts as in place while it's the user's latest ban action, so bans that were already lifted by hand or
+/// replaced by a newer ban are left out.
+pub fn active_temporary_bans(
+	db_connection: &mut PgConnection,
+	db_guild_id: Option<i64>,
+	expiring_by: Option<DateTime<Utc>>,
+) -> QueryResult<Vec<BanAction>> {
+	let later_actions = diesel::alias!(ban_actions as later_actions);
+	let later_action_matches = later_actions
+		.field(ban_actions::guild)
+		.eq(ban_actions::guild)
+		.and(
+			later_actions
+				.field(ban_actions::banned_user)
+				.eq(ban_actions::banned_user),
+		)
+		.and(
+			later_actions
+				.field(ban_actions::action_time)
+				.gt(ban_actions::action_time),
+		);
+	let mut query = ban_actions::table
+		.left_join(later_actions.on(later_action_matches))
+		.filter(
+			ban_actions::added
+				.eq(true)
+				.and(ban_actions::expires_at.is_not_null())
+				.and(later_actions.field(ban_actions::id).is_null()),
+		)
+		.select(ban_actions::all_columns)
+		.into_boxed();
+	if let Some(db_guild_id) = db_guild_id {
+		query = query.filter(ban_actions::guild.eq(db_guild_id));
+	}
+	if let Some(expiring_by) = expiring_by {
+		query = query.filter(ban_actions::expires_at.le(expiring_by));
+	}
+	query.order(ban_actions::expires_at.asc()).load(db_connection)
+}
+
+/// Lifts every temporary ban that has expired
+pub async fn lift_expired_bans(
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
+) -> miette::Result<()> {
+	let mut db_connection = db_connection_pool.get().into_diagnostic()?;
+
+	let expired_bans = active_temporary_bans(&mut db_connection, None, Some(Utc::now())).into_diagnostic()?;
+	if expired_bans.is_empty() {
+		return Ok(());
+	}
+
+	let bot_user = http_client
+		.current_user()
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	for expired_ban in expired_bans.iter() {
+		let lift_result = lift_ban(expired_ban, bot_user.id, http_client, config, &mut db_connection).await;
+		if let Err(error) = lift_result {
+			tracing::warn!(source = ?error, ban = %expired_ban.id, "Failed to lift an expired temporary ban");
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks whether an error from a ban request was because Discord has no ban for the user
+pub fn ban_missing(error: &twilight_http::Error) -> bool {
+	matches!(
+		error.kind(),
+		ErrorType::Response {
+			status: StatusCode::NOT_FOUND,
+			..
+		}
+	)
+}
+
+async fn lift_ban(
+	expired_ban: &BanAction,
+	bot_user_id: Id<UserMarker>,
+	http_client: &Client,
+	config: &ConfigData,
+	db_connection: &mut PgConnection,
+) -> miette::Result<()> {
+	let guild: Option<Guild> = guilds::table
+		.find(expired_ban.guild)
+		.first(db_connection)
+		.optional()
+		.into_diagnostic()?;
+	let Some(guild) = guild else {
+		return Ok(());
+	};
+	let guild_id = expired_ban.get_guild();
+	let banned_user_id = expired_ban.get_banned_user();
+
+	let action_time = Utc::now();
+	let new_unban_action = BanAction {
+		id: cuid2::create_id(),
+		guild: expired_ban.guild,
+		banning_user: database_id_from_discord_id(bot_user_id.get()),
+		banned_user: expired_ban.banned_user,
+		added: false,
+		action_time,
+		reason: String::from(EXPIRED_BAN_REASON),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+		audit_log_entry: None,
+		evidence: None,
+		via_bot_command: false,
+		escalation_rule: None,
+		expires_at: None,
+		original_ban: Some(expired_ban.id.clone()),
+		shared_ban: None,
+	};
+
+	// If Discord no longer has the ban, the user was unbanned without us seeing it; the unban record stays so the ban
+	// isn't retried forever.
+	let unban = async {
+		let unban_result = http_client
+			.delete_ban(guild_id, banned_user_id)
+			.reason(EXPIRED_BAN_REASON)
+			.await;
+		match unban_result {
+			Err(error) if !ban_missing(&error) => Err(error),
+			_ => Ok(()),
+		}
+	};
+	let case_number = perform_recorded_action(db_connection, NewActionRecord::Ban(&new_unban_action), unban)
+		.await?
+		.into_diagnostic()?;
+	let original_ban_text = format!(
+		"Banned by {} on {}",
+		expired_ban.get_banning_user().mention(),
+		MentionTimestamp::new(
+			expired_ban.action_time.timestamp() as u64,
+			Some(TimestampStyle::LongDateTime)
+		)
+		.mention()
+	);
+	let mod_log_entry = ModLogEntry {
+		title: "Unban",
+		color: MOD_LOG_COLOR_UNBAN,
+		case_number: Some(case_number),
+		target_user: Some(banned_user_id),
+		actor: ModLogActor::User(bot_user_id),
+		reason: Some(&new_unban_action.reason),
+		expires_at: None,
+		action_time,
+		details: vec![("Temporary Ban", original_ban_text)],
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		db_connection,
+		&guild,
+		ModerationActionType::Ban,
+		&new_unban_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	Ok(())
+}
@@ -0,0 +1,196 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::discord::utils::temporary_bans::ban_missing;
+use crate::discord::utils::tickets::staff_message;
+use crate::model::{
+	BuiltInTicketCategory, DepartedTicketUserPolicy, Guild, Ticket, TicketMessage, database_id_from_discord_id,
+};
+use crate::schema::{ticket_messages, tickets};
+use chrono::Utc;
+use diesel::prelude::*;
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_http::request::AuditLogReason;
+use twilight_mention::fmt::Mention;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::user::CurrentUser;
+use twilight_model::util::datetime::Timestamp;
+
+/// How a ticket's user stopped being able to see their ticket thread
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TicketUserDeparture {
+	/// The user left the guild or was kicked
+	Removed,
+	/// The user was banned
+	Banned,
+}
+
+/// What is done with a ticket whose user departed
+enum DepartedTicketAction {
+	KeepOpen,
+	Close,
+	ConvertToBanAppeal,
+}
+
+/// Handles the open tickets of a user who can no longer see their ticket threads, noting the change for staff in each
+/// ticket and applying the guild's policy for departed ticket users
+pub async fn handle_ticket_user_departure(
+	http_client: &Client,
+	db_connection: &mut PgConnection,
+	guild: &Guild,
+	user_id: Id<UserMarker>,
+	departure: TicketUserDeparture,
+) -> miette::Result<()> {
+	let open_tickets: Vec<Ticket> = tickets::table
+		.filter(
+			tickets::guild
+				.eq(guild.guild_id)
+				.and(tickets::with_user.eq(database_id_from_discord_id(user_id.get())))
+				.and(tickets::closed_at.is_null()),
+		)
+		.load(db_connection)
+		.into_diagnostic()?;
+	if open_tickets.is_empty() {
+		return Ok(());
+	}
+
+	// Bans also remove the user from the guild. Those departures are handled when the ban comes through, so that each
+	// departure gets only one notice and one policy decision.
+	if departure == TicketUserDeparture::Removed && user_is_banned(http_client, guild, user_id).await {
+		return Ok(());
+	}
+
+	let bot_user = http_client
+		.current_user()
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+
+	for ticket in open_tickets.iter() {
+		let ticket_result =
+			handle_departed_ticket(http_client, db_connection, guild, ticket, &bot_user, departure).await;
+		if let Err(error) = ticket_result {
+			tracing::warn!(source = ?error, ticket = %ticket.id, "Failed to handle a ticket whose user departed");
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks whether a user is banned from the guild. If the ban can't be checked, the user is treated as not banned so
+/// that the departure is still noted.
+async fn user_is_banned(http_client: &Client, guild: &Guild, user_id: Id<UserMarker>) -> bool {
+	match http_client.ban(guild.get_guild_id(), user_id).await {
+		Ok(_) => true,
+		Err(error) if ban_missing(&error) => false,
+		Err(error) => {
+			tracing::warn!(source = ?error, "Couldn't check whether a departed ticket user was banned");
+			false
+		}
+	}
+}
+
+async fn handle_departed_ticket(
+	http_client: &Client,
+	db_connection: &mut PgConnection,
+	guild: &Guild,
+	ticket: &Ticket,
+	bot_user: &CurrentUser,
+	departure: TicketUserDeparture,
+) -> miette::Result<()> {
+	let already_ban_appeal = matches!(ticket.built_in_category, Some(BuiltInTicketCategory::BanAppeal));
+	let action = match (guild.departed_ticket_user_policy, departure) {
+		(DepartedTicketUserPolicy::Close, _) => DepartedTicketAction::Close,
+		(DepartedTicketUserPolicy::ConvertBans, TicketUserDeparture::Banned) if !already_ban_appeal => {
+			DepartedTicketAction::ConvertToBanAppeal
+		}
+		_ => DepartedTicketAction::KeepOpen,
+	};
+
+	let user_mention = ticket.get_with_user().mention();
+	let departure_text = match departure {
+		TicketUserDeparture::Removed => format!("{} is no longer in the server", user_mention),
+		TicketUserDeparture::Banned => format!("{} was banned from the server", user_mention),
+	};
+	let action_text = match action {
+		DepartedTicketAction::KeepOpen => String::from("The ticket has been left open."),
+		DepartedTicketAction::Close => String::from("The ticket has been closed."),
+		DepartedTicketAction::ConvertToBanAppeal => String::from(
+			"The ticket has been converted to a ban appeal, so replies can still be followed on the website.",
+		),
+	};
+	let notice = format!(
+		"{}, so they can't see replies in their ticket thread. {}",
+		departure_text, action_text
+	);
+
+	match action {
+		DepartedTicketAction::KeepOpen => (),
+		DepartedTicketAction::Close => {
+			diesel::update(tickets::table)
+				.filter(tickets::id.eq(&ticket.id))
+				.set(tickets::closed_at.eq(Some(Utc::now())))
+				.execute(db_connection)
+				.into_diagnostic()?;
+		}
+		DepartedTicketAction::ConvertToBanAppeal => {
+			diesel::update(tickets::table)
+				.filter(tickets::id.eq(&ticket.id))
+				.set((
+					tickets::built_in_category.eq(Some(BuiltInTicketCategory::BanAppeal)),
+					tickets::custom_category.eq(None::<String>),
+				))
+				.execute(db_connection)
+				.into_diagnostic()?;
+		}
+	}
+
+	let send_time = Utc::now();
+	let timestamp = Timestamp::from_micros(send_time.timestamp_micros()).into_diagnostic()?;
+	let staff_message_data = staff_message(&bot_user.name, &notice, timestamp).into_diagnostic()?;
+	let posted_message = staff_message_data
+		.set_create_message_data(http_client.create_message(ticket.get_staff_thread()))
+		.await
+		.into_diagnostic()?
+		.model()
+		.await
+		.into_diagnostic()?;
+	let ticket_message = TicketMessage {
+		id: cuid2::create_id(),
+		ticket: ticket.id.clone(),
+		author: database_id_from_discord_id(bot_user.id.get()),
+		send_time,
+		body: notice,
+		staff_message: database_id_from_discord_id(posted_message.id.get()),
+		user_message: None,
+	};
+	diesel::insert_into(ticket_messages::table)
+		.values(ticket_message)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	if let DepartedTicketAction::Close = action {
+		let staff_thread_future = http_client
+			.update_thread(ticket.get_staff_thread())
+			.locked(true)
+			.reason("Closed ticket after its user departed")
+			.into_future();
+		let user_thread_future = http_client
+			.update_thread(ticket.get_user_thread())
+			.locked(true)
+			.reason("Closed ticket after its user departed")
+			.into_future();
+		let (staff_thread_result, user_thread_result) = tokio::join!(staff_thread_future, user_thread_future);
+		staff_thread_result.into_diagnostic()?;
+		user_thread_result.into_diagnostic()?;
+	}
+
+	Ok(())
+}
@@ -0,0 +1,23 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::model::{TrackedActionType, UntrackedActionType};
+use crate::schema::untracked_action_types;
+use diesel::prelude::*;
+
+/// Checks whether the guild records actions of the given type. Action types are recorded unless the guild turned them
+/// off.
+pub fn is_action_tracked(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	action_type: TrackedActionType,
+) -> QueryResult<bool> {
+	let untracked: Option<UntrackedActionType> = untracked_action_types::table
+		.find((db_guild_id, action_type))
+		.first(db_connection)
+		.optional()?;
+	Ok(untracked.is_none())
+}
@@ -0,0 +1,122 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::discord::utils::cases::assign_case_number;
+use crate::discord::utils::mod_log::{MOD_LOG_COLOR_WARN, ModLogActor, ModLogEntry};
+use crate::discord::utils::moderation::{
+	ModerationCommandOptions, dm_result_text, dm_user_about_action, post_action_to_mod_log,
+};
+use crate::discord::utils::rules::{describe_rules, link_rules_from_reason};
+use crate::model::{Guild, ModerationActionType, WarnAction, database_id_from_discord_id};
+use crate::schema::warn_actions;
+use chrono::{TimeDelta, Utc};
+use diesel::prelude::*;
+use miette::IntoDiagnostic;
+use twilight_http::client::Client;
+use twilight_mention::fmt::Mention;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+/// Records a warning, tells the warned user about it, and posts it to the mod log.
+///
+/// The moderator's permission to warn the user should already have been checked. Returns the confirmation message to
+/// show the moderator.
+pub async fn give_warning(
+	db_connection: &mut PgConnection,
+	http_client: &Client,
+	config: &ConfigData,
+	guild: &Guild,
+	moderator_id: Id<UserMarker>,
+	options: &ModerationCommandOptions,
+) -> miette::Result<String> {
+	let guild_id = guild.get_guild_id();
+
+	// The DM is sent first so the record can say whether the user actually saw the warning
+	let dm_sent = if options.dm_user {
+		Some(dm_user_about_action(http_client, guild_id, options.target_user, "warned in", &options.reason).await)
+	} else {
+		None
+	};
+
+	let action_time = Utc::now();
+	let expires_at = guild
+		.warning_expiry_days
+		.and_then(|days| TimeDelta::try_days(i64::from(days)))
+		.map(|duration| action_time + duration);
+	let new_warn_action = WarnAction {
+		id: cuid2::create_id(),
+		guild: database_id_from_discord_id(guild_id.get()),
+		warning_user: database_id_from_discord_id(moderator_id.get()),
+		warned_user: database_id_from_discord_id(options.target_user.get()),
+		action_time,
+		reason: options.reason.clone(),
+		expires_at,
+		user_notified: dm_sent.unwrap_or(false),
+		evidence: options.evidence.clone(),
+		amended_reason: None,
+		reason_amended_by: None,
+		reason_amended_at: None,
+		mod_log_channel: None,
+		mod_log_message: None,
+	};
+	diesel::insert_into(warn_actions::table)
+		.values(&new_warn_action)
+		.execute(db_connection)
+		.into_diagnostic()?;
+
+	let case_number = assign_case_number(
+		db_connection,
+		new_warn_action.guild,
+		ModerationActionType::Warn,
+		&new_warn_action.id,
+	)
+	.into_diagnostic()?;
+	let rules = link_rules_from_reason(
+		db_connection,
+		new_warn_action.guild,
+		ModerationActionType::Warn,
+		&new_warn_action.id,
+		action_time,
+		&options.reason,
+		options.rule_number,
+	)
+	.into_diagnostic()?;
+	let mut details = Vec::new();
+	if !rules.is_empty() {
+		details.push(("Rules", describe_rules(&rules)));
+	}
+	if let Some(evidence) = &new_warn_action.evidence {
+		details.push(("Evidence", evidence.clone()));
+	}
+	let mod_log_entry = ModLogEntry {
+		title: "Warning",
+		color: MOD_LOG_COLOR_WARN,
+		case_number: Some(case_number),
+		target_user: Some(options.target_user),
+		actor: ModLogActor::User(moderator_id),
+		reason: Some(&new_warn_action.reason),
+		expires_at,
+		action_time,
+		details,
+	};
+	post_action_to_mod_log(
+		http_client,
+		config,
+		db_connection,
+		guild,
+		ModerationActionType::Warn,
+		&new_warn_action.id,
+		&mod_log_entry,
+	)
+	.await;
+
+	Ok(format!(
+		"Warned {}.{}",
+		options.target_user.mention(),
+		dm_result_text(dm_sent)
+	))
+}
@@ -0,0 +1,17 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::config::ConfigData;
+use crate::model::Guild;
+
+/// Gets the URL of a page of a guild's website, using the guild's custom host if it has one. The path is given without
+/// a leading slash (e.g. `staff/case/4`).
+pub fn guild_web_url(config: &ConfigData, guild: &Guild, path: &str) -> String {
+	match &guild.custom_host {
+		Some(host) => format!("https://{}/{}", host, path),
+		None => format!("{}/{}/{}", config.web.base_url, guild.get_guild_id(), path),
+	}
+}
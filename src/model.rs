@@ -5,8 +5,12 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::schema::{
-	automod_actions, ban_actions, custom_categories, form_questions, forms, guilds, kick_actions, pending_partnerships,
-	sessions, ticket_messages, ticket_restricted_users, tickets, timeout_actions,
+	action_rules, automod_actions, ban_actions, custom_categories, declined_partnerships, escalation_applications,
+	escalation_rules, form_questions, forms, guilds, kick_actions, member_events, message_delete_actions,
+	moderation_cases, nickname_change_actions, partner_invite_checks, partnerships, pending_automod_executions,
+	pending_partnerships, reason_policies, reason_policy_exempt_roles, role_change_actions, server_rules, sessions,
+	shared_bans, tcn_bans, tcn_partners, ticket_messages, ticket_restricted_users, tickets, timeout_actions,
+	trusted_guilds, untracked_action_types, user_note_edits, user_notes, voice_actions, warn_actions,
 };
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
@@ -14,7 +18,9 @@ use diesel::prelude::*;
 use diesel_derive_enum::DbEnum;
 use std::fmt;
 use twilight_model::id::Id;
-use twilight_model::id::marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker};
+use twilight_model::id::marker::{
+	AuditLogEntryMarker, ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker,
+};
 
 #[derive(DbEnum, Debug)]
 #[ExistingTypePath = "crate::schema::sql_types::BuiltInTicketCategory"]
@@ -37,11 +43,161 @@ impl fmt::Display for BuiltInTicketCategory {
 	}
 }
 
-#[derive(DbEnum, Debug)]
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
 #[ExistingTypePath = "crate::schema::sql_types::AutomodActionType"]
 pub enum AutomodActionType {
 	Block,
 	DisableCommunication,
+	FlagToChannel,
+}
+
+impl AutomodActionType {
+	/// The title used when showing an AutoMod action of this type in logs and histories
+	pub fn title(&self) -> &'static str {
+		match self {
+			Self::Block => "AutoMod: Message Blocked",
+			Self::DisableCommunication => "AutoMod: Timeout",
+			Self::FlagToChannel => "AutoMod: Message Flagged",
+		}
+	}
+}
+
+/// The kinds of moderation actions that are recorded by the bot
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::ModerationActionType"]
+pub enum ModerationActionType {
+	Automod,
+	Ban,
+	Kick,
+	Timeout,
+	Warn,
+}
+
+impl fmt::Display for ModerationActionType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::Automod => "Automod",
+			Self::Ban => "Ban",
+			Self::Kick => "Kick",
+			Self::Timeout => "Timeout",
+			Self::Warn => "Warning",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The kinds of voice channel moderation actions
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::VoiceActionType"]
+pub enum VoiceActionType {
+	Disconnect,
+	Move,
+}
+
+/// The kinds of moderation actions that guilds can choose not to record, since they can be noisy
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::TrackedActionType"]
+pub enum TrackedActionType {
+	RoleChange,
+	NicknameChange,
+	MessageDelete,
+	VoiceDisconnect,
+	VoiceMove,
+}
+
+impl TrackedActionType {
+	/// All of the action types that can be turned off
+	pub const ALL: [Self; 5] = [
+		Self::RoleChange,
+		Self::NicknameChange,
+		Self::MessageDelete,
+		Self::VoiceDisconnect,
+		Self::VoiceMove,
+	];
+}
+
+impl fmt::Display for TrackedActionType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::RoleChange => "Role changes",
+			Self::NicknameChange => "Nickname changes",
+			Self::MessageDelete => "Message deletions",
+			Self::VoiceDisconnect => "Voice disconnects",
+			Self::VoiceMove => "Voice moves",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The kinds of recorded moderation actions that count towards escalation rules
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::EscalationTriggerType"]
+pub enum EscalationTriggerType {
+	AutomodBlock,
+	AutomodFlag,
+	AutomodTimeout,
+	Kick,
+	Timeout,
+}
+
+impl fmt::Display for EscalationTriggerType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::AutomodBlock => "automod blocks",
+			Self::AutomodFlag => "automod flags",
+			Self::AutomodTimeout => "automod timeouts",
+			Self::Kick => "kicks",
+			Self::Timeout => "timeouts",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The actions escalation rules can take when they're triggered
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::EscalationActionType"]
+pub enum EscalationActionType {
+	Timeout,
+	Kick,
+	Ban,
+	StaffReview,
+}
+
+impl fmt::Display for EscalationActionType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::Timeout => "timeout",
+			Self::Kick => "kick",
+			Self::Ban => "ban",
+			Self::StaffReview => "staff review",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// What happens to a ticket when its user leaves the guild or is removed from it
+#[derive(Clone, Copy, DbEnum, Debug, Default, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::DepartedTicketUserPolicy"]
+pub enum DepartedTicketUserPolicy {
+	/// The ticket stays open in case the user returns
+	#[default]
+	KeepOpen,
+	/// The ticket is closed
+	Close,
+	/// Tickets of banned users become ban appeal tickets, which the user can still follow on the website; other
+	/// tickets stay open
+	ConvertBans,
+}
+
+impl fmt::Display for DepartedTicketUserPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::KeepOpen => "keep the ticket open",
+			Self::Close => "close the ticket",
+			Self::ConvertBans => "convert tickets of banned users to ban appeals and keep others open",
+		};
+		write!(f, "{}", name)
+	}
 }
 
 /// Gets a guild that's using the bot and its various settings.
@@ -105,6 +261,43 @@ pub struct Guild {
 	pub new_partner_ticket_form: Option<String>,
 	/// The ID of the form used for existing partnership tickets, if those tickets use a form.
 	pub existing_partner_ticket_form: Option<String>,
+	/// The ID of the channel to which the bot posts a log entry for each moderation action.
+	/// If the feature is disabled, no ID will be entered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the newest audit log entry the bot has processed for the guild, used to catch up on entries missed
+	/// while the bot was offline.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_last_audit_log_entry].
+	pub last_audit_log_entry: Option<i64>,
+	/// How many days warnings remain active for after they're given. If null, warnings don't expire.
+	pub warning_expiry_days: Option<i32>,
+	/// The case number most recently given to a moderation action in the guild
+	pub last_case_number: i32,
+	/// The ID of the channel to which the bot posts alerts about partner invites that stop working.
+	/// If the feature is disabled, no ID will be entered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_partner_invite_alert_channel].
+	pub partner_invite_alert_channel: Option<i64>,
+	/// The ID of the channel in which the bot keeps a list of the guild's active partners.
+	/// If the feature is disabled, no ID will be entered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_partner_list_channel].
+	pub partner_list_channel: Option<i64>,
+	/// The ID of the partner list message in the partner list channel.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_partner_list_message].
+	pub partner_list_message: Option<i64>,
+	/// The ID of the channel to which the bot posts alerts about members joining who need staff attention.
+	/// If the feature is disabled, no ID will be entered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_member_alert_channel].
+	pub member_alert_channel: Option<i64>,
+	/// Accounts younger than this many days are flagged when they join. If null, account age isn't flagged.
+	pub new_account_age_days: Option<i32>,
+	/// What happens to open tickets when their user leaves the guild, is kicked, or is banned.
+	pub departed_ticket_user_policy: DepartedTicketUserPolicy,
 }
 
 impl Guild {
@@ -190,6 +383,54 @@ impl Guild {
 		self.action_reason_complain_channel
 			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
 	}
+
+	/// The channel to which the bot posts a log entry for each moderation action.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The newest audit log entry the bot has processed for the guild.
+	///
+	/// For the raw database representation, use [Self::last_audit_log_entry].
+	pub fn get_last_audit_log_entry(&self) -> Option<Id<AuditLogEntryMarker>> {
+		self.last_audit_log_entry
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The channel to which the bot posts alerts about partner invites that stop working.
+	///
+	/// For the raw database representation, use [Self::partner_invite_alert_channel].
+	pub fn get_partner_invite_alert_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.partner_invite_alert_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The channel in which the bot keeps a list of the guild's active partners.
+	///
+	/// For the raw database representation, use [Self::partner_list_channel].
+	pub fn get_partner_list_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.partner_list_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The partner list message in the partner list channel.
+	///
+	/// For the raw database representation, use [Self::partner_list_message].
+	pub fn get_partner_list_message(&self) -> Option<Id<MessageMarker>> {
+		self.partner_list_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The channel to which the bot posts alerts about members joining who need staff attention.
+	///
+	/// For the raw database representation, use [Self::member_alert_channel].
+	pub fn get_member_alert_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.member_alert_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
 /// The database representation of a form, a set of default questions that can be given to a user for a particular type
@@ -295,6 +536,8 @@ pub struct Ticket {
 	pub user_thread: i64,
 	/// When the ticket was closed. If not specified, the ticket is still open.
 	pub closed_at: Option<DateTime<Utc>>,
+	/// The ID of the partnership the ticket is about, for existing partner tickets that were linked to one
+	pub partnership: Option<String>,
 }
 
 impl Ticket {
@@ -402,6 +645,32 @@ pub struct AutomodAction {
 	pub reason: String,
 	/// The name of the automod rule that was triggered
 	pub rule_name: String,
+	/// The content of the message that triggered automod, if it's been received
+	pub message_content: Option<String>,
+	/// The ID of the channel in which the mod log entry for this action was posted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the mod log entry message for this action.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_message].
+	pub mod_log_message: Option<i64>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: Option<i64>,
+	/// The ID of the channel in which the triggering message was sent, if it's known.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_channel].
+	pub channel: Option<i64>,
+	/// The keyword in the automod rule that the message matched, if any
+	pub matched_keyword: Option<String>,
+	/// The part of the message that matched the automod rule, if any
+	pub matched_content: Option<String>,
+	/// The ID of the alert message automod posted when flagging the message to a channel.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_alert_message].
+	pub alert_message: Option<i64>,
 }
 
 impl AutomodAction {
@@ -418,6 +687,173 @@ impl AutomodAction {
 	pub fn get_target_user(&self) -> Id<UserMarker> {
 		Id::new(discord_id_from_database_id(self.target_user))
 	}
+
+	/// The channel in which the mod log entry for this action was posted.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The mod log entry message for this action.
+	///
+	/// For the raw database representation, use [Self::mod_log_message].
+	pub fn get_mod_log_message(&self) -> Option<Id<MessageMarker>> {
+		self.mod_log_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Option<Id<AuditLogEntryMarker>> {
+		self.audit_log_entry
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The channel in which the triggering message was sent.
+	///
+	/// For the raw database representation, use [Self::channel].
+	pub fn get_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The alert message automod posted when flagging the message to a channel.
+	///
+	/// For the raw database representation, use [Self::alert_message].
+	pub fn get_alert_message(&self) -> Option<Id<MessageMarker>> {
+		self.alert_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+}
+
+/// The database representation of a guild in the partner network, as last pulled from the network
+#[derive(Debug, Insertable, Queryable)]
+pub struct TcnPartner {
+	/// The ID of the network guild
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild_id].
+	pub guild_id: i64,
+	/// The name of the guild as known to the network
+	pub name: String,
+	/// The network's invite code for the guild
+	pub invite_code: String,
+	/// When the guild was last pulled from the network
+	pub synced_at: DateTime<Utc>,
+}
+
+impl TcnPartner {
+	/// The ID of the network guild
+	///
+	/// For the raw database representation, use [Self::guild_id].
+	pub fn get_guild_id(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild_id))
+	}
+}
+
+/// The database representation of a ban shared through the partner network, as last pulled from the network
+#[derive(Debug, Insertable, Queryable)]
+pub struct TcnBan {
+	/// The network's ID for the ban
+	pub id: String,
+	/// The banned user
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_user_id].
+	pub user_id: i64,
+	/// The network guild that shared the ban
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_origin_guild].
+	pub origin_guild: i64,
+	/// The reason given for the ban
+	pub reason: String,
+	/// When the user was banned
+	pub banned_at: DateTime<Utc>,
+	/// When the ban was last pulled from the network
+	pub synced_at: DateTime<Utc>,
+}
+
+impl TcnBan {
+	/// The banned user
+	///
+	/// For the raw database representation, use [Self::user_id].
+	pub fn get_user_id(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.user_id))
+	}
+
+	/// The network guild that shared the ban
+	///
+	/// For the raw database representation, use [Self::origin_guild].
+	pub fn get_origin_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.origin_guild))
+	}
+}
+
+/// The database representation of an automod execution event that hasn't yet been matched to the audit log entry for
+/// the same action
+#[derive(Debug, Insertable, Queryable)]
+pub struct PendingAutomodExecution {
+	/// The ID of the pending execution
+	pub id: String,
+	/// The ID of the guild in which automod took action.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user on whom automod took action.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: i64,
+	/// The type of action taken by automod
+	pub action_type: AutomodActionType,
+	/// When the execution event was received
+	pub received_at: DateTime<Utc>,
+	/// The ID of the channel in which the triggering message was sent, if there was one.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_channel].
+	pub channel: Option<i64>,
+	/// The content of the message that triggered automod
+	pub message_content: String,
+	/// The keyword in the automod rule that the message matched, if any
+	pub matched_keyword: Option<String>,
+	/// The part of the message that matched the automod rule, if any
+	pub matched_content: Option<String>,
+	/// The ID of the alert message automod posted when flagging the message to a channel.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_alert_message].
+	pub alert_message: Option<i64>,
+}
+
+impl PendingAutomodExecution {
+	/// The guild in which automod took action.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user on whom automod took action.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.target_user))
+	}
+
+	/// The channel in which the triggering message was sent.
+	///
+	/// For the raw database representation, use [Self::channel].
+	pub fn get_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The alert message automod posted when flagging the message to a channel.
+	///
+	/// For the raw database representation, use [Self::alert_message].
+	pub fn get_alert_message(&self) -> Option<Id<MessageMarker>> {
+		self.alert_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
 /// The database representation of a ban or unban
@@ -443,6 +879,38 @@ pub struct BanAction {
 	pub action_time: DateTime<Utc>,
 	/// The ban reason entered by the banning user
 	pub reason: String,
+	/// The reason provided by a staff member after the action took place, if any
+	pub amended_reason: Option<String>,
+	/// The ID of the staff member who provided the amended reason.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_reason_amended_by].
+	pub reason_amended_by: Option<i64>,
+	/// When the amended reason was provided
+	pub reason_amended_at: Option<DateTime<Utc>>,
+	/// The ID of the channel in which the mod log entry for this ban was posted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the mod log entry message for this ban.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_message].
+	pub mod_log_message: Option<i64>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: Option<i64>,
+	/// Links to messages serving as evidence for the action, if any were given
+	pub evidence: Option<String>,
+	/// Whether the action was performed through one of the bot's moderation commands
+	pub via_bot_command: bool,
+	/// If the ban was applied automatically by an escalation rule, the ID of that rule
+	pub escalation_rule: Option<String>,
+	/// When the ban is automatically lifted, if it's temporary
+	pub expires_at: Option<DateTime<Utc>>,
+	/// For an unban that lifted a temporary ban, the ID of the ban that was lifted
+	pub original_ban: Option<String>,
+	/// If the ban or unban was applied automatically because a trusted guild shared one, the ID of the shared record
+	pub shared_ban: Option<String>,
 }
 
 impl BanAction {
@@ -466,6 +934,43 @@ impl BanAction {
 	pub fn get_banned_user(&self) -> Id<UserMarker> {
 		Id::new(discord_id_from_database_id(self.banned_user))
 	}
+
+	/// The staff member who provided the amended reason.
+	///
+	/// For the raw database representation, use [Self::reason_amended_by].
+	pub fn get_reason_amended_by(&self) -> Option<Id<UserMarker>> {
+		self.reason_amended_by
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The most up-to-date reason for the ban, preferring a reason provided after the fact over the audit log reason
+	pub fn current_reason(&self) -> &str {
+		self.amended_reason.as_deref().unwrap_or(&self.reason)
+	}
+
+	/// The channel in which the mod log entry for this ban was posted.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The mod log entry message for this ban.
+	///
+	/// For the raw database representation, use [Self::mod_log_message].
+	pub fn get_mod_log_message(&self) -> Option<Id<MessageMarker>> {
+		self.mod_log_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Option<Id<AuditLogEntryMarker>> {
+		self.audit_log_entry
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
 /// The database representation of a kick action
@@ -489,6 +994,32 @@ pub struct KickAction {
 	pub action_time: DateTime<Utc>,
 	/// The kick reason entered by the kicking user
 	pub reason: String,
+	/// The reason provided by a staff member after the action took place, if any
+	pub amended_reason: Option<String>,
+	/// The ID of the staff member who provided the amended reason.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_reason_amended_by].
+	pub reason_amended_by: Option<i64>,
+	/// When the amended reason was provided
+	pub reason_amended_at: Option<DateTime<Utc>>,
+	/// The ID of the channel in which the mod log entry for this kick was posted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the mod log entry message for this kick.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_message].
+	pub mod_log_message: Option<i64>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: Option<i64>,
+	/// Links to messages serving as evidence for the action, if any were given
+	pub evidence: Option<String>,
+	/// Whether the action was performed through one of the bot's moderation commands
+	pub via_bot_command: bool,
+	/// If the kick was applied automatically by an escalation rule, the ID of that rule
+	pub escalation_rule: Option<String>,
 }
 
 impl KickAction {
@@ -512,6 +1043,43 @@ impl KickAction {
 	pub fn get_kicked_user(&self) -> Id<UserMarker> {
 		Id::new(discord_id_from_database_id(self.kicked_user))
 	}
+
+	/// The staff member who provided the amended reason.
+	///
+	/// For the raw database representation, use [Self::reason_amended_by].
+	pub fn get_reason_amended_by(&self) -> Option<Id<UserMarker>> {
+		self.reason_amended_by
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The most up-to-date reason for the kick, preferring a reason provided after the fact over the audit log reason
+	pub fn current_reason(&self) -> &str {
+		self.amended_reason.as_deref().unwrap_or(&self.reason)
+	}
+
+	/// The channel in which the mod log entry for this kick was posted.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The mod log entry message for this kick.
+	///
+	/// For the raw database representation, use [Self::mod_log_message].
+	pub fn get_mod_log_message(&self) -> Option<Id<MessageMarker>> {
+		self.mod_log_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Option<Id<AuditLogEntryMarker>> {
+		self.audit_log_entry
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
 /// The database representation of a timeout action
@@ -537,6 +1105,32 @@ pub struct TimeoutAction {
 	pub timeout_until: Option<DateTime<Utc>>,
 	/// The timeout reason entered by the user
 	pub reason: String,
+	/// The reason provided by a staff member after the action took place, if any
+	pub amended_reason: Option<String>,
+	/// The ID of the staff member who provided the amended reason.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_reason_amended_by].
+	pub reason_amended_by: Option<i64>,
+	/// When the amended reason was provided
+	pub reason_amended_at: Option<DateTime<Utc>>,
+	/// The ID of the channel in which the mod log entry for this timeout was posted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the mod log entry message for this timeout.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_message].
+	pub mod_log_message: Option<i64>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: Option<i64>,
+	/// Links to messages serving as evidence for the action, if any were given
+	pub evidence: Option<String>,
+	/// Whether the action was performed through one of the bot's moderation commands
+	pub via_bot_command: bool,
+	/// If the timeout was applied automatically by an escalation rule, the ID of that rule
+	pub escalation_rule: Option<String>,
 }
 
 impl TimeoutAction {
@@ -560,41 +1154,800 @@ impl TimeoutAction {
 	pub fn get_target_user(&self) -> Id<UserMarker> {
 		Id::new(discord_id_from_database_id(self.target_user))
 	}
+
+	/// The staff member who provided the amended reason.
+	///
+	/// For the raw database representation, use [Self::reason_amended_by].
+	pub fn get_reason_amended_by(&self) -> Option<Id<UserMarker>> {
+		self.reason_amended_by
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The most up-to-date reason for the timeout, preferring a reason provided after the fact over the audit log reason
+	pub fn current_reason(&self) -> &str {
+		self.amended_reason.as_deref().unwrap_or(&self.reason)
+	}
+
+	/// The channel in which the mod log entry for this timeout was posted.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The mod log entry message for this timeout.
+	///
+	/// For the raw database representation, use [Self::mod_log_message].
+	pub fn get_mod_log_message(&self) -> Option<Id<MessageMarker>> {
+		self.mod_log_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Option<Id<AuditLogEntryMarker>> {
+		self.audit_log_entry
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
-/// The database representation of a partnership that is being discussed or decided
+/// The database representation of a warning
 #[derive(Debug, Insertable, Queryable)]
-pub struct PendingPartnership {
-	/// The ID of the pending partnership
+pub struct WarnAction {
+	/// The ID of the warning
 	pub id: String,
-	/// The guild to which the partner request was sent
+	/// The ID of the guild in which the warning was given.
 	///
-	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
 	pub guild: i64,
-	/// The guild ID of the partner
+	/// The ID of the user who gave the warning.
 	///
-	/// To get a Discord-facing representation of this more easily, use [Self::get_partner_guild].
-	pub partner_guild: i64,
-	/// The invite code usable to join the partner guild
-	pub invite_code: String,
-	/// The ID of the ticket submitted with the partnership request
-	pub ticket: String,
+	/// To get a Discord-facing version of this more easily, use [Self::get_warning_user].
+	pub warning_user: i64,
+	/// The ID of the user who was warned.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_warned_user].
+	pub warned_user: i64,
+	/// When the warning was given
+	pub action_time: DateTime<Utc>,
+	/// The warning reason entered by the warning user
+	pub reason: String,
+	/// When the warning stops counting against the user. If null, the warning doesn't expire.
+	pub expires_at: Option<DateTime<Utc>>,
+	/// Whether the warned user was successfully sent a DM about the warning
+	pub user_notified: bool,
+	/// Links to messages serving as evidence for the warning, if any were given
+	pub evidence: Option<String>,
+	/// The reason provided by a staff member after the action took place, if any
+	pub amended_reason: Option<String>,
+	/// The ID of the staff member who provided the amended reason.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_reason_amended_by].
+	pub reason_amended_by: Option<i64>,
+	/// When the amended reason was provided
+	pub reason_amended_at: Option<DateTime<Utc>>,
+	/// The ID of the channel in which the mod log entry for this warning was posted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_channel].
+	pub mod_log_channel: Option<i64>,
+	/// The ID of the mod log entry message for this warning.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_mod_log_message].
+	pub mod_log_message: Option<i64>,
 }
 
-impl PendingPartnership {
-	/// The ID of the guild to which the partner request was sent
+impl WarnAction {
+	/// The ID of the guild in which the warning was given.
 	///
 	/// For the raw database representation, use [Self::guild].
 	pub fn get_guild(&self) -> Id<GuildMarker> {
 		Id::new(discord_id_from_database_id(self.guild))
 	}
 
-	/// The ID of the guild that is the partner
+	/// The ID of the user who gave the warning.
+	///
+	/// For the raw database representation, use [Self::warning_user].
+	pub fn get_warning_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.warning_user))
+	}
+
+	/// The ID of the user who was warned.
+	///
+	/// For the raw database representation, use [Self::warned_user].
+	pub fn get_warned_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.warned_user))
+	}
+
+	/// The staff member who provided the amended reason.
+	///
+	/// For the raw database representation, use [Self::reason_amended_by].
+	pub fn get_reason_amended_by(&self) -> Option<Id<UserMarker>> {
+		self.reason_amended_by
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The most up-to-date reason for the warning, preferring a reason provided after the fact over the original reason
+	pub fn current_reason(&self) -> &str {
+		self.amended_reason.as_deref().unwrap_or(&self.reason)
+	}
+
+	/// Whether the warning has expired as of the given time
+	pub fn is_expired_at(&self, time: DateTime<Utc>) -> bool {
+		self.expires_at.is_some_and(|expires_at| expires_at <= time)
+	}
+
+	/// The channel in which the mod log entry for this warning was posted.
+	///
+	/// For the raw database representation, use [Self::mod_log_channel].
+	pub fn get_mod_log_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.mod_log_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The mod log entry message for this warning.
+	///
+	/// For the raw database representation, use [Self::mod_log_message].
+	pub fn get_mod_log_message(&self) -> Option<Id<MessageMarker>> {
+		self.mod_log_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+}
+
+/// The database representation of a case number given to a moderation action
+#[derive(Debug, Insertable, Queryable)]
+pub struct ModerationCase {
+	/// The ID of the guild in which the action took place.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The case number, which counts up from 1 in each guild
+	pub case_number: i32,
+	/// The type of the action
+	pub action_type: ModerationActionType,
+	/// The ID of the action in the table for its type
+	pub action_id: String,
+}
+
+impl ModerationCase {
+	/// The ID of the guild in which the action took place.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+}
+
+/// A guild's rule for automatically acting against users who repeatedly have actions taken against them
+#[derive(Debug, Insertable, Queryable)]
+pub struct EscalationRule {
+	/// The ID of the rule
+	pub id: String,
+	/// The ID of the guild to which the rule applies.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The type of action counted by the rule
+	pub trigger_type: EscalationTriggerType,
+	/// How many actions of the trigger type trigger the rule
+	pub threshold: i32,
+	/// How many days back actions are counted. If null, all of the user's actions are counted.
+	pub window_days: Option<i32>,
+	/// The action taken when the rule is triggered
+	pub action_type: EscalationActionType,
+	/// For timeout rules, how long the timeout lasts in minutes
+	pub timeout_minutes: Option<i32>,
+	/// The ID of the staff member who created the rule.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_created_by].
+	pub created_by: i64,
+	/// When the rule was created
+	pub created_at: DateTime<Utc>,
+	/// Whether the rule is still in use. Removed rules are kept so that actions they applied can still refer to them.
+	pub active: bool,
+}
+
+impl EscalationRule {
+	/// The ID of the guild to which the rule applies.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The ID of the staff member who created the rule.
+	///
+	/// For the raw database representation, use [Self::created_by].
+	pub fn get_created_by(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.created_by))
+	}
+}
+
+/// A record of an escalation rule being triggered for a user
+#[derive(Debug, Insertable, Queryable)]
+pub struct EscalationApplication {
+	/// The ID of the application
+	pub id: String,
+	/// The ID of the rule that was triggered
+	pub rule: String,
+	/// The ID of the guild in which the rule was triggered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user for whom the rule was triggered.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: i64,
+	/// When the rule was triggered
+	pub applied_at: DateTime<Utc>,
+	/// The ID of the audit log entry for the action that triggered the rule.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_triggering_audit_log_entry].
+	pub triggering_audit_log_entry: i64,
+}
+
+impl EscalationApplication {
+	/// The ID of the guild in which the rule was triggered.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The ID of the user for whom the rule was triggered.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.target_user))
+	}
+
+	/// The ID of the audit log entry for the action that triggered the rule.
+	///
+	/// For the raw database representation, use [Self::triggering_audit_log_entry].
+	pub fn get_triggering_audit_log_entry(&self) -> Id<AuditLogEntryMarker> {
+		Id::new(discord_id_from_database_id(self.triggering_audit_log_entry))
+	}
+}
+
+/// A rule of a guild, which moderation actions can reference
+#[derive(Debug, Insertable, Queryable)]
+pub struct ServerRule {
+	/// The ID of the rule
+	pub id: String,
+	/// The ID of the guild to which the rule belongs.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The number by which the guild refers to the rule
+	pub rule_number: i32,
+	/// A short title for the rule
+	pub title: String,
+	/// The full text of the rule
+	pub description: String,
+	/// Whether the rule is still in use. Removed rules are kept so that actions that referenced them still can.
+	pub active: bool,
+}
+
+impl ServerRule {
+	/// The ID of the guild to which the rule belongs.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+}
+
+/// A record of a moderation action being taken for breaking a server rule
+#[derive(Debug, Insertable, Queryable)]
+pub struct ActionRule {
+	/// The type of the action
+	pub action_type: ModerationActionType,
+	/// The ID of the action in the table for its type
+	pub action_id: String,
+	/// The ID of the rule that was broken
+	pub rule: String,
+	/// When the action was taken
+	pub action_time: DateTime<Utc>,
+}
+
+/// The database representation of a role being given to or taken from a user
+#[derive(Debug, Insertable, Queryable)]
+pub struct RoleChangeAction {
+	/// The ID of the role change
+	pub id: String,
+	/// The ID of the guild in which the role change occurred.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user who changed the role.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_performing_user].
+	pub performing_user: i64,
+	/// The ID of the user whose roles changed.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: i64,
+	/// The ID of the role that was given or taken.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_role].
+	pub role: i64,
+	/// Whether the role was given (as opposed to taken)
+	pub added: bool,
+	/// When the role change occurred
+	pub action_time: DateTime<Utc>,
+	/// The reason for the role change
+	pub reason: String,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: i64,
+}
+
+impl RoleChangeAction {
+	/// The guild in which the role change occurred.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user who changed the role.
+	///
+	/// For the raw database representation, use [Self::performing_user].
+	pub fn get_performing_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.performing_user))
+	}
+
+	/// The user whose roles changed.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.target_user))
+	}
+
+	/// The role that was given or taken.
+	///
+	/// For the raw database representation, use [Self::role].
+	pub fn get_role(&self) -> Id<RoleMarker> {
+		Id::new(discord_id_from_database_id(self.role))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Id<AuditLogEntryMarker> {
+		Id::new(discord_id_from_database_id(self.audit_log_entry))
+	}
+}
+
+/// The database representation of a moderator changing a user's nickname
+#[derive(Debug, Insertable, Queryable)]
+pub struct NicknameChangeAction {
+	/// The ID of the nickname change
+	pub id: String,
+	/// The ID of the guild in which the nickname was changed.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user who changed the nickname.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_performing_user].
+	pub performing_user: i64,
+	/// The ID of the user whose nickname was changed.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: i64,
+	/// The user's nickname before the change, if they had one
+	pub old_nickname: Option<String>,
+	/// The user's nickname after the change, if it wasn't reset
+	pub new_nickname: Option<String>,
+	/// When the nickname was changed
+	pub action_time: DateTime<Utc>,
+	/// The reason for the nickname change
+	pub reason: String,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: i64,
+}
+
+impl NicknameChangeAction {
+	/// The guild in which the nickname was changed.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user who changed the nickname.
+	///
+	/// For the raw database representation, use [Self::performing_user].
+	pub fn get_performing_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.performing_user))
+	}
+
+	/// The user whose nickname was changed.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.target_user))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Id<AuditLogEntryMarker> {
+		Id::new(discord_id_from_database_id(self.audit_log_entry))
+	}
+}
+
+/// The database representation of a moderator deleting one or more messages
+#[derive(Debug, Insertable, Queryable)]
+pub struct MessageDeleteAction {
+	/// The ID of the deletion
+	pub id: String,
+	/// The ID of the guild in which the messages were deleted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user who deleted the messages.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_performing_user].
+	pub performing_user: i64,
+	/// The ID of the user who sent the deleted messages. Bulk deletions don't record this.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: Option<i64>,
+	/// The ID of the channel from which the messages were deleted.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_channel].
+	pub channel: i64,
+	/// The number of messages deleted
+	pub message_count: i32,
+	/// Whether the messages were deleted in bulk
+	pub bulk: bool,
+	/// When the messages were deleted
+	pub action_time: DateTime<Utc>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: i64,
+}
+
+impl MessageDeleteAction {
+	/// The guild in which the messages were deleted.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user who deleted the messages.
+	///
+	/// For the raw database representation, use [Self::performing_user].
+	pub fn get_performing_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.performing_user))
+	}
+
+	/// The user who sent the deleted messages.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Option<Id<UserMarker>> {
+		self.target_user
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The channel from which the messages were deleted.
+	///
+	/// For the raw database representation, use [Self::channel].
+	pub fn get_channel(&self) -> Id<ChannelMarker> {
+		Id::new(discord_id_from_database_id(self.channel))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Id<AuditLogEntryMarker> {
+		Id::new(discord_id_from_database_id(self.audit_log_entry))
+	}
+}
+
+/// The database representation of a moderator disconnecting or moving members in voice channels
+#[derive(Debug, Insertable, Queryable)]
+pub struct VoiceAction {
+	/// The ID of the voice action
+	pub id: String,
+	/// The ID of the guild in which the voice action occurred.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user who disconnected or moved members.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_performing_user].
+	pub performing_user: i64,
+	/// Whether members were disconnected or moved
+	pub action_type: VoiceActionType,
+	/// The ID of the channel to which members were moved. Disconnections don't have a channel.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_channel].
+	pub channel: Option<i64>,
+	/// The number of members disconnected or moved.
+	///
+	/// Discord doesn't tell us which members were affected.
+	pub member_count: i32,
+	/// When the voice action occurred
+	pub action_time: DateTime<Utc>,
+	/// The ID of the audit log entry from which this action was recorded.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_audit_log_entry].
+	pub audit_log_entry: i64,
+}
+
+impl VoiceAction {
+	/// The guild in which the voice action occurred.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user who disconnected or moved members.
+	///
+	/// For the raw database representation, use [Self::performing_user].
+	pub fn get_performing_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.performing_user))
+	}
+
+	/// The channel to which members were moved.
+	///
+	/// For the raw database representation, use [Self::channel].
+	pub fn get_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The audit log entry from which this action was recorded.
+	///
+	/// For the raw database representation, use [Self::audit_log_entry].
+	pub fn get_audit_log_entry(&self) -> Id<AuditLogEntryMarker> {
+		Id::new(discord_id_from_database_id(self.audit_log_entry))
+	}
+}
+
+/// The database representation of an action type a guild doesn't want recorded
+#[derive(Debug, Insertable, Queryable)]
+pub struct UntrackedActionType {
+	/// The guild that doesn't record the action type.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The action type that isn't recorded
+	pub action_type: TrackedActionType,
+}
+
+impl UntrackedActionType {
+	/// The guild that doesn't record the action type.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+}
+
+/// The database representation of a partnership that is being discussed or decided
+#[derive(Debug, Insertable, Queryable)]
+pub struct PendingPartnership {
+	/// The ID of the pending partnership
+	pub id: String,
+	/// The guild to which the partner request was sent
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The guild ID of the partner
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_partner_guild].
+	pub partner_guild: i64,
+	/// The invite code usable to join the partner guild
+	pub invite_code: String,
+	/// The ID of the ticket submitted with the partnership request
+	pub ticket: String,
+}
+
+impl PendingPartnership {
+	/// The ID of the guild to which the partner request was sent
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The ID of the guild that is the partner
+	///
+	/// For the raw database representation, use [Self::partner_guild].
+	pub fn get_partner_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.partner_guild))
+	}
+}
+
+/// The database representation of a partnership approved by staff
+#[derive(Debug, Insertable, Queryable)]
+pub struct Partnership {
+	/// The ID of the partnership
+	pub id: String,
+	/// The guild with which the partnership was made
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The guild ID of the partner
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_partner_guild].
+	pub partner_guild: i64,
+	/// The name of the partner guild at the time of approval
+	pub partner_name: String,
+	/// The icon hash of the partner guild at the time of approval, if it had one
+	pub partner_icon: Option<String>,
+	/// The approximate member count of the partner guild at the time of approval
+	pub member_count: i64,
+	/// The invite code usable to join the partner guild
+	pub invite_code: String,
+	/// The user who represents the partner guild
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_representative].
+	pub representative: i64,
+	/// The ID of the ticket in which the partnership was requested
+	pub ticket: String,
+	/// The staff member who approved the partnership
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_approved_by].
+	pub approved_by: i64,
+	/// When the partnership was approved
+	pub approved_at: DateTime<Utc>,
+	/// Whether the partnership is currently active. Inactive partnerships are kept for their history but aren't listed
+	/// publicly or checked.
+	pub active: bool,
+	/// Whether the partnership's current state still needs to be pushed to the partner network
+	pub tcn_push_pending: bool,
+}
+
+impl Partnership {
+	/// The ID of the guild with which the partnership was made
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The ID of the guild that is the partner
+	///
+	/// For the raw database representation, use [Self::partner_guild].
+	pub fn get_partner_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.partner_guild))
+	}
+
+	/// The user who represents the partner guild
+	///
+	/// For the raw database representation, use [Self::representative].
+	pub fn get_representative(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.representative))
+	}
+
+	/// The staff member who approved the partnership
+	///
+	/// For the raw database representation, use [Self::approved_by].
+	pub fn get_approved_by(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.approved_by))
+	}
+}
+
+/// The database representation of a partnership request that staff declined
+#[derive(Debug, Insertable, Queryable)]
+pub struct DeclinedPartnership {
+	/// The ID of the declined partnership
+	pub id: String,
+	/// The guild to which the partner request was sent
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The guild ID of the prospective partner
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_partner_guild].
+	pub partner_guild: i64,
+	/// The invite code that was submitted with the request
+	pub invite_code: String,
+	/// The user who requested the partnership
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_representative].
+	pub representative: i64,
+	/// The ID of the ticket in which the partnership was requested
+	pub ticket: String,
+	/// The staff member who declined the partnership
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_declined_by].
+	pub declined_by: i64,
+	/// When the partnership was declined
+	pub declined_at: DateTime<Utc>,
+	/// The reason given to the requester for declining
+	pub reason: String,
+}
+
+impl DeclinedPartnership {
+	/// The ID of the guild to which the partner request was sent
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The ID of the prospective partner guild
 	///
 	/// For the raw database representation, use [Self::partner_guild].
 	pub fn get_partner_guild(&self) -> Id<GuildMarker> {
 		Id::new(discord_id_from_database_id(self.partner_guild))
 	}
+
+	/// The user who requested the partnership
+	///
+	/// For the raw database representation, use [Self::representative].
+	pub fn get_representative(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.representative))
+	}
+
+	/// The staff member who declined the partnership
+	///
+	/// For the raw database representation, use [Self::declined_by].
+	pub fn get_declined_by(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.declined_by))
+	}
+}
+
+/// The result of checking whether a partner's invite still works
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::PartnerInviteStatus"]
+pub enum PartnerInviteStatus {
+	Valid,
+	Expiring,
+	Invalid,
+}
+
+impl fmt::Display for PartnerInviteStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::Valid => "Valid",
+			Self::Expiring => "Expiring",
+			Self::Invalid => "Invalid",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The database representation of a check of a partner's invite
+#[derive(Debug, Insertable, Queryable)]
+pub struct PartnerInviteCheck {
+	/// The ID of the check
+	pub id: String,
+	/// The ID of the partnership whose invite was checked
+	pub partnership: String,
+	/// When the invite was checked
+	pub checked_at: DateTime<Utc>,
+	/// What the check found
+	pub status: PartnerInviteStatus,
+	/// When the invite expires, if it was found to have an expiry
+	pub expires_at: Option<DateTime<Utc>>,
+	/// The alert posted about the check, if one was posted
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_alert_message].
+	pub alert_message: Option<i64>,
+}
+
+impl PartnerInviteCheck {
+	/// The alert posted about the check, if one was posted
+	///
+	/// For the raw database representation, use [Self::alert_message].
+	pub fn get_alert_message(&self) -> Option<Id<MessageMarker>> {
+		self.alert_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
 }
 
 #[derive(Debug, Insertable, Queryable)]
@@ -604,6 +1957,60 @@ pub struct Session {
 	pub expires: DateTime<Utc>,
 }
 
+/// The database representation of a guild's policy for moderation action reasons
+#[derive(Debug, Insertable, Queryable)]
+#[diesel(table_name = reason_policies)]
+pub struct ReasonPolicy {
+	/// The guild to which the policy applies.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The minimum number of characters a reason must have
+	pub minimum_length: i32,
+	/// A regular expression that reasons must match, if any
+	pub required_pattern: Option<String>,
+	/// Whether actions performed by bots are exempt from the policy
+	pub exempt_bots: bool,
+}
+
+impl ReasonPolicy {
+	/// The guild to which the policy applies.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+}
+
+/// The database representation of a role whose members are exempt from a guild's reason policy
+#[derive(Debug, Insertable, Queryable)]
+pub struct ReasonPolicyExemptRole {
+	/// The guild to which the exemption applies.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The exempt role.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_role].
+	pub role: i64,
+}
+
+impl ReasonPolicyExemptRole {
+	/// The guild to which the exemption applies.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The exempt role.
+	///
+	/// For the raw database representation, use [Self::role].
+	pub fn get_role(&self) -> Id<RoleMarker> {
+		Id::new(discord_id_from_database_id(self.role))
+	}
+}
+
 /// The database representation of a user who is restricted from creating new tickets
 #[derive(Debug, Insertable, Queryable)]
 pub struct TicketRestrictedUser {
@@ -633,12 +2040,268 @@ impl TicketRestrictedUser {
 	}
 }
 
+/// The database representation of a note written by staff about a user
+#[derive(Debug, Insertable, Queryable)]
+pub struct UserNote {
+	/// The ID of the note
+	pub id: String,
+	/// The ID of the guild in which the note was written.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The ID of the user the note is about.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_target_user].
+	pub target_user: i64,
+	/// The ID of the staff member who wrote the note.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_author].
+	pub author: i64,
+	/// When the note was written
+	pub created_at: DateTime<Utc>,
+	/// The current text of the note
+	pub body: String,
+	/// If the note is about a moderation action, the type of that action
+	pub action_type: Option<ModerationActionType>,
+	/// If the note is about a moderation action, the ID of that action
+	pub action_id: Option<String>,
+	/// If the note is about a ticket, the ID of that ticket
+	pub ticket: Option<String>,
+}
+
+impl UserNote {
+	/// The guild in which the note was written.
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user the note is about.
+	///
+	/// For the raw database representation, use [Self::target_user].
+	pub fn get_target_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.target_user))
+	}
+
+	/// The staff member who wrote the note.
+	///
+	/// For the raw database representation, use [Self::author].
+	pub fn get_author(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.author))
+	}
+}
+
+/// The database representation of a previous version of a note, saved when the note is edited
+#[derive(Debug, Insertable, Queryable)]
+pub struct UserNoteEdit {
+	/// The ID of the edit
+	pub id: String,
+	/// The ID of the note that was edited
+	pub note: String,
+	/// The ID of the staff member who edited the note.
+	///
+	/// To get a Discord-facing version of this more easily, use [Self::get_editor].
+	pub editor: i64,
+	/// When the edit was made
+	pub edit_time: DateTime<Utc>,
+	/// The text of the note before this edit was made
+	pub previous_body: String,
+}
+
+impl UserNoteEdit {
+	/// The staff member who edited the note.
+	///
+	/// For the raw database representation, use [Self::editor].
+	pub fn get_editor(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.editor))
+	}
+}
+
+/// What a guild does when a guild it trusts shares a ban or unban
+#[derive(Clone, Copy, DbEnum, Debug, Eq, PartialEq)]
+#[ExistingTypePath = "crate::schema::sql_types::BanSharePolicy"]
+pub enum BanSharePolicy {
+	/// Post an alert to the mod log channel for staff to act on
+	Alert,
+	/// Apply the ban or unban automatically
+	Ban,
+}
+
+impl fmt::Display for BanSharePolicy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Self::Alert => "Alert",
+			Self::Ban => "Ban",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The database representation of one guild's trust in another. Bans are only shared between two guilds when both
+/// trust each other.
+#[derive(Debug, Insertable, Queryable)]
+pub struct TrustedGuild {
+	/// The guild doing the trusting, which receives shared bans
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The guild that is trusted, whose bans are shared
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_trusted_guild].
+	pub trusted_guild: i64,
+	/// What the trusting guild does with shared bans
+	pub policy: BanSharePolicy,
+	/// The admin who added the trust
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_added_by].
+	pub added_by: i64,
+	/// When the trust was added
+	pub added_at: DateTime<Utc>,
+}
+
+impl TrustedGuild {
+	/// The guild doing the trusting
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The guild that is trusted
+	///
+	/// For the raw database representation, use [Self::trusted_guild].
+	pub fn get_trusted_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.trusted_guild))
+	}
+
+	/// The admin who added the trust
+	///
+	/// For the raw database representation, use [Self::added_by].
+	pub fn get_added_by(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.added_by))
+	}
+}
+
+/// The database representation of a ban or unban shared from one guild to a guild that trusts it
+#[derive(Debug, Insertable, Queryable)]
+pub struct SharedBan {
+	/// The ID of the shared record
+	pub id: String,
+	/// The ID of the ban action in the origin guild
+	pub origin_action: String,
+	/// The guild in which the ban or unban happened
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_origin_guild].
+	pub origin_guild: i64,
+	/// The guild the ban or unban was shared with
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_target_guild].
+	pub target_guild: i64,
+	/// The user who was banned or unbanned
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_banned_user].
+	pub banned_user: i64,
+	/// Whether a ban (rather than an unban) was shared
+	pub added: bool,
+	/// The reason given in the origin guild at the time it was shared
+	pub reason: String,
+	/// The policy the target guild applied
+	pub policy: BanSharePolicy,
+	/// The channel to which an alert was posted, if one was
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_alert_channel].
+	pub alert_channel: Option<i64>,
+	/// The alert message, if one was posted
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_alert_message].
+	pub alert_message: Option<i64>,
+	/// When the ban or unban was shared
+	pub shared_at: DateTime<Utc>,
+}
+
+impl SharedBan {
+	/// The guild in which the ban or unban happened
+	///
+	/// For the raw database representation, use [Self::origin_guild].
+	pub fn get_origin_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.origin_guild))
+	}
+
+	/// The guild the ban or unban was shared with
+	///
+	/// For the raw database representation, use [Self::target_guild].
+	pub fn get_target_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.target_guild))
+	}
+
+	/// The user who was banned or unbanned
+	///
+	/// For the raw database representation, use [Self::banned_user].
+	pub fn get_banned_user(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.banned_user))
+	}
+
+	/// The channel to which an alert was posted
+	///
+	/// For the raw database representation, use [Self::alert_channel].
+	pub fn get_alert_channel(&self) -> Option<Id<ChannelMarker>> {
+		self.alert_channel
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+
+	/// The alert message
+	///
+	/// For the raw database representation, use [Self::alert_message].
+	pub fn get_alert_message(&self) -> Option<Id<MessageMarker>> {
+		self.alert_message
+			.map(|database_id| Id::new(discord_id_from_database_id(database_id)))
+	}
+}
+
+/// The database representation of a member joining or leaving a guild
+#[derive(Debug, Insertable, Queryable)]
+pub struct MemberEvent {
+	/// The ID of the event
+	pub id: String,
+	/// The guild the member joined or left
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_guild].
+	pub guild: i64,
+	/// The user who joined or left
+	///
+	/// To get a Discord-facing representation of this more easily, use [Self::get_user_id].
+	pub user_id: i64,
+	/// Whether the user joined (rather than left)
+	pub joined: bool,
+	/// When the user joined or left
+	pub event_time: DateTime<Utc>,
+	/// When the user's account was created, taken from their user ID
+	pub account_created_at: DateTime<Utc>,
+}
+
+impl MemberEvent {
+	/// The guild the member joined or left
+	///
+	/// For the raw database representation, use [Self::guild].
+	pub fn get_guild(&self) -> Id<GuildMarker> {
+		Id::new(discord_id_from_database_id(self.guild))
+	}
+
+	/// The user who joined or left
+	///
+	/// For the raw database representation, use [Self::user_id].
+	pub fn get_user_id(&self) -> Id<UserMarker> {
+		Id::new(discord_id_from_database_id(self.user_id))
+	}
+}
+
 /// Converts an ID used with Discord (unsigned) to an ID for Postgres use (signed)
 pub fn database_id_from_discord_id(discord_id: u64) -> i64 {
 	discord_id as i64
 }
 
 /// Converts an ID retrieved from the database (signed) to an ID for use with Discord (unsigned)
-fn discord_id_from_database_id(database_id: i64) -> u64 {
+pub fn discord_id_from_database_id(database_id: i64) -> u64 {
 	database_id as u64
 }
@@ -5,9 +5,53 @@ pub mod sql_types {
 	#[diesel(postgres_type(name = "automod_action_type"))]
 	pub struct AutomodActionType;
 
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "ban_share_policy"))]
+	pub struct BanSharePolicy;
+
 	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
 	#[diesel(postgres_type(name = "built_in_ticket_category"))]
 	pub struct BuiltInTicketCategory;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "departed_ticket_user_policy"))]
+	pub struct DepartedTicketUserPolicy;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "escalation_action_type"))]
+	pub struct EscalationActionType;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "escalation_trigger_type"))]
+	pub struct EscalationTriggerType;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "moderation_action_type"))]
+	pub struct ModerationActionType;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "partner_invite_status"))]
+	pub struct PartnerInviteStatus;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "tracked_action_type"))]
+	pub struct TrackedActionType;
+
+	#[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+	#[diesel(postgres_type(name = "voice_action_type"))]
+	pub struct VoiceActionType;
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::ModerationActionType;
+
+	action_rules (action_type, action_id, rule) {
+		action_type -> ModerationActionType,
+		action_id -> Text,
+		rule -> Text,
+		action_time -> Timestamptz,
+	}
 }
 
 diesel::table! {
@@ -22,7 +66,14 @@ diesel::table! {
 		action_time -> Timestamptz,
 		reason -> Text,
 		rule_name -> Text,
-		message_content -> Text,
+		message_content -> Nullable<Text>,
+		mod_log_channel -> Nullable<Int8>,
+		mod_log_message -> Nullable<Int8>,
+		audit_log_entry -> Nullable<Int8>,
+		channel -> Nullable<Int8>,
+		matched_keyword -> Nullable<Text>,
+		matched_content -> Nullable<Text>,
+		alert_message -> Nullable<Int8>,
 	}
 }
 
@@ -35,6 +86,18 @@ diesel::table! {
 		added -> Bool,
 		action_time -> Timestamptz,
 		reason -> Text,
+		amended_reason -> Nullable<Text>,
+		reason_amended_by -> Nullable<Int8>,
+		reason_amended_at -> Nullable<Timestamptz>,
+		mod_log_channel -> Nullable<Int8>,
+		mod_log_message -> Nullable<Int8>,
+		audit_log_entry -> Nullable<Int8>,
+		evidence -> Nullable<Text>,
+		via_bot_command -> Bool,
+		escalation_rule -> Nullable<Text>,
+		expires_at -> Nullable<Timestamptz>,
+		original_ban -> Nullable<Text>,
+		shared_ban -> Nullable<Text>,
 	}
 }
 
@@ -49,6 +112,50 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	declined_partnerships (id) {
+		id -> Text,
+		guild -> Int8,
+		partner_guild -> Int8,
+		invite_code -> Text,
+		representative -> Int8,
+		ticket -> Text,
+		declined_by -> Int8,
+		declined_at -> Timestamptz,
+		reason -> Text,
+	}
+}
+
+diesel::table! {
+	escalation_applications (id) {
+		id -> Text,
+		rule -> Text,
+		guild -> Int8,
+		target_user -> Int8,
+		applied_at -> Timestamptz,
+		triggering_audit_log_entry -> Int8,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::EscalationTriggerType;
+	use super::sql_types::EscalationActionType;
+
+	escalation_rules (id) {
+		id -> Text,
+		guild -> Int8,
+		trigger_type -> EscalationTriggerType,
+		threshold -> Int4,
+		window_days -> Nullable<Int4>,
+		action_type -> EscalationActionType,
+		timeout_minutes -> Nullable<Int4>,
+		created_by -> Int8,
+		created_at -> Timestamptz,
+		active -> Bool,
+	}
+}
+
 diesel::table! {
 	form_questions (id) {
 		id -> Text,
@@ -67,6 +174,9 @@ diesel::table! {
 }
 
 diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::DepartedTicketUserPolicy;
+
 	guilds (guild_id) {
 		guild_id -> Int8,
 		start_ticket_channel -> Nullable<Int8>,
@@ -84,6 +194,16 @@ diesel::table! {
 		ban_appeal_ticket_form -> Nullable<Text>,
 		new_partner_ticket_form -> Nullable<Text>,
 		existing_partner_ticket_form -> Nullable<Text>,
+		mod_log_channel -> Nullable<Int8>,
+		last_audit_log_entry -> Nullable<Int8>,
+		warning_expiry_days -> Nullable<Int4>,
+		last_case_number -> Int4,
+		partner_invite_alert_channel -> Nullable<Int8>,
+		partner_list_channel -> Nullable<Int8>,
+		partner_list_message -> Nullable<Int8>,
+		member_alert_channel -> Nullable<Int8>,
+		new_account_age_days -> Nullable<Int4>,
+		departed_ticket_user_policy -> DepartedTicketUserPolicy,
 	}
 }
 
@@ -95,6 +215,116 @@ diesel::table! {
 		kicked_user -> Int8,
 		action_time -> Timestamptz,
 		reason -> Text,
+		amended_reason -> Nullable<Text>,
+		reason_amended_by -> Nullable<Int8>,
+		reason_amended_at -> Nullable<Timestamptz>,
+		mod_log_channel -> Nullable<Int8>,
+		mod_log_message -> Nullable<Int8>,
+		audit_log_entry -> Nullable<Int8>,
+		evidence -> Nullable<Text>,
+		via_bot_command -> Bool,
+		escalation_rule -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	member_events (id) {
+		id -> Text,
+		guild -> Int8,
+		user_id -> Int8,
+		joined -> Bool,
+		event_time -> Timestamptz,
+		account_created_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	message_delete_actions (id) {
+		id -> Text,
+		guild -> Int8,
+		performing_user -> Int8,
+		target_user -> Nullable<Int8>,
+		channel -> Int8,
+		message_count -> Int4,
+		bulk -> Bool,
+		action_time -> Timestamptz,
+		audit_log_entry -> Int8,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::ModerationActionType;
+
+	moderation_cases (guild, case_number) {
+		guild -> Int8,
+		case_number -> Int4,
+		action_type -> ModerationActionType,
+		action_id -> Text,
+	}
+}
+
+diesel::table! {
+	nickname_change_actions (id) {
+		id -> Text,
+		guild -> Int8,
+		performing_user -> Int8,
+		target_user -> Int8,
+		old_nickname -> Nullable<Text>,
+		new_nickname -> Nullable<Text>,
+		action_time -> Timestamptz,
+		reason -> Text,
+		audit_log_entry -> Int8,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::PartnerInviteStatus;
+
+	partner_invite_checks (id) {
+		id -> Text,
+		partnership -> Text,
+		checked_at -> Timestamptz,
+		status -> PartnerInviteStatus,
+		expires_at -> Nullable<Timestamptz>,
+		alert_message -> Nullable<Int8>,
+	}
+}
+
+diesel::table! {
+	partnerships (id) {
+		id -> Text,
+		guild -> Int8,
+		partner_guild -> Int8,
+		partner_name -> Text,
+		partner_icon -> Nullable<Text>,
+		member_count -> Int8,
+		invite_code -> Text,
+		representative -> Int8,
+		ticket -> Text,
+		approved_by -> Int8,
+		approved_at -> Timestamptz,
+		active -> Bool,
+		tcn_push_pending -> Bool,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::AutomodActionType;
+
+	pending_automod_executions (id) {
+		id -> Text,
+		guild -> Int8,
+		target_user -> Int8,
+		action_type -> AutomodActionType,
+		received_at -> Timestamptz,
+		channel -> Nullable<Int8>,
+		message_content -> Text,
+		matched_keyword -> Nullable<Text>,
+		matched_content -> Nullable<Text>,
+		alert_message -> Nullable<Int8>,
 	}
 }
 
@@ -108,6 +338,47 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	reason_policies (guild) {
+		guild -> Int8,
+		minimum_length -> Int4,
+		required_pattern -> Nullable<Text>,
+		exempt_bots -> Bool,
+	}
+}
+
+diesel::table! {
+	reason_policy_exempt_roles (guild, role) {
+		guild -> Int8,
+		role -> Int8,
+	}
+}
+
+diesel::table! {
+	role_change_actions (id) {
+		id -> Text,
+		guild -> Int8,
+		performing_user -> Int8,
+		target_user -> Int8,
+		role -> Int8,
+		added -> Bool,
+		action_time -> Timestamptz,
+		reason -> Text,
+		audit_log_entry -> Int8,
+	}
+}
+
+diesel::table! {
+	server_rules (id) {
+		id -> Text,
+		guild -> Int8,
+		rule_number -> Int4,
+		title -> Text,
+		description -> Text,
+		active -> Bool,
+	}
+}
+
 diesel::table! {
 	sessions (session_id) {
 		session_id -> Numeric,
@@ -116,6 +387,45 @@ diesel::table! {
 	}
 }
 
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::BanSharePolicy;
+
+	shared_bans (id) {
+		id -> Text,
+		origin_action -> Text,
+		origin_guild -> Int8,
+		target_guild -> Int8,
+		banned_user -> Int8,
+		added -> Bool,
+		reason -> Text,
+		policy -> BanSharePolicy,
+		alert_channel -> Nullable<Int8>,
+		alert_message -> Nullable<Int8>,
+		shared_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	tcn_bans (id) {
+		id -> Text,
+		user_id -> Int8,
+		origin_guild -> Int8,
+		reason -> Text,
+		banned_at -> Timestamptz,
+		synced_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	tcn_partners (guild_id) {
+		guild_id -> Int8,
+		name -> Text,
+		invite_code -> Text,
+		synced_at -> Timestamptz,
+	}
+}
+
 diesel::table! {
 	ticket_messages (id) {
 		id -> Text,
@@ -149,6 +459,7 @@ diesel::table! {
 		staff_thread -> Int8,
 		user_thread -> Int8,
 		closed_at -> Nullable<Timestamptz>,
+		partnership -> Nullable<Text>,
 	}
 }
 
@@ -161,34 +472,180 @@ diesel::table! {
 		action_time -> Timestamptz,
 		timeout_until -> Nullable<Timestamptz>,
 		reason -> Text,
+		amended_reason -> Nullable<Text>,
+		reason_amended_by -> Nullable<Int8>,
+		reason_amended_at -> Nullable<Timestamptz>,
+		mod_log_channel -> Nullable<Int8>,
+		mod_log_message -> Nullable<Int8>,
+		audit_log_entry -> Nullable<Int8>,
+		evidence -> Nullable<Text>,
+		via_bot_command -> Bool,
+		escalation_rule -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::BanSharePolicy;
+
+	trusted_guilds (guild, trusted_guild) {
+		guild -> Int8,
+		trusted_guild -> Int8,
+		policy -> BanSharePolicy,
+		added_by -> Int8,
+		added_at -> Timestamptz,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::TrackedActionType;
+
+	untracked_action_types (guild, action_type) {
+		guild -> Int8,
+		action_type -> TrackedActionType,
+	}
+}
+
+diesel::table! {
+	user_note_edits (id) {
+		id -> Text,
+		note -> Text,
+		editor -> Int8,
+		edit_time -> Timestamptz,
+		previous_body -> Text,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::ModerationActionType;
+
+	user_notes (id) {
+		id -> Text,
+		guild -> Int8,
+		target_user -> Int8,
+		author -> Int8,
+		created_at -> Timestamptz,
+		body -> Text,
+		action_type -> Nullable<ModerationActionType>,
+		action_id -> Nullable<Text>,
+		ticket -> Nullable<Text>,
+	}
+}
+
+diesel::table! {
+	use diesel::sql_types::*;
+	use super::sql_types::VoiceActionType;
+
+	voice_actions (id) {
+		id -> Text,
+		guild -> Int8,
+		performing_user -> Int8,
+		action_type -> VoiceActionType,
+		channel -> Nullable<Int8>,
+		member_count -> Int4,
+		action_time -> Timestamptz,
+		audit_log_entry -> Int8,
+	}
+}
+
+diesel::table! {
+	warn_actions (id) {
+		id -> Text,
+		guild -> Int8,
+		warning_user -> Int8,
+		warned_user -> Int8,
+		action_time -> Timestamptz,
+		reason -> Text,
+		expires_at -> Nullable<Timestamptz>,
+		user_notified -> Bool,
+		evidence -> Nullable<Text>,
+		amended_reason -> Nullable<Text>,
+		reason_amended_by -> Nullable<Int8>,
+		reason_amended_at -> Nullable<Timestamptz>,
+		mod_log_channel -> Nullable<Int8>,
+		mod_log_message -> Nullable<Int8>,
 	}
 }
 
+diesel::joinable!(action_rules -> server_rules (rule));
 diesel::joinable!(automod_actions -> guilds (guild));
+diesel::joinable!(ban_actions -> escalation_rules (escalation_rule));
 diesel::joinable!(ban_actions -> guilds (guild));
 diesel::joinable!(custom_categories -> forms (form));
 diesel::joinable!(custom_categories -> guilds (guild));
+diesel::joinable!(declined_partnerships -> guilds (guild));
+diesel::joinable!(declined_partnerships -> tickets (ticket));
+diesel::joinable!(escalation_applications -> escalation_rules (rule));
+diesel::joinable!(escalation_applications -> guilds (guild));
+diesel::joinable!(escalation_rules -> guilds (guild));
 diesel::joinable!(form_questions -> forms (form));
+diesel::joinable!(kick_actions -> escalation_rules (escalation_rule));
 diesel::joinable!(kick_actions -> guilds (guild));
+diesel::joinable!(member_events -> guilds (guild));
+diesel::joinable!(message_delete_actions -> guilds (guild));
+diesel::joinable!(moderation_cases -> guilds (guild));
+diesel::joinable!(nickname_change_actions -> guilds (guild));
+diesel::joinable!(partner_invite_checks -> partnerships (partnership));
+diesel::joinable!(partnerships -> guilds (guild));
+diesel::joinable!(partnerships -> tickets (ticket));
+diesel::joinable!(pending_automod_executions -> guilds (guild));
 diesel::joinable!(pending_partnerships -> guilds (guild));
 diesel::joinable!(pending_partnerships -> tickets (ticket));
+diesel::joinable!(reason_policies -> guilds (guild));
+diesel::joinable!(reason_policy_exempt_roles -> guilds (guild));
+diesel::joinable!(role_change_actions -> guilds (guild));
+diesel::joinable!(server_rules -> guilds (guild));
+diesel::joinable!(shared_bans -> ban_actions (origin_action));
 diesel::joinable!(ticket_messages -> tickets (ticket));
 diesel::joinable!(tickets -> custom_categories (custom_category));
 diesel::joinable!(tickets -> guilds (guild));
+diesel::joinable!(timeout_actions -> escalation_rules (escalation_rule));
 diesel::joinable!(timeout_actions -> guilds (guild));
+diesel::joinable!(untracked_action_types -> guilds (guild));
+diesel::joinable!(user_note_edits -> user_notes (note));
+diesel::joinable!(user_notes -> guilds (guild));
+diesel::joinable!(user_notes -> tickets (ticket));
+diesel::joinable!(voice_actions -> guilds (guild));
+diesel::joinable!(warn_actions -> guilds (guild));
 
 diesel::allow_tables_to_appear_in_same_query!(
+	action_rules,
 	automod_actions,
 	ban_actions,
 	custom_categories,
+	declined_partnerships,
+	escalation_applications,
+	escalation_rules,
 	form_questions,
 	forms,
 	guilds,
 	kick_actions,
+	member_events,
+	message_delete_actions,
+	moderation_cases,
+	nickname_change_actions,
+	partner_invite_checks,
+	partnerships,
+	pending_automod_executions,
 	pending_partnerships,
+	reason_policies,
+	reason_policy_exempt_roles,
+	role_change_actions,
+	server_rules,
 	sessions,
+	shared_bans,
+	tcn_bans,
+	tcn_partners,
 	ticket_messages,
 	ticket_restricted_users,
 	tickets,
 	timeout_actions,
+	trusted_guilds,
+	untracked_action_types,
+	user_note_edits,
+	user_notes,
+	voice_actions,
+	warn_actions,
 );
@@ -7,9 +7,13 @@
 use super::errors::error::Error;
 use super::errors::not_found::NotFound;
 use super::guild::dashboard::Dashboard;
+use super::guild::partners::Partners;
 use super::guild::ticket::TicketPage;
 use super::header::PageHeader;
-use super::staff::{FormEditor, ManageForms, OpenTickets};
+use super::staff::{
+	CasePage, FormEditor, ManageForms, ModerationActions, ModeratorStats, OpenTickets, PartnershipPage, Partnerships,
+	ServerRules, TemporaryBans, UserNotes, UserProfile,
+};
 use super::utils::{GuildParam, get_guild_data};
 use leptos::prelude::*;
 use leptos_meta::{Stylesheet, Title, provide_meta_context};
@@ -30,9 +34,19 @@ pub fn App() -> impl IntoView {
 			<Routes fallback=|| view! { NotFound }>
 				<ParentRoute path=path!("/:guild?") view=MainPage>
 					<Route path=path!("/ticket/:ticket") view=TicketPage />
+					<Route path=path!("/partners") view=Partners />
 					<Route path=path!("/staff/open_tickets") view=OpenTickets />
 					<Route path=path!("/staff/manage_forms") view=ManageForms />
 					<Route path=path!("/staff/edit_form/:form_id?") view=FormEditor />
+					<Route path=path!("/staff/user_notes/:user_id") view=UserNotes />
+					<Route path=path!("/staff/user/:user_id") view=UserProfile />
+					<Route path=path!("/staff/temporary_bans") view=TemporaryBans />
+					<Route path=path!("/staff/case/:case_number") view=CasePage />
+					<Route path=path!("/staff/rules") view=ServerRules />
+					<Route path=path!("/staff/actions") view=ModerationActions />
+					<Route path=path!("/staff/moderator_stats") view=ModeratorStats />
+					<Route path=path!("/staff/partnerships") view=Partnerships />
+					<Route path=path!("/staff/partnership/:partnership_id") view=PartnershipPage />
 					<Route path=path!("/") view=Dashboard />
 				</ParentRoute>
 			</Routes>
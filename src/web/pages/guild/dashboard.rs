@@ -130,10 +130,37 @@ pub fn Dashboard() -> impl IntoView {
 									"Form Manager"
 								</a>
 							</li>
+							<li>
+								<a href={make_temporary_bans_url(guild_id)}>
+									"Upcoming Unbans"
+								</a>
+							</li>
+							<li>
+								<a href={make_server_rules_url(guild_id)}>
+									"Server Rules"
+								</a>
+							</li>
+							<li>
+								<a href={make_moderation_actions_url(guild_id)}>
+									"Moderation Actions"
+								</a>
+							</li>
+							<li>
+								<a href={make_partnerships_url(guild_id)}>
+									"Partnerships"
+								</a>
+							</li>
 						</ul>
 
 						<Show when=move || permission_level() == PermissionLevel::Admin>
 							<h2>Admin Menu</h2>
+							<ul>
+								<li>
+									<a href={make_moderator_stats_url(guild_id)}>
+										"Moderator Activity"
+									</a>
+								</li>
+							</ul>
 						</Show>
 					</div>
 				</Show>
@@ -201,6 +228,46 @@ fn make_form_manager_url(guild_id: Option<u64>) -> String {
 	}
 }
 
+/// Makes a URL to the list of temporary bans for staff
+fn make_temporary_bans_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/temporary_bans", id),
+		None => String::from("/staff/temporary_bans"),
+	}
+}
+
+/// Makes a URL to the server rules page for staff
+fn make_server_rules_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/rules", id),
+		None => String::from("/staff/rules"),
+	}
+}
+
+/// Makes a URL to the moderation action browser for staff
+fn make_moderation_actions_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/actions", id),
+		None => String::from("/staff/actions"),
+	}
+}
+
+/// Makes a URL to the staff list of partnerships
+fn make_partnerships_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/partnerships", id),
+		None => String::from("/staff/partnerships"),
+	}
+}
+
+/// Makes a URL to the moderator activity statistics for admins
+fn make_moderator_stats_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/moderator_stats", id),
+		None => String::from("/staff/moderator_stats"),
+	}
+}
+
 /// Information about active tickets for showing on the dashboard
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ActiveTicketMetadata {
@@ -6,4 +6,5 @@
 
 pub mod dashboard;
 mod not_found;
+pub mod partners;
 pub mod ticket;
@@ -0,0 +1,90 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::GuildParam;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn Partners() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let partners = OnceResource::new(get_partners(guild_id));
+
+	view! {
+		<h1>"Our Partners"</h1>
+		<Transition fallback=|| view! { <div class="partner_directory_loading">"Loading partners..."</div> }>
+			{
+				move || match partners.read().as_ref().and_then(|partners| partners.as_ref().ok()) {
+					Some(partner_data) if !partner_data.is_empty() => view! {
+						<ul class="partner_directory">
+							{
+								partner_data.iter().map(|partner| view! {
+									<li>
+										{
+											partner.icon_url.clone().map(|icon_url| view! {
+												<img class="partner_directory_icon" src={icon_url} />
+											})
+										}
+										<span class="partner_directory_name">{partner.name.clone()}</span>
+										<a href={partner.invite_url.clone()}>"Join"</a>
+									</li>
+								}).collect::<Vec<_>>()
+							}
+						</ul>
+					}.into_any(),
+					_ => view! {
+						<div class="partner_directory_empty">"This server doesn't have any partners right now."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartnerData {
+	pub name: String,
+	pub icon_url: Option<String>,
+	pub invite_url: String,
+}
+
+#[server]
+async fn get_partners(guild_id: Option<u64>) -> Result<Vec<PartnerData>, ServerFnError> {
+	use crate::discord::utils::partner_list::active_partnerships;
+	use crate::web::pages::server_utils::get_guild_data_from_request;
+	use crate::web::state::AppState;
+
+	let Some(guild) = get_guild_data_from_request(guild_id).await? else {
+		return Ok(Vec::new());
+	};
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let partners = active_partnerships(&guild, &mut db_connection)?;
+	let partners = partners
+		.into_iter()
+		.map(|partnership| {
+			let icon_url = partnership.partner_icon.as_ref().map(|icon_hash| {
+				format!(
+					"https://cdn.discordapp.com/icons/{}/{}.png",
+					partnership.get_partner_guild(),
+					icon_hash
+				)
+			});
+			PartnerData {
+				name: partnership.partner_name,
+				icon_url,
+				invite_url: format!("https://discord.gg/{}", partnership.invite_code),
+			}
+		})
+		.collect();
+
+	Ok(partners)
+}
@@ -6,12 +6,15 @@
 
 use crate::model::{Guild, database_id_from_discord_id};
 use crate::schema::guilds;
+use crate::web::permissions::PermissionLevel;
 use crate::web::session_key::DISCORD_USER;
 use crate::web::state::AppState;
 use axum_extra::extract::Host;
 use diesel::prelude::*;
 use leptos::prelude::*;
 use leptos_axum::extract_with_state;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use tower_sessions::session::Session;
 use twilight_model::id::Id;
 use twilight_model::id::marker::{GuildMarker, UserMarker};
@@ -74,3 +77,53 @@ pub async fn get_user_id_from_request() -> Result<Option<Id<UserMarker>>, Server
 	let user_id: Option<Id<UserMarker>> = session.get(DISCORD_USER).await?;
 	Ok(user_id)
 }
+
+/// Gets the guild for a request along with the permission level the requesting user has in that guild.
+/// Must be used from a server function; relies on extracting request data.
+pub async fn get_guild_and_permission_level_from_request(
+	client_guild_id: Option<u64>,
+) -> Result<(Guild, PermissionLevel), ServerFnError> {
+	let guild = get_guild_data_from_request(client_guild_id).await?;
+	let user_id = get_user_id_from_request().await?;
+
+	let (Some(guild), Some(user_id)) = (guild, user_id) else {
+		return Err(ServerFnError::ServerError(String::from(
+			"No guild found and/or user not logged in",
+		)));
+	};
+
+	let state: AppState = expect_context();
+	let member = state
+		.discord_client
+		.guild_member(guild.get_guild_id(), user_id)
+		.await?
+		.model()
+		.await?;
+
+	let permission_level = if member.roles.contains(&guild.get_admin_role()) {
+		PermissionLevel::Admin
+	} else if member.roles.contains(&guild.get_staff_role()) {
+		PermissionLevel::Staff
+	} else {
+		PermissionLevel::Member
+	};
+
+	Ok((guild, permission_level))
+}
+
+/// Gets a user's name, looking it up from Discord only if it isn't already in the cache.
+/// Must be used from a server function.
+pub async fn get_user_name(
+	user_id: Id<UserMarker>,
+	state: &AppState,
+	usernames_cache: &mut HashMap<Id<UserMarker>, String>,
+) -> Result<String, ServerFnError> {
+	match usernames_cache.entry(user_id) {
+		Entry::Occupied(entry) => Ok(entry.get().clone()),
+		Entry::Vacant(entry) => {
+			let user = state.discord_client.user(user_id).await?.model().await?;
+			entry.insert(user.name.clone());
+			Ok(user.name)
+		}
+	}
+}
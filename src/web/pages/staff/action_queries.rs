@@ -0,0 +1,537 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::moderation_actions::{ActionData, ActionFilter, ActionKind};
+use crate::model::{AutomodAction, BanAction, KickAction, TimeoutAction, WarnAction, database_id_from_discord_id};
+use crate::schema::{
+	action_rules, automod_actions, ban_actions, kick_actions, moderation_cases, server_rules, timeout_actions,
+	warn_actions,
+};
+use crate::web::pages::server_utils::get_user_name;
+use crate::web::state::AppState;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use diesel::prelude::*;
+use leptos::prelude::ServerFnError;
+use std::collections::HashMap;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+/// How many actions are shown on each page of the action browser
+pub const ACTIONS_PAGE_SIZE: usize = 25;
+
+/// The most actions that can be exported at once
+pub const MAX_EXPORTED_ACTIONS: i64 = 5000;
+
+/// A moderation action that matched a filter, before anything is looked up for display
+pub struct MatchedAction {
+	id: String,
+	kind: ActionKind,
+	title: &'static str,
+	action_time: DateTime<Utc>,
+	moderator: Option<Id<UserMarker>>,
+	target: Id<UserMarker>,
+	reason: String,
+	expires_at: Option<DateTime<Utc>>,
+}
+
+/// An action filter, converted to the values used in the database
+struct DatabaseFilter {
+	guild: i64,
+	kind: Option<ActionKind>,
+	moderator: Option<i64>,
+	target: Option<i64>,
+	since: Option<DateTime<Utc>>,
+	until: Option<DateTime<Utc>>,
+	/// When filtering by rule, the IDs of every action linked to that rule
+	rule_action_ids: Option<Vec<String>>,
+	missing_reason_only: bool,
+}
+
+impl DatabaseFilter {
+	fn includes(&self, kind: ActionKind) -> bool {
+		self.kind.is_none_or(|filter_kind| filter_kind == kind)
+	}
+}
+
+fn ban_query(filter: &DatabaseFilter) -> ban_actions::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = ban_actions::table
+		.filter(ban_actions::guild.eq(filter.guild))
+		.into_boxed();
+	match filter.kind {
+		Some(ActionKind::Ban) => query = query.filter(ban_actions::added.eq(true)),
+		Some(ActionKind::Unban) => query = query.filter(ban_actions::added.eq(false)),
+		_ => (),
+	}
+	if let Some(moderator) = filter.moderator {
+		query = query.filter(ban_actions::banning_user.eq(moderator));
+	}
+	if let Some(target) = filter.target {
+		query = query.filter(ban_actions::banned_user.eq(target));
+	}
+	if let Some(since) = filter.since {
+		query = query.filter(ban_actions::action_time.ge(since));
+	}
+	if let Some(until) = filter.until {
+		query = query.filter(ban_actions::action_time.lt(until));
+	}
+	if let Some(action_ids) = &filter.rule_action_ids {
+		query = query.filter(ban_actions::id.eq_any(action_ids));
+	}
+	if filter.missing_reason_only {
+		// Unbans aren't expected to have a reason, so they're never counted as missing one
+		query = query.filter(
+			ban_actions::added
+				.eq(true)
+				.and(ban_actions::reason.eq(""))
+				.and(ban_actions::amended_reason.is_null()),
+		);
+	}
+	query
+}
+
+fn kick_query(filter: &DatabaseFilter) -> kick_actions::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = kick_actions::table
+		.filter(kick_actions::guild.eq(filter.guild))
+		.into_boxed();
+	if let Some(moderator) = filter.moderator {
+		query = query.filter(kick_actions::kicking_user.eq(moderator));
+	}
+	if let Some(target) = filter.target {
+		query = query.filter(kick_actions::kicked_user.eq(target));
+	}
+	if let Some(since) = filter.since {
+		query = query.filter(kick_actions::action_time.ge(since));
+	}
+	if let Some(until) = filter.until {
+		query = query.filter(kick_actions::action_time.lt(until));
+	}
+	if let Some(action_ids) = &filter.rule_action_ids {
+		query = query.filter(kick_actions::id.eq_any(action_ids));
+	}
+	if filter.missing_reason_only {
+		query = query.filter(kick_actions::reason.eq("").and(kick_actions::amended_reason.is_null()));
+	}
+	query
+}
+
+fn timeout_query(filter: &DatabaseFilter) -> timeout_actions::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = timeout_actions::table
+		.filter(timeout_actions::guild.eq(filter.guild))
+		.into_boxed();
+	if let Some(moderator) = filter.moderator {
+		query = query.filter(timeout_actions::performing_user.eq(moderator));
+	}
+	if let Some(target) = filter.target {
+		query = query.filter(timeout_actions::target_user.eq(target));
+	}
+	if let Some(since) = filter.since {
+		query = query.filter(timeout_actions::action_time.ge(since));
+	}
+	if let Some(until) = filter.until {
+		query = query.filter(timeout_actions::action_time.lt(until));
+	}
+	if let Some(action_ids) = &filter.rule_action_ids {
+		query = query.filter(timeout_actions::id.eq_any(action_ids));
+	}
+	if filter.missing_reason_only {
+		query = query.filter(
+			timeout_actions::reason
+				.eq("")
+				.and(timeout_actions::amended_reason.is_null()),
+		);
+	}
+	query
+}
+
+fn warn_query(filter: &DatabaseFilter) -> warn_actions::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = warn_actions::table
+		.filter(warn_actions::guild.eq(filter.guild))
+		.into_boxed();
+	if let Some(moderator) = filter.moderator {
+		query = query.filter(warn_actions::warning_user.eq(moderator));
+	}
+	if let Some(target) = filter.target {
+		query = query.filter(warn_actions::warned_user.eq(target));
+	}
+	if let Some(since) = filter.since {
+		query = query.filter(warn_actions::action_time.ge(since));
+	}
+	if let Some(until) = filter.until {
+		query = query.filter(warn_actions::action_time.lt(until));
+	}
+	if let Some(action_ids) = &filter.rule_action_ids {
+		query = query.filter(warn_actions::id.eq_any(action_ids));
+	}
+	if filter.missing_reason_only {
+		query = query.filter(warn_actions::reason.eq("").and(warn_actions::amended_reason.is_null()));
+	}
+	query
+}
+
+fn automod_query(filter: &DatabaseFilter) -> automod_actions::BoxedQuery<'_, diesel::pg::Pg> {
+	let mut query = automod_actions::table
+		.filter(automod_actions::guild.eq(filter.guild))
+		.into_boxed();
+	if let Some(target) = filter.target {
+		query = query.filter(automod_actions::target_user.eq(target));
+	}
+	if let Some(since) = filter.since {
+		query = query.filter(automod_actions::action_time.ge(since));
+	}
+	if let Some(until) = filter.until {
+		query = query.filter(automod_actions::action_time.lt(until));
+	}
+	if let Some(action_ids) = &filter.rule_action_ids {
+		query = query.filter(automod_actions::id.eq_any(action_ids));
+	}
+	if filter.missing_reason_only {
+		query = query.filter(automod_actions::reason.eq(""));
+	}
+	query
+}
+
+/// Finds the actions in the guild matching the filter, newest first.
+///
+/// When a limit is given, at most that many actions are loaded. Also returns how many actions match the filter in
+/// total.
+pub fn find_actions(
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	filter: &ActionFilter,
+	limit: Option<i64>,
+) -> QueryResult<(Vec<MatchedAction>, i64)> {
+	let rule_action_ids: Option<Vec<String>> = match filter.rule_number {
+		Some(rule_number) => {
+			// Rule numbers can be reused after a rule is removed, so every rule that had the number counts
+			let rule_ids: Vec<String> = server_rules::table
+				.filter(
+					server_rules::guild
+						.eq(db_guild_id)
+						.and(server_rules::rule_number.eq(rule_number)),
+				)
+				.select(server_rules::id)
+				.load(db_connection)?;
+			let action_ids: Vec<String> = action_rules::table
+				.filter(action_rules::rule.eq_any(&rule_ids))
+				.select(action_rules::action_id)
+				.load(db_connection)?;
+			Some(action_ids)
+		}
+		None => None,
+	};
+	let day_start = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc();
+	let db_filter = DatabaseFilter {
+		guild: db_guild_id,
+		kind: filter.action_kind,
+		moderator: filter.moderator.map(database_id_from_discord_id),
+		target: filter.target.map(database_id_from_discord_id),
+		since: filter.from_date.map(day_start),
+		// The end date is included in the range, so the range ends at the start of the following day
+		until: filter.to_date.map(|date| day_start(date) + TimeDelta::days(1)),
+		rule_action_ids,
+		missing_reason_only: filter.missing_reason_only,
+	};
+
+	let mut actions: Vec<MatchedAction> = Vec::new();
+	let mut total_count: i64 = 0;
+
+	if db_filter.includes(ActionKind::Ban) || db_filter.includes(ActionKind::Unban) {
+		total_count += ban_query(&db_filter).count().get_result::<i64>(db_connection)?;
+		let mut query = ban_query(&db_filter).order(ban_actions::action_time.desc());
+		if let Some(limit) = limit {
+			query = query.limit(limit);
+		}
+		let bans: Vec<BanAction> = query.load(db_connection)?;
+		for action in bans {
+			let (kind, title) = if action.added {
+				(ActionKind::Ban, "Ban")
+			} else {
+				(ActionKind::Unban, "Unban")
+			};
+			actions.push(MatchedAction {
+				kind,
+				title,
+				action_time: action.action_time,
+				moderator: Some(action.get_banning_user()),
+				target: action.get_banned_user(),
+				reason: action.current_reason().to_string(),
+				expires_at: action.expires_at,
+				id: action.id,
+			});
+		}
+	}
+
+	if db_filter.includes(ActionKind::Kick) {
+		total_count += kick_query(&db_filter).count().get_result::<i64>(db_connection)?;
+		let mut query = kick_query(&db_filter).order(kick_actions::action_time.desc());
+		if let Some(limit) = limit {
+			query = query.limit(limit);
+		}
+		let kicks: Vec<KickAction> = query.load(db_connection)?;
+		for action in kicks {
+			actions.push(MatchedAction {
+				kind: ActionKind::Kick,
+				title: "Kick",
+				action_time: action.action_time,
+				moderator: Some(action.get_kicking_user()),
+				target: action.get_kicked_user(),
+				reason: action.current_reason().to_string(),
+				expires_at: None,
+				id: action.id,
+			});
+		}
+	}
+
+	if db_filter.includes(ActionKind::Timeout) {
+		total_count += timeout_query(&db_filter).count().get_result::<i64>(db_connection)?;
+		let mut query = timeout_query(&db_filter).order(timeout_actions::action_time.desc());
+		if let Some(limit) = limit {
+			query = query.limit(limit);
+		}
+		let timeouts: Vec<TimeoutAction> = query.load(db_connection)?;
+		for action in timeouts {
+			let title = if action.timeout_until.is_some() {
+				"Timeout"
+			} else {
+				"Timeout Removed"
+			};
+			actions.push(MatchedAction {
+				kind: ActionKind::Timeout,
+				title,
+				action_time: action.action_time,
+				moderator: Some(action.get_performing_user()),
+				target: action.get_target_user(),
+				reason: action.current_reason().to_string(),
+				expires_at: action.timeout_until,
+				id: action.id,
+			});
+		}
+	}
+
+	if db_filter.includes(ActionKind::Warning) {
+		total_count += warn_query(&db_filter).count().get_result::<i64>(db_connection)?;
+		let mut query = warn_query(&db_filter).order(warn_actions::action_time.desc());
+		if let Some(limit) = limit {
+			query = query.limit(limit);
+		}
+		let warnings: Vec<WarnAction> = query.load(db_connection)?;
+		for action in warnings {
+			actions.push(MatchedAction {
+				kind: ActionKind::Warning,
+				title: "Warning",
+				action_time: action.action_time,
+				moderator: Some(action.get_warning_user()),
+				target: action.get_warned_user(),
+				reason: action.current_reason().to_string(),
+				expires_at: action.expires_at,
+				id: action.id,
+			});
+		}
+	}
+
+	// AutoMod actions don't have a moderator, so they can't match a moderator filter
+	if db_filter.includes(ActionKind::Automod) && db_filter.moderator.is_none() {
+		total_count += automod_query(&db_filter).count().get_result::<i64>(db_connection)?;
+		let mut query = automod_query(&db_filter).order(automod_actions::action_time.desc());
+		if let Some(limit) = limit {
+			query = query.limit(limit);
+		}
+		let automod: Vec<AutomodAction> = query.load(db_connection)?;
+		for action in automod {
+			let title = action.action_type.title();
+			actions.push(MatchedAction {
+				kind: ActionKind::Automod,
+				title,
+				action_time: action.action_time,
+				moderator: None,
+				target: action.get_target_user(),
+				reason: action.reason,
+				expires_at: None,
+				id: action.id,
+			});
+		}
+	}
+
+	actions.sort_by(|a, b| b.action_time.cmp(&a.action_time));
+	if let Some(limit) = limit.and_then(|limit| usize::try_from(limit).ok()) {
+		actions.truncate(limit);
+	}
+
+	Ok((actions, total_count))
+}
+
+/// Looks up the case numbers, rules, and user names for actions so they can be shown or exported
+pub async fn describe_actions(
+	state: &AppState,
+	db_connection: &mut PgConnection,
+	db_guild_id: i64,
+	actions: Vec<MatchedAction>,
+) -> Result<Vec<ActionData>, ServerFnError> {
+	let action_ids: Vec<&str> = actions.iter().map(|action| action.id.as_str()).collect();
+	let case_numbers: HashMap<String, i32> = moderation_cases::table
+		.filter(
+			moderation_cases::guild
+				.eq(db_guild_id)
+				.and(moderation_cases::action_id.eq_any(&action_ids)),
+		)
+		.select((moderation_cases::action_id, moderation_cases::case_number))
+		.load::<(String, i32)>(db_connection)?
+		.into_iter()
+		.collect();
+	let action_rule_numbers: Vec<(String, i32)> = action_rules::table
+		.inner_join(server_rules::table)
+		.filter(action_rules::action_id.eq_any(&action_ids))
+		.order(server_rules::rule_number.asc())
+		.select((action_rules::action_id, server_rules::rule_number))
+		.load(db_connection)?;
+	let mut rules_by_action: HashMap<String, Vec<i32>> = HashMap::new();
+	for (action_id, rule_number) in action_rule_numbers {
+		rules_by_action.entry(action_id).or_default().push(rule_number);
+	}
+
+	let mut usernames: HashMap<Id<UserMarker>, String> = HashMap::new();
+	let mut action_data: Vec<ActionData> = Vec::with_capacity(actions.len());
+	for action in actions {
+		let moderator_name = match action.moderator {
+			Some(moderator) => Some(user_name_or_id(moderator, state, &mut usernames).await),
+			None => None,
+		};
+		let target_name = user_name_or_id(action.target, state, &mut usernames).await;
+
+		action_data.push(ActionData {
+			case_number: case_numbers.get(&action.id).copied(),
+			rules: rules_by_action.remove(&action.id).unwrap_or_default(),
+			id: action.id,
+			kind: action.kind,
+			title: String::from(action.title),
+			action_time: action.action_time,
+			moderator_id: action.moderator.map(|moderator| moderator.get()),
+			moderator_name,
+			target_id: action.target.get(),
+			target_name,
+			reason: action.reason,
+			expires_at: action.expires_at,
+		});
+	}
+
+	Ok(action_data)
+}
+
+/// Gets a user's name, falling back to the user ID if the user can't be looked up
+async fn user_name_or_id(
+	user_id: Id<UserMarker>,
+	state: &AppState,
+	usernames_cache: &mut HashMap<Id<UserMarker>, String>,
+) -> String {
+	match get_user_name(user_id, state, usernames_cache).await {
+		Ok(name) => name,
+		Err(error) => {
+			tracing::warn!(source = ?error, user = %user_id, "Failed to look up a user's name for moderation actions");
+			let name = user_id.to_string();
+			usernames_cache.insert(user_id, name.clone());
+			name
+		}
+	}
+}
+
+/// Writes actions out as CSV, with a header row
+pub fn actions_csv(actions: &[ActionData]) -> String {
+	fn csv_field(value: &str) -> String {
+		if value.contains([',', '"', '\n', '\r']) {
+			format!("\"{}\"", value.replace('"', "\"\""))
+		} else {
+			value.to_string()
+		}
+	}
+
+	let mut lines = vec![String::from(
+		"case,action_id,type,time,moderator_id,moderator,user_id,user,reason,rules,expires_at",
+	)];
+	for action in actions.iter() {
+		let rules: Vec<String> = action.rules.iter().map(|rule| rule.to_string()).collect();
+		let fields = [
+			action.case_number.map(|case| case.to_string()).unwrap_or_default(),
+			action.id.clone(),
+			action.title.clone(),
+			action.action_time.to_rfc3339(),
+			action.moderator_id.map(|id| id.to_string()).unwrap_or_default(),
+			action.moderator_name.clone().unwrap_or_else(|| String::from("AutoMod")),
+			action.target_id.to_string(),
+			action.target_name.clone(),
+			action.reason.clone(),
+			rules.join(";"),
+			action
+				.expires_at
+				.map(|expires_at| expires_at.to_rfc3339())
+				.unwrap_or_default(),
+		];
+		let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+		lines.push(fields.join(","));
+	}
+	let mut csv = lines.join("\r\n");
+	csv.push_str("\r\n");
+	csv
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	fn action(reason: &str) -> ActionData {
+		ActionData {
+			id: String::from("action"),
+			kind: ActionKind::Ban,
+			title: String::from("Ban"),
+			case_number: Some(12),
+			action_time: Utc.with_ymd_and_hms(2026, 10, 18, 12, 30, 0).unwrap(),
+			moderator_id: Some(1),
+			moderator_name: Some(String::from("moderator")),
+			target_id: 2,
+			target_name: String::from("target"),
+			reason: String::from(reason),
+			expires_at: None,
+			rules: vec![4, 7],
+		}
+	}
+
+	#[test]
+	fn header_only_without_actions() {
+		assert_eq!(
+			actions_csv(&[]),
+			"case,action_id,type,time,moderator_id,moderator,user_id,user,reason,rules,expires_at\r\n"
+		);
+	}
+
+	#[test]
+	fn one_row_per_action() {
+		let csv = actions_csv(&[action("spam")]);
+		let rows: Vec<&str> = csv.split("\r\n").collect();
+		assert_eq!(rows.len(), 3);
+		assert_eq!(
+			rows[1],
+			"12,action,Ban,2026-10-18T12:30:00+00:00,1,moderator,2,target,spam,4;7,"
+		);
+		assert_eq!(rows[2], "");
+	}
+
+	#[test]
+	fn automod_actions_have_no_moderator_id() {
+		let mut automod_action = action("spam");
+		automod_action.case_number = None;
+		automod_action.moderator_id = None;
+		automod_action.moderator_name = None;
+		automod_action.rules = Vec::new();
+		let csv = actions_csv(&[automod_action]);
+		assert!(csv.contains("\r\n,action,Ban,2026-10-18T12:30:00+00:00,,AutoMod,2,target,spam,,\r\n"));
+	}
+
+	#[test]
+	fn fields_with_special_characters_are_quoted() {
+		let csv = actions_csv(&[action("said \"hi\", then\nleft")]);
+		assert!(csv.contains(",\"said \"\"hi\"\", then\nleft\","));
+	}
+}
@@ -0,0 +1,184 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{CaseParams, make_user_profile_url};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn CasePage() -> impl IntoView {
+	let params = use_params::<CaseParams>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+	let case_number = params.read().as_ref().ok().and_then(|params| params.case_number);
+
+	let case = OnceResource::new(get_case(guild_id, case_number));
+
+	view! {
+		<Transition fallback=|| view! { <div class="case_loading">"Loading case..."</div> }>
+			{
+				move || match case.read().as_ref().and_then(|case| case.as_ref().ok()).cloned().flatten() {
+					Some(case_data) => view! {
+						<h1>"Case " {case_data.case_number} ": " {case_data.title}</h1>
+						<table class="case_details">
+							<tbody>
+								<tr>
+									<th>"User"</th>
+									<td>
+										<a href={make_user_profile_url(guild_id, case_data.user_id)}>
+											{case_data.user_name}
+										</a>
+									</td>
+								</tr>
+								<tr>
+									<th>"Moderator"</th>
+									<td>{case_data.moderator_name}</td>
+								</tr>
+								<tr>
+									<th>"Time"</th>
+									<td>{case_data.action_time.to_rfc3339()}</td>
+								</tr>
+								{
+									case_data.expires_at.map(|expires_at| view! {
+										<tr>
+											<th>"Expires"</th>
+											<td>{expires_at.to_rfc3339()}</td>
+										</tr>
+									})
+								}
+								<tr>
+									<th>"Reason"</th>
+									<td class="case_reason">{case_data.reason}</td>
+								</tr>
+								{
+									case_data.original_reason.map(|original_reason| view! {
+										<tr>
+											<th>"Original Reason"</th>
+											<td class="case_reason">{original_reason}</td>
+										</tr>
+									})
+								}
+								{
+									(!case_data.rules.is_empty()).then(|| view! {
+										<tr>
+											<th>"Rules Broken"</th>
+											<td>
+												<ul class="case_rules">
+													{
+														case_data.rules.into_iter().map(|rule| view! {
+															<li>"Rule " {rule.0} ": " {rule.1}</li>
+														}).collect::<Vec<_>>()
+													}
+												</ul>
+											</td>
+										</tr>
+									})
+								}
+								{
+									case_data.evidence.map(|evidence| view! {
+										<tr>
+											<th>"Evidence"</th>
+											<td class="case_reason">{evidence}</td>
+										</tr>
+									})
+								}
+								{
+									case_data.mod_log_link.map(|link| view! {
+										<tr>
+											<th>"Mod Log"</th>
+											<td><a href={link}>"View in Discord"</a></td>
+										</tr>
+									})
+								}
+							</tbody>
+						</table>
+					}.into_any(),
+					None => view! {
+						<div class="case_error">"This case couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CaseData {
+	pub case_number: i32,
+	pub title: String,
+	pub user_id: u64,
+	pub user_name: String,
+	pub moderator_name: String,
+	pub action_time: DateTime<Utc>,
+	pub expires_at: Option<DateTime<Utc>>,
+	pub reason: String,
+	pub original_reason: Option<String>,
+	pub evidence: Option<String>,
+	/// The number and title of each rule the action was taken for breaking
+	pub rules: Vec<(i32, String)>,
+	pub mod_log_link: Option<String>,
+}
+
+#[server]
+async fn get_case(guild_id: Option<u64>, case_number: Option<i32>) -> Result<Option<CaseData>, ServerFnError> {
+	use crate::discord::utils::cases::find_case;
+	use crate::discord::utils::rules::rules_for_action;
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+
+	let Some(case_number) = case_number else {
+		return Ok(None);
+	};
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let Some(case) = find_case(&mut db_connection, guild.guild_id, case_number)? else {
+		return Ok(None);
+	};
+
+	let rules = rules_for_action(&mut db_connection, case.case.action_type, &case.case.action_id)?
+		.into_iter()
+		.map(|rule| (rule.rule_number, rule.title))
+		.collect();
+
+	let user = state.discord_client.user(case.target_user).await?.model().await?;
+	let moderator_name = match case.moderator {
+		Some(moderator) if moderator == case.target_user => user.name.clone(),
+		Some(moderator) => state.discord_client.user(moderator).await?.model().await?.name,
+		None => String::from("AutoMod"),
+	};
+	let mod_log_link = match (case.mod_log_channel, case.mod_log_message) {
+		(Some(channel), Some(message)) => Some(format!(
+			"https://discord.com/channels/{}/{}/{}",
+			guild.get_guild_id(),
+			channel,
+			message
+		)),
+		_ => None,
+	};
+
+	Ok(Some(CaseData {
+		case_number: case.case.case_number,
+		title: case.title.to_string(),
+		user_id: case.target_user.get(),
+		user_name: user.name,
+		moderator_name,
+		action_time: case.action_time,
+		expires_at: case.expires_at,
+		reason: case.current_reason().to_string(),
+		original_reason: case.amended_reason.is_some().then(|| case.reason.clone()),
+		evidence: case.evidence.clone(),
+		rules,
+		mod_log_link,
+	}))
+}
@@ -4,10 +4,30 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+#[cfg(feature = "ssr")]
+mod action_queries;
+mod case;
 mod form_editor;
 mod manage_forms;
+mod moderation_actions;
+mod moderator_stats;
 mod open_tickets;
+mod partnership;
+mod partnerships;
+mod server_rules;
+mod temporary_bans;
+mod user_notes;
+mod user_profile;
 
+pub use case::CasePage;
 pub use form_editor::FormEditor;
 pub use manage_forms::ManageForms;
+pub use moderation_actions::ModerationActions;
+pub use moderator_stats::ModeratorStats;
 pub use open_tickets::OpenTickets;
+pub use partnership::PartnershipPage;
+pub use partnerships::Partnerships;
+pub use server_rules::ServerRules;
+pub use temporary_bans::TemporaryBans;
+pub use user_notes::UserNotes;
+pub use user_profile::UserProfile;
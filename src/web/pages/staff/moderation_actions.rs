@@ -0,0 +1,539 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{GuildParam, make_case_url, make_user_profile_url};
+use chrono::{DateTime, NaiveDate, Utc};
+use leptos::ev::{MouseEvent, SubmitEvent};
+use leptos::prelude::*;
+use leptos::task::spawn;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn ModerationActions() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let (action_kind, set_action_kind) = signal(String::new());
+	let (moderator, set_moderator) = signal(String::new());
+	let (target, set_target) = signal(String::new());
+	let (from_date, set_from_date) = signal(String::new());
+	let (to_date, set_to_date) = signal(String::new());
+	let (rule_number, set_rule_number) = signal(String::new());
+	let (missing_reason_only, set_missing_reason_only) = signal(false);
+	let (filter_error, set_filter_error) = signal(None::<String>);
+
+	let (filter, set_filter) = signal(ActionFilter::default());
+	let (page, set_page) = signal(0usize);
+	let (export_file, set_export_file) = signal(None::<ActionExport>);
+
+	let actions = Resource::new(
+		move || (filter.get(), page.get()),
+		move |(filter, page)| get_moderation_actions(guild_id, filter, page),
+	);
+
+	let filter_submit = move |event: SubmitEvent| {
+		event.prevent_default();
+		set_export_file.set(None);
+
+		let new_filter = parse_filter_form(
+			&action_kind.get(),
+			&moderator.get(),
+			&target.get(),
+			&from_date.get(),
+			&to_date.get(),
+			&rule_number.get(),
+			missing_reason_only.get(),
+		);
+		match new_filter {
+			Ok(new_filter) => {
+				set_filter_error.set(None);
+				set_filter.set(new_filter);
+				set_page.set(0);
+			}
+			Err(error) => set_filter_error.set(Some(error)),
+		}
+	};
+
+	let export = move |format: ExportFormat| {
+		let filter = filter.get_untracked();
+		spawn(async move {
+			match export_moderation_actions(guild_id, filter, format).await {
+				Ok(export) => set_export_file.set(Some(export)),
+				Err(error) => set_filter_error.set(Some(error.to_string())),
+			}
+		});
+	};
+	let export_csv_click = move |_: MouseEvent| export(ExportFormat::Csv);
+	let export_json_click = move |_: MouseEvent| export(ExportFormat::Json);
+
+	view! {
+		<h1>"Moderation Actions"</h1>
+		<form class="moderation_actions_filter" on:submit=filter_submit>
+			<label>
+				<span class="moderation_actions_label_text">"Type"</span>
+				<select bind:value=(action_kind, set_action_kind)>
+					<option value="">"All"</option>
+					{
+						ActionKind::ALL_KINDS.iter().map(|kind| view! {
+							<option value={kind.name()}>{kind.label()}</option>
+						}).collect::<Vec<_>>()
+					}
+				</select>
+			</label>
+			<label>
+				<span class="moderation_actions_label_text">"Moderator ID"</span>
+				<input type="text" bind:value=(moderator, set_moderator) />
+			</label>
+			<label>
+				<span class="moderation_actions_label_text">"User ID"</span>
+				<input type="text" bind:value=(target, set_target) />
+			</label>
+			<label>
+				<span class="moderation_actions_label_text">"From"</span>
+				<input type="date" bind:value=(from_date, set_from_date) />
+			</label>
+			<label>
+				<span class="moderation_actions_label_text">"To"</span>
+				<input type="date" bind:value=(to_date, set_to_date) />
+			</label>
+			<label>
+				<span class="moderation_actions_label_text">"Rule"</span>
+				<input type="number" min="1" bind:value=(rule_number, set_rule_number) />
+			</label>
+			<label>
+				<input type="checkbox" bind:checked=(missing_reason_only, set_missing_reason_only) />
+				"Only actions missing a reason"
+			</label>
+			<div>
+				<button type="submit">"Search"</button>
+				<button type="button" on:click=export_csv_click>"Export CSV"</button>
+				<button type="button" on:click=export_json_click>"Export JSON"</button>
+			</div>
+			{move || filter_error.get().map(|error| view! {
+				<div class="moderation_actions_filter_error">{error}</div>
+			})}
+			{move || export_file.get().map(|export| view! {
+				<div class="moderation_actions_export">
+					<a href={make_data_url(&export.content_type, &export.content)} download={export.file_name.clone()}>
+						"Download " {export.file_name}
+					</a>
+				</div>
+			})}
+		</form>
+		<Transition fallback=|| view! { <div class="moderation_actions_loading">"Loading actions..."</div> }>
+			{
+				move || match actions.read().as_ref().and_then(|actions| actions.as_ref().ok()).cloned().flatten() {
+					Some(page_data) if !page_data.actions.is_empty() => {
+						let current_page = page_data.page;
+						let page_count = page_data.page_count;
+						view! {
+							<table class="moderation_actions_list">
+								<thead>
+									<tr>
+										<th>"Case"</th>
+										<th>"Type"</th>
+										<th>"Time"</th>
+										<th>"Moderator"</th>
+										<th>"User"</th>
+										<th>"Reason"</th>
+										<th>"Rules"</th>
+									</tr>
+								</thead>
+								<tbody>
+									{
+										page_data.actions.into_iter().map(|action| {
+											let rules: Vec<String> = action.rules.iter().map(|rule| rule.to_string()).collect();
+											view! {
+												<tr>
+													<td>
+														{
+															action.case_number.map(|case_number| view! {
+																<a href={make_case_url(guild_id, case_number)}>{case_number}</a>
+															})
+														}
+													</td>
+													<td>{action.title}</td>
+													<td>{action.action_time.to_rfc3339()}</td>
+													<td>{action.moderator_name.unwrap_or_else(|| String::from("AutoMod"))}</td>
+													<td>
+														<a href={make_user_profile_url(guild_id, action.target_id)}>
+															{action.target_name}
+														</a>
+													</td>
+													<td class="moderation_actions_reason">
+														{
+															if action.reason.is_empty() {
+																view! { <em>"No reason given"</em> }.into_any()
+															} else {
+																action.reason.into_any()
+															}
+														}
+													</td>
+													<td>{rules.join(", ")}</td>
+												</tr>
+											}
+										}).collect::<Vec<_>>()
+									}
+								</tbody>
+							</table>
+							<div class="moderation_actions_pages">
+								<button
+									type="button"
+									disabled={current_page == 0}
+									on:click=move |_| set_page.set(current_page.saturating_sub(1))
+								>
+									"Previous"
+								</button>
+								<span>
+									{format!("Page {} of {} ({} actions)", current_page + 1, page_count, page_data.total_count)}
+								</span>
+								<button
+									type="button"
+									disabled={current_page + 1 >= page_count}
+									on:click=move |_| set_page.set(current_page + 1)
+								>
+									"Next"
+								</button>
+							</div>
+						}.into_any()
+					}
+					Some(_) => view! {
+						<div class="moderation_actions_empty">"No actions match this filter."</div>
+					}.into_any(),
+					None => view! {
+						<div class="moderation_actions_error">"Actions couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+/// Turns the values entered in the filter form into a filter, or describes the first value that isn't valid
+fn parse_filter_form(
+	action_kind: &str,
+	moderator: &str,
+	target: &str,
+	from_date: &str,
+	to_date: &str,
+	rule_number: &str,
+	missing_reason_only: bool,
+) -> Result<ActionFilter, String> {
+	let parse_user = |value: &str, field_name: &str| -> Result<Option<u64>, String> {
+		let value = value.trim();
+		if value.is_empty() {
+			return Ok(None);
+		}
+		match value.parse::<u64>() {
+			Ok(id) if id > 0 => Ok(Some(id)),
+			_ => Err(format!("The {} must be a user ID", field_name)),
+		}
+	};
+	let parse_date = |value: &str| -> Result<Option<NaiveDate>, String> {
+		let value = value.trim();
+		if value.is_empty() {
+			return Ok(None);
+		}
+		match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+			Ok(date) => Ok(Some(date)),
+			Err(_) => Err(format!("{} isn't a valid date", value)),
+		}
+	};
+
+	let rule_number = rule_number.trim();
+	let rule_number = if rule_number.is_empty() {
+		None
+	} else {
+		match rule_number.parse::<i32>() {
+			Ok(number) => Some(number),
+			Err(_) => return Err(String::from("The rule must be a rule number")),
+		}
+	};
+
+	Ok(ActionFilter {
+		action_kind: ActionKind::from_name(action_kind),
+		moderator: parse_user(moderator, "moderator")?,
+		target: parse_user(target, "user")?,
+		from_date: parse_date(from_date)?,
+		to_date: parse_date(to_date)?,
+		rule_number,
+		missing_reason_only,
+	})
+}
+
+/// Makes a `data:` URL holding the given text, so it can be downloaded from a link without another request
+fn make_data_url(content_type: &str, content: &str) -> String {
+	let mut url = format!("data:{};charset=utf-8,", content_type);
+	for byte in content.bytes() {
+		if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+			url.push(char::from(byte));
+		} else {
+			url.push_str(&format!("%{:02X}", byte));
+		}
+	}
+	url
+}
+
+/// The kinds of actions that can be browsed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ActionKind {
+	Ban,
+	Unban,
+	Kick,
+	Timeout,
+	Warning,
+	Automod,
+}
+
+impl ActionKind {
+	pub const ALL_KINDS: [Self; 6] = [
+		Self::Ban,
+		Self::Unban,
+		Self::Kick,
+		Self::Timeout,
+		Self::Warning,
+		Self::Automod,
+	];
+
+	/// Gets the name used to refer to the kind in the filter form
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Ban => "ban",
+			Self::Unban => "unban",
+			Self::Kick => "kick",
+			Self::Timeout => "timeout",
+			Self::Warning => "warning",
+			Self::Automod => "automod",
+		}
+	}
+
+	/// Parses a kind from its name
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL_KINDS.into_iter().find(|kind| kind.name() == name)
+	}
+
+	/// Gets the name of the kind to show to users
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::Ban => "Bans",
+			Self::Unban => "Unbans",
+			Self::Kick => "Kicks",
+			Self::Timeout => "Timeouts",
+			Self::Warning => "Warnings",
+			Self::Automod => "AutoMod",
+		}
+	}
+}
+
+/// Limits which actions are listed. Each filter that's set must match for an action to be included.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ActionFilter {
+	pub action_kind: Option<ActionKind>,
+	pub moderator: Option<u64>,
+	pub target: Option<u64>,
+	/// The first day to include actions from
+	pub from_date: Option<NaiveDate>,
+	/// The last day to include actions from
+	pub to_date: Option<NaiveDate>,
+	pub rule_number: Option<i32>,
+	pub missing_reason_only: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActionPageData {
+	pub actions: Vec<ActionData>,
+	/// The page being shown, counting from 0
+	pub page: usize,
+	pub page_count: usize,
+	/// How many actions match the filter across all pages
+	pub total_count: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActionData {
+	pub id: String,
+	pub kind: ActionKind,
+	pub title: String,
+	pub case_number: Option<i32>,
+	pub action_time: DateTime<Utc>,
+	/// The moderator who took the action; AutoMod actions don't have one
+	pub moderator_id: Option<u64>,
+	pub moderator_name: Option<String>,
+	pub target_id: u64,
+	pub target_name: String,
+	pub reason: String,
+	pub expires_at: Option<DateTime<Utc>>,
+	/// The numbers of the rules the action was taken for breaking
+	pub rules: Vec<i32>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ExportFormat {
+	Csv,
+	Json,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActionExport {
+	pub file_name: String,
+	pub content_type: String,
+	pub content: String,
+}
+
+#[server]
+async fn get_moderation_actions(
+	guild_id: Option<u64>,
+	filter: ActionFilter,
+	page: usize,
+) -> Result<Option<ActionPageData>, ServerFnError> {
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::pages::staff::action_queries::{ACTIONS_PAGE_SIZE, describe_actions, find_actions};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	// Only the actions up to the end of the requested page are loaded from each kind of action
+	let Some(limit) = page
+		.checked_add(1)
+		.and_then(|page_count| page_count.checked_mul(ACTIONS_PAGE_SIZE))
+	else {
+		return Err(ServerFnError::ServerError(String::from("Invalid page")));
+	};
+	let limit = i64::try_from(limit)?;
+	let (actions, total_count) = find_actions(&mut db_connection, guild.guild_id, &filter, Some(limit))?;
+	let page_count = usize::try_from(total_count)?.div_ceil(ACTIONS_PAGE_SIZE).max(1);
+	let page = page.min(page_count - 1);
+
+	let page_actions = actions
+		.into_iter()
+		.skip(page * ACTIONS_PAGE_SIZE)
+		.take(ACTIONS_PAGE_SIZE)
+		.collect();
+	let actions = describe_actions(&state, &mut db_connection, guild.guild_id, page_actions).await?;
+
+	Ok(Some(ActionPageData {
+		actions,
+		page,
+		page_count,
+		total_count,
+	}))
+}
+
+#[server]
+async fn export_moderation_actions(
+	guild_id: Option<u64>,
+	filter: ActionFilter,
+	format: ExportFormat,
+) -> Result<ActionExport, ServerFnError> {
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::pages::staff::action_queries::{MAX_EXPORTED_ACTIONS, actions_csv, describe_actions, find_actions};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Err(ServerFnError::ServerError(String::from("Permission denied")));
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let (actions, total_count) = find_actions(&mut db_connection, guild.guild_id, &filter, Some(MAX_EXPORTED_ACTIONS))?;
+	if total_count > MAX_EXPORTED_ACTIONS {
+		return Err(ServerFnError::ServerError(format!(
+			"{} actions match this filter, but at most {} can be exported at once; narrow the filter to export them",
+			total_count, MAX_EXPORTED_ACTIONS
+		)));
+	}
+	let actions = describe_actions(&state, &mut db_connection, guild.guild_id, actions).await?;
+
+	let date = Utc::now().format("%Y-%m-%d");
+	let export = match format {
+		ExportFormat::Csv => ActionExport {
+			file_name: format!("moderation-actions-{}.csv", date),
+			content_type: String::from("text/csv"),
+			content: actions_csv(&actions),
+		},
+		ExportFormat::Json => ActionExport {
+			file_name: format!("moderation-actions-{}.json", date),
+			content_type: String::from("application/json"),
+			content: serde_json::to_string_pretty(&actions)?,
+		},
+	};
+	Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty_form_has_no_filters() {
+		let filter = parse_filter_form("", " ", "", "", "", "", false).unwrap();
+		assert_eq!(filter, ActionFilter::default());
+	}
+
+	#[test]
+	fn filled_form_sets_every_filter() {
+		let filter = parse_filter_form("kick", " 12 ", "34", "2026-10-01", "2026-10-18", "4", true).unwrap();
+		assert_eq!(
+			filter,
+			ActionFilter {
+				action_kind: Some(ActionKind::Kick),
+				moderator: Some(12),
+				target: Some(34),
+				from_date: NaiveDate::from_ymd_opt(2026, 10, 1),
+				to_date: NaiveDate::from_ymd_opt(2026, 10, 18),
+				rule_number: Some(4),
+				missing_reason_only: true,
+			}
+		);
+	}
+
+	#[test]
+	fn unknown_kinds_include_every_kind() {
+		let filter = parse_filter_form("everything", "", "", "", "", "", false).unwrap();
+		assert_eq!(filter.action_kind, None);
+	}
+
+	#[test]
+	fn user_ids_must_be_positive_numbers() {
+		assert!(parse_filter_form("", "someone", "", "", "", "", false).is_err());
+		assert!(parse_filter_form("", "0", "", "", "", "", false).is_err());
+		assert!(parse_filter_form("", "", "-5", "", "", "", false).is_err());
+	}
+
+	#[test]
+	fn invalid_dates_and_rules_are_rejected() {
+		assert!(parse_filter_form("", "", "", "2026-13-01", "", "", false).is_err());
+		assert!(parse_filter_form("", "", "", "", "yesterday", "", false).is_err());
+		assert!(parse_filter_form("", "", "", "", "", "R4", false).is_err());
+	}
+
+	#[test]
+	fn data_url_keeps_unreserved_characters() {
+		assert_eq!(
+			make_data_url("text/csv", "a-Z_0.~"),
+			"data:text/csv;charset=utf-8,a-Z_0.~"
+		);
+	}
+
+	#[test]
+	fn data_url_encodes_other_bytes() {
+		assert_eq!(
+			make_data_url("text/csv", "a,b\r\n\"é\""),
+			"data:text/csv;charset=utf-8,a%2Cb%0D%0A%22%C3%A9%22"
+		);
+	}
+}
@@ -0,0 +1,370 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{GuildParam, make_user_profile_url};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+/// The periods, in days, that statistics can be shown for
+const STATS_PERIODS: [i64; 3] = [30, 90, 365];
+
+#[component]
+pub fn ModeratorStats() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let (period, set_period) = signal(STATS_PERIODS[0].to_string());
+
+	let stats = Resource::new(
+		move || period.get().parse::<i64>().ok(),
+		move |days| get_moderator_stats(guild_id, days),
+	);
+
+	view! {
+		<h1>"Moderator Activity"</h1>
+		<label class="moderator_stats_period">
+			"Show activity from "
+			<select bind:value=(period, set_period)>
+				{
+					STATS_PERIODS.iter().map(|days| view! {
+						<option value={days.to_string()}>{format!("the last {} days", days)}</option>
+					}).collect::<Vec<_>>()
+				}
+				<option value="all">"all time"</option>
+			</select>
+		</label>
+		<Transition fallback=|| view! { <div class="moderator_stats_loading">"Loading statistics..."</div> }>
+			{
+				move || match stats.read().as_ref().and_then(|stats| stats.as_ref().ok()).cloned().flatten() {
+					Some(stats) if !stats.is_empty() => view! {
+						<table class="moderator_stats_list">
+							<thead>
+								<tr>
+									<th>"Moderator"</th>
+									<th>"Bans"</th>
+									<th>"Unbans"</th>
+									<th>"Kicks"</th>
+									<th>"Timeouts"</th>
+									<th>"Warnings"</th>
+									<th>"Total Actions"</th>
+									<th>"Missing Reasons"</th>
+									<th>"Tickets Answered"</th>
+									<th>"Ticket Messages"</th>
+									<th>"Average Response Time"</th>
+								</tr>
+							</thead>
+							<tbody>
+								{
+									stats.into_iter().map(|moderator| {
+										let total_actions = moderator.total_actions();
+										let missing_reasons = if total_actions == 0 {
+											String::from("-")
+										} else {
+											format!(
+												"{} ({:.0}%)",
+												moderator.missing_reasons,
+												moderator.missing_reasons as f64 * 100.0 / total_actions as f64
+											)
+										};
+										let response_time = moderator
+											.average_response_seconds
+											.map(format_duration)
+											.unwrap_or_else(|| String::from("-"));
+										view! {
+											<tr>
+												<td>
+													<a href={make_user_profile_url(guild_id, moderator.user_id)}>
+														{moderator.user_name}
+													</a>
+												</td>
+												<td>{moderator.bans}</td>
+												<td>{moderator.unbans}</td>
+												<td>{moderator.kicks}</td>
+												<td>{moderator.timeouts}</td>
+												<td>{moderator.warnings}</td>
+												<td>{total_actions}</td>
+												<td>{missing_reasons}</td>
+												<td>{moderator.tickets_answered}</td>
+												<td>{moderator.ticket_messages}</td>
+												<td>{response_time}</td>
+											</tr>
+										}
+									}).collect::<Vec<_>>()
+								}
+							</tbody>
+						</table>
+					}.into_any(),
+					Some(_) => view! {
+						<div class="moderator_stats_empty">"There's no moderator activity in this period."</div>
+					}.into_any(),
+					None => view! {
+						<div class="moderator_stats_error">"Statistics couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+/// Formats a number of seconds as a short, readable duration
+fn format_duration(seconds: i64) -> String {
+	let minutes = seconds / 60;
+	let hours = minutes / 60;
+	let days = hours / 24;
+	if days > 0 {
+		format!("{}d {}h", days, hours % 24)
+	} else if hours > 0 {
+		format!("{}h {}m", hours, minutes % 60)
+	} else if minutes > 0 {
+		format!("{}m", minutes)
+	} else {
+		format!("{}s", seconds)
+	}
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ModeratorActivity {
+	pub user_id: u64,
+	pub user_name: String,
+	pub bans: i64,
+	pub unbans: i64,
+	pub kicks: i64,
+	pub timeouts: i64,
+	pub warnings: i64,
+	/// How many of the moderator's actions have no reason
+	pub missing_reasons: i64,
+	/// How many different tickets the moderator replied to
+	pub tickets_answered: i64,
+	/// How many replies the moderator sent in tickets
+	pub ticket_messages: i64,
+	/// On average, how long a user waited for a reply when this moderator was the one who replied
+	pub average_response_seconds: Option<i64>,
+}
+
+impl ModeratorActivity {
+	pub fn total_actions(&self) -> i64 {
+		self.bans + self.unbans + self.kicks + self.timeouts + self.warnings
+	}
+}
+
+#[server]
+async fn get_moderator_stats(
+	guild_id: Option<u64>,
+	days: Option<i64>,
+) -> Result<Option<Vec<ModeratorActivity>>, ServerFnError> {
+	use crate::model::discord_id_from_database_id;
+	use crate::schema::{ban_actions, kick_actions, ticket_messages, tickets, timeout_actions, warn_actions};
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use chrono::{DateTime, TimeDelta, Utc};
+	use diesel::dsl::count_star;
+	use diesel::prelude::*;
+	use std::collections::{HashMap, HashSet};
+	use twilight_model::id::Id;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level != PermissionLevel::Admin {
+		return Ok(None);
+	}
+
+	// Nothing in the database predates Discord, so the Unix epoch works as the start of "all time"
+	let since = match days {
+		Some(days) if STATS_PERIODS.contains(&days) => Utc::now() - TimeDelta::days(days),
+		Some(days) => {
+			return Err(ServerFnError::ServerError(format!(
+				"Statistics can't be shown for a period of {} days",
+				days
+			)));
+		}
+		None => DateTime::UNIX_EPOCH,
+	};
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let mut activity: HashMap<i64, ModeratorActivity> = HashMap::new();
+
+	let ban_counts: Vec<(i64, bool, i64)> = ban_actions::table
+		.filter(
+			ban_actions::guild
+				.eq(guild.guild_id)
+				.and(ban_actions::action_time.ge(since)),
+		)
+		.group_by((ban_actions::banning_user, ban_actions::added))
+		.select((ban_actions::banning_user, ban_actions::added, count_star()))
+		.load(&mut db_connection)?;
+	for (user, added, count) in ban_counts {
+		let moderator = activity.entry(user).or_default();
+		if added {
+			moderator.bans += count;
+		} else {
+			moderator.unbans += count;
+		}
+	}
+
+	let kick_counts: Vec<(i64, i64)> = kick_actions::table
+		.filter(
+			kick_actions::guild
+				.eq(guild.guild_id)
+				.and(kick_actions::action_time.ge(since)),
+		)
+		.group_by(kick_actions::kicking_user)
+		.select((kick_actions::kicking_user, count_star()))
+		.load(&mut db_connection)?;
+	for (user, count) in kick_counts {
+		activity.entry(user).or_default().kicks += count;
+	}
+
+	let timeout_counts: Vec<(i64, i64)> = timeout_actions::table
+		.filter(
+			timeout_actions::guild
+				.eq(guild.guild_id)
+				.and(timeout_actions::action_time.ge(since)),
+		)
+		.group_by(timeout_actions::performing_user)
+		.select((timeout_actions::performing_user, count_star()))
+		.load(&mut db_connection)?;
+	for (user, count) in timeout_counts {
+		activity.entry(user).or_default().timeouts += count;
+	}
+
+	let warning_counts: Vec<(i64, i64)> = warn_actions::table
+		.filter(
+			warn_actions::guild
+				.eq(guild.guild_id)
+				.and(warn_actions::action_time.ge(since)),
+		)
+		.group_by(warn_actions::warning_user)
+		.select((warn_actions::warning_user, count_star()))
+		.load(&mut db_connection)?;
+	for (user, count) in warning_counts {
+		activity.entry(user).or_default().warnings += count;
+	}
+
+	// Unbans aren't expected to have a reason, so they're never counted as missing one
+	let mut missing_reason_counts: Vec<(i64, i64)> = ban_actions::table
+		.filter(
+			ban_actions::guild
+				.eq(guild.guild_id)
+				.and(ban_actions::action_time.ge(since))
+				.and(ban_actions::added.eq(true))
+				.and(ban_actions::reason.eq(""))
+				.and(ban_actions::amended_reason.is_null()),
+		)
+		.group_by(ban_actions::banning_user)
+		.select((ban_actions::banning_user, count_star()))
+		.load(&mut db_connection)?;
+	let kick_missing_reasons: Vec<(i64, i64)> = kick_actions::table
+		.filter(
+			kick_actions::guild
+				.eq(guild.guild_id)
+				.and(kick_actions::action_time.ge(since))
+				.and(kick_actions::reason.eq(""))
+				.and(kick_actions::amended_reason.is_null()),
+		)
+		.group_by(kick_actions::kicking_user)
+		.select((kick_actions::kicking_user, count_star()))
+		.load(&mut db_connection)?;
+	let timeout_missing_reasons: Vec<(i64, i64)> = timeout_actions::table
+		.filter(
+			timeout_actions::guild
+				.eq(guild.guild_id)
+				.and(timeout_actions::action_time.ge(since))
+				.and(timeout_actions::reason.eq(""))
+				.and(timeout_actions::amended_reason.is_null()),
+		)
+		.group_by(timeout_actions::performing_user)
+		.select((timeout_actions::performing_user, count_star()))
+		.load(&mut db_connection)?;
+	let warning_missing_reasons: Vec<(i64, i64)> = warn_actions::table
+		.filter(
+			warn_actions::guild
+				.eq(guild.guild_id)
+				.and(warn_actions::action_time.ge(since))
+				.and(warn_actions::reason.eq(""))
+				.and(warn_actions::amended_reason.is_null()),
+		)
+		.group_by(warn_actions::warning_user)
+		.select((warn_actions::warning_user, count_star()))
+		.load(&mut db_connection)?;
+	missing_reason_counts.extend(kick_missing_reasons);
+	missing_reason_counts.extend(timeout_missing_reasons);
+	missing_reason_counts.extend(warning_missing_reasons);
+	for (user, count) in missing_reason_counts {
+		activity.entry(user).or_default().missing_reasons += count;
+	}
+
+	// Internal staff messages aren't replies to the user, so only messages the user could see are counted
+	let messages: Vec<(String, i64, DateTime<Utc>, i64)> = ticket_messages::table
+		.inner_join(tickets::table)
+		.filter(
+			tickets::guild
+				.eq(guild.guild_id)
+				.and(ticket_messages::user_message.is_not_null())
+				.and(ticket_messages::send_time.ge(since)),
+		)
+		.order((ticket_messages::ticket.asc(), ticket_messages::send_time.asc()))
+		.select((
+			ticket_messages::ticket,
+			ticket_messages::author,
+			ticket_messages::send_time,
+			tickets::with_user,
+		))
+		.load(&mut db_connection)?;
+
+	let mut answered_tickets: HashMap<i64, HashSet<String>> = HashMap::new();
+	let mut response_times: HashMap<i64, Vec<TimeDelta>> = HashMap::new();
+	let mut current_ticket: Option<String> = None;
+	let mut waiting_since: Option<DateTime<Utc>> = None;
+	for (ticket, author, send_time, with_user) in messages {
+		if current_ticket.as_ref() != Some(&ticket) {
+			current_ticket = Some(ticket.clone());
+			waiting_since = None;
+		}
+
+		if author == with_user {
+			// Only the first message in a run of user messages starts the wait for a reply
+			if waiting_since.is_none() {
+				waiting_since = Some(send_time);
+			}
+			continue;
+		}
+
+		activity.entry(author).or_default().ticket_messages += 1;
+		if let Some(waiting_since) = waiting_since.take() {
+			response_times
+				.entry(author)
+				.or_default()
+				.push(send_time - waiting_since);
+		}
+		answered_tickets.entry(author).or_default().insert(ticket);
+	}
+	for (user, tickets) in answered_tickets {
+		activity.entry(user).or_default().tickets_answered = tickets.len() as i64;
+	}
+	for (user, times) in response_times {
+		let total_seconds: i64 = times.iter().map(|time| time.num_seconds()).sum();
+		activity.entry(user).or_default().average_response_seconds = Some(total_seconds / times.len() as i64);
+	}
+
+	let mut moderators: Vec<ModeratorActivity> = Vec::with_capacity(activity.len());
+	for (user, mut moderator) in activity {
+		let user_id = Id::new(discord_id_from_database_id(user));
+		let user = state.discord_client.user(user_id).await?.model().await?;
+		moderator.user_id = user_id.get();
+		moderator.user_name = user.name;
+		moderators.push(moderator);
+	}
+	moderators.sort_by(|a, b| {
+		(b.total_actions() + b.ticket_messages)
+			.cmp(&(a.total_actions() + a.ticket_messages))
+			.then_with(|| a.user_name.cmp(&b.user_name))
+	});
+
+	Ok(Some(moderators))
+}
@@ -4,7 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::web::pages::utils::{GuildParam, make_ticket_url};
+use crate::web::pages::utils::{GuildParam, make_ticket_url, make_user_profile_url};
 use chrono::{DateTime, Utc};
 use leptos::prelude::*;
 use leptos_router::hooks::use_params;
@@ -43,7 +43,9 @@ pub fn OpenTickets() -> impl IntoView {
 														</a>
 													</td>
 													<td>
-														{ticket.with_user_name.clone()}
+														<a href={make_user_profile_url(guild_id, ticket.with_user_id)}>
+															{ticket.with_user_name.clone()}
+														</a>
 													</td>
 													<td>
 														{ticket.last_message_author_name.clone()}
@@ -74,6 +76,7 @@ pub fn OpenTickets() -> impl IntoView {
 pub struct StaffTicketMetadata {
 	pub id: String,
 	pub title: String,
+	pub with_user_id: u64,
 	pub with_user_name: String,
 	pub last_message_author_name: String,
 	pub last_message_time: DateTime<Utc>,
@@ -133,6 +136,7 @@ async fn get_active_tickets(guild_id: Option<u64>) -> Result<Vec<StaffTicketMeta
 		Ok(StaffTicketMetadata {
 			id: ticket.id,
 			title: ticket.title,
+			with_user_id: with_user.get(),
 			with_user_name,
 			last_message_author_name,
 			last_message_time: last_message.send_time,
@@ -0,0 +1,380 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{PartnershipParams, make_ticket_url, make_user_profile_url};
+use chrono::{DateTime, Utc};
+use leptos::ev::MouseEvent;
+use leptos::prelude::*;
+use leptos::task::spawn;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn PartnershipPage() -> impl IntoView {
+	let params = use_params::<PartnershipParams>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+	let partnership_id = params
+		.read()
+		.as_ref()
+		.ok()
+		.and_then(|params| params.partnership_id.clone());
+
+	let resource_partnership_id = partnership_id.clone();
+	let partnership = Resource::new(
+		|| (),
+		move |_| get_partnership(guild_id, resource_partnership_id.clone()),
+	);
+	let (update_error, set_update_error) = signal(None::<String>);
+
+	view! {
+		<Transition fallback=|| view! { <div class="partnership_loading">"Loading partnership..."</div> }>
+			{
+				move || match partnership.read().as_ref().and_then(|partnership| partnership.as_ref().ok()).cloned().flatten() {
+					Some(partnership_data) => {
+						let active = partnership_data.active;
+						let partnership_id = partnership_id.clone();
+						let toggle_active_click = move |_: MouseEvent| {
+							let partnership_id = partnership_id.clone();
+							set_update_error.set(None);
+							spawn(async move {
+								if let Err(error) = set_partnership_active(guild_id, partnership_id, !active).await {
+									set_update_error.set(Some(error.to_string()));
+								}
+								partnership.refetch();
+							});
+						};
+						view! {
+							<div class="partnership_header">
+								{
+									partnership_data.icon_url.clone().map(|icon_url| view! {
+										<img class="partnership_icon" src={icon_url} />
+									})
+								}
+								<h1>{partnership_data.partner_name.clone()}</h1>
+							</div>
+							<table class="partnership_details">
+								<tbody>
+									<tr>
+										<th>"Server ID"</th>
+										<td>{partnership_data.partner_guild_id}</td>
+									</tr>
+									<tr>
+										<th>"Invite"</th>
+										<td>
+											<a href={partnership_data.invite_url.clone()}>{partnership_data.invite_url.clone()}</a>
+										</td>
+									</tr>
+									<tr>
+										<th>"Members When Approved"</th>
+										<td>{partnership_data.member_count}</td>
+									</tr>
+									<tr>
+										<th>"Representative"</th>
+										<td>
+											<a href={make_user_profile_url(guild_id, partnership_data.representative_id)}>
+												{partnership_data.representative_name.clone()}
+											</a>
+										</td>
+									</tr>
+									<tr>
+										<th>"Approved By"</th>
+										<td>{partnership_data.approved_by_name.clone()}</td>
+									</tr>
+									<tr>
+										<th>"Approved"</th>
+										<td>{partnership_data.approved_at.to_rfc3339()}</td>
+									</tr>
+									{
+										partnership_data.in_network.map(|in_network| view! {
+											<tr>
+												<th>"In Partner Network"</th>
+												<td>{if in_network { "Yes" } else { "No" }}</td>
+											</tr>
+										})
+									}
+									<tr>
+										<th>"Active"</th>
+										<td>
+											{if active { "Yes" } else { "No" }}
+											<button type="button" on:click=toggle_active_click>
+												{if active { "Mark inactive" } else { "Mark active" }}
+											</button>
+										</td>
+									</tr>
+								</tbody>
+							</table>
+							{move || update_error.get().map(|error| view! {
+								<div class="partnership_update_error">{error}</div>
+							})}
+
+							<h2>"Tickets"</h2>
+							<table class="partnership_tickets">
+								<thead>
+									<tr>
+										<th>"Ticket"</th>
+										<th>"Opened"</th>
+										<th>"Closed"</th>
+									</tr>
+								</thead>
+								<tbody>
+									{
+										partnership_data.tickets.iter().map(|ticket| view! {
+											<tr>
+												<td>
+													<a href={make_ticket_url(guild_id, &ticket.id)}>{ticket.title.clone()}</a>
+												</td>
+												<td>{ticket.opened_at.map(|opened_at| opened_at.to_rfc3339())}</td>
+												<td>
+													{
+														ticket
+															.closed_at
+															.map(|closed_at| closed_at.to_rfc3339())
+															.unwrap_or_else(|| String::from("Open"))
+													}
+												</td>
+											</tr>
+										}).collect::<Vec<_>>()
+									}
+								</tbody>
+							</table>
+
+							<h2>"Invite Checks"</h2>
+							{
+								if partnership_data.invite_checks.is_empty() {
+									view! {
+										<div class="partnership_no_checks">"The invite hasn't been checked yet."</div>
+									}.into_any()
+								} else {
+									view! {
+										<table class="partnership_invite_checks">
+											<thead>
+												<tr>
+													<th>"Checked"</th>
+													<th>"Result"</th>
+													<th>"Expires"</th>
+												</tr>
+											</thead>
+											<tbody>
+												{
+													partnership_data.invite_checks.iter().map(|check| view! {
+														<tr>
+															<td>{check.checked_at.to_rfc3339()}</td>
+															<td>{check.status.clone()}</td>
+															<td>{check.expires_at.map(|expires_at| expires_at.to_rfc3339())}</td>
+														</tr>
+													}).collect::<Vec<_>>()
+												}
+											</tbody>
+										</table>
+									}.into_any()
+								}
+							}
+						}.into_any()
+					}
+					None => view! {
+						<div class="partnership_error">"This partnership couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartnershipData {
+	pub partner_name: String,
+	pub partner_guild_id: u64,
+	pub icon_url: Option<String>,
+	pub invite_url: String,
+	pub member_count: i64,
+	pub representative_id: u64,
+	pub representative_name: String,
+	pub approved_by_name: String,
+	pub approved_at: DateTime<Utc>,
+	pub active: bool,
+	/// Whether the partner is part of the partner network, if the server has TCN partner integration enabled
+	pub in_network: Option<bool>,
+	/// Every ticket about the partnership, including the one it was requested in, newest first
+	pub tickets: Vec<PartnershipTicket>,
+	/// The most recent checks of the partner's invite, newest first
+	pub invite_checks: Vec<InviteCheckData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartnershipTicket {
+	pub id: String,
+	pub title: String,
+	pub opened_at: Option<DateTime<Utc>>,
+	pub closed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InviteCheckData {
+	pub checked_at: DateTime<Utc>,
+	pub status: String,
+	pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[server]
+async fn get_partnership(
+	guild_id: Option<u64>,
+	partnership_id: Option<String>,
+) -> Result<Option<PartnershipData>, ServerFnError> {
+	use crate::discord::utils::timestamp::datetime_from_id;
+	use crate::model::{PartnerInviteCheck, Partnership, Ticket};
+	use crate::schema::{partner_invite_checks, partnerships, tcn_partners, tickets};
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::dsl::exists;
+	use diesel::prelude::*;
+
+	/// How many of the partner's invite checks to show
+	const INVITE_CHECK_HISTORY_LENGTH: i64 = 30;
+
+	let Some(partnership_id) = partnership_id else {
+		return Ok(None);
+	};
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let partnership: Option<Partnership> = partnerships::table
+		.filter(
+			partnerships::id
+				.eq(&partnership_id)
+				.and(partnerships::guild.eq(guild.guild_id)),
+		)
+		.first(&mut db_connection)
+		.optional()?;
+	let Some(partnership) = partnership else {
+		return Ok(None);
+	};
+
+	let partnership_tickets: Vec<Ticket> = tickets::table
+		.filter(
+			tickets::partnership
+				.eq(&partnership.id)
+				.or(tickets::id.eq(&partnership.ticket)),
+		)
+		.load(&mut db_connection)?;
+	let mut ticket_data: Vec<PartnershipTicket> = partnership_tickets
+		.into_iter()
+		.map(|ticket| PartnershipTicket {
+			opened_at: datetime_from_id(ticket.get_user_thread()),
+			closed_at: ticket.closed_at,
+			id: ticket.id,
+			title: ticket.title,
+		})
+		.collect();
+	ticket_data.sort_by(|a, b| b.opened_at.cmp(&a.opened_at));
+
+	let invite_checks: Vec<PartnerInviteCheck> = partner_invite_checks::table
+		.filter(partner_invite_checks::partnership.eq(&partnership.id))
+		.order(partner_invite_checks::checked_at.desc())
+		.limit(INVITE_CHECK_HISTORY_LENGTH)
+		.load(&mut db_connection)?;
+	let invite_checks = invite_checks
+		.into_iter()
+		.map(|check| InviteCheckData {
+			checked_at: check.checked_at,
+			status: check.status.to_string(),
+			expires_at: check.expires_at,
+		})
+		.collect();
+
+	let in_network = if guild.tcn_partner_integration {
+		let in_network: bool = diesel::select(exists(tcn_partners::table.find(partnership.partner_guild)))
+			.get_result(&mut db_connection)?;
+		Some(in_network)
+	} else {
+		None
+	};
+
+	let partner_guild_id = partnership.get_partner_guild();
+	let icon_url = partnership.partner_icon.as_ref().map(|icon_hash| {
+		format!(
+			"https://cdn.discordapp.com/icons/{}/{}.png",
+			partner_guild_id, icon_hash
+		)
+	});
+	let representative_id = partnership.get_representative();
+	let representative_name = state.discord_client.user(representative_id).await?.model().await?.name;
+	let approved_by_name = state
+		.discord_client
+		.user(partnership.get_approved_by())
+		.await?
+		.model()
+		.await?
+		.name;
+
+	Ok(Some(PartnershipData {
+		partner_name: partnership.partner_name,
+		partner_guild_id: partner_guild_id.get(),
+		icon_url,
+		invite_url: format!("https://discord.gg/{}", partnership.invite_code),
+		member_count: partnership.member_count,
+		representative_id: representative_id.get(),
+		representative_name,
+		approved_by_name,
+		approved_at: partnership.approved_at,
+		active: partnership.active,
+		in_network,
+		tickets: ticket_data,
+		invite_checks,
+	}))
+}
+
+#[server]
+async fn set_partnership_active(
+	guild_id: Option<u64>,
+	partnership_id: Option<String>,
+	active: bool,
+) -> Result<(), ServerFnError> {
+	use crate::discord::utils::partner_list::update_partner_list_message;
+	use crate::schema::partnerships;
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::prelude::*;
+
+	let Some(partnership_id) = partnership_id else {
+		return Err(ServerFnError::ServerError(String::from("No partnership specified")));
+	};
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Err(ServerFnError::ServerError(String::from("Permission denied")));
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let updated_count = diesel::update(partnerships::table)
+		.filter(
+			partnerships::id
+				.eq(&partnership_id)
+				.and(partnerships::guild.eq(guild.guild_id)),
+		)
+		.set((
+			partnerships::active.eq(active),
+			partnerships::tcn_push_pending.eq(guild.tcn_partner_integration),
+		))
+		.execute(&mut db_connection)?;
+	if updated_count == 0 {
+		return Err(ServerFnError::ServerError(String::from("Partnership not found")));
+	}
+
+	// The change is already saved, so a Discord failure here shouldn't be reported as the update failing.
+	if let Err(error) = update_partner_list_message(&state.discord_client, &guild, &mut db_connection).await {
+		tracing::warn!(source = ?error, "Failed to update the partner list after changing a partnership");
+	}
+
+	Ok(())
+}
@@ -0,0 +1,148 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{GuildParam, make_partner_directory_url, make_partnership_url, make_user_profile_url};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn Partnerships() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let partnerships = OnceResource::new(get_partnerships(guild_id));
+
+	view! {
+		<h1>"Partnerships"</h1>
+		<a class="partnership_directory_link" href={make_partner_directory_url(guild_id)}>"Public partner list"</a>
+		<Transition fallback=|| view! { <div class="partnership_list_loading">"Loading partnerships..."</div> }>
+			{
+				move || match partnerships.read().as_ref().and_then(|partnerships| partnerships.as_ref().ok()) {
+					Some(partnership_data) if !partnership_data.is_empty() => view! {
+						<table class="partnership_list">
+							<thead>
+								<tr>
+									<th>"Server"</th>
+									<th>"Members"</th>
+									<th>"Representative"</th>
+									<th>"Approved"</th>
+									<th>"Invite"</th>
+									<th>"Active"</th>
+								</tr>
+							</thead>
+							<tbody>
+								{
+									partnership_data.iter().map(|partnership| view! {
+										<tr>
+											<td>
+												<a href={make_partnership_url(guild_id, &partnership.id)}>
+													{partnership.partner_name.clone()}
+												</a>
+											</td>
+											<td>{partnership.member_count}</td>
+											<td>
+												<a href={make_user_profile_url(guild_id, partnership.representative_id)}>
+													{partnership.representative_name.clone()}
+												</a>
+											</td>
+											<td>{partnership.approved_at.to_rfc3339()}</td>
+											<td>
+												{
+													partnership
+														.invite_status
+														.clone()
+														.unwrap_or_else(|| String::from("Not checked yet"))
+												}
+											</td>
+										</tr>
+									}).collect::<Vec<_>>()
+								}
+							</tbody>
+						</table>
+					}.into_any(),
+					_ => view! {
+						<div class="partnership_list_empty">"There are no partnerships."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartnershipSummary {
+	pub id: String,
+	pub partner_name: String,
+	pub member_count: i64,
+	pub representative_id: u64,
+	pub representative_name: String,
+	pub approved_at: DateTime<Utc>,
+	/// The result of the most recent check of the partner's invite, if it's been checked
+	pub invite_status: Option<String>,
+	pub active: bool,
+}
+
+#[server]
+async fn get_partnerships(guild_id: Option<u64>) -> Result<Vec<PartnershipSummary>, ServerFnError> {
+	use crate::model::{PartnerInviteStatus, Partnership};
+	use crate::schema::{partner_invite_checks, partnerships};
+	use crate::web::pages::server_utils::{get_guild_and_permission_level_from_request, get_user_name};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::prelude::*;
+	use std::collections::HashMap;
+	use twilight_model::id::Id;
+	use twilight_model::id::marker::UserMarker;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(Vec::new());
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let guild_partnerships: Vec<Partnership> = partnerships::table
+		.filter(partnerships::guild.eq(guild.guild_id))
+		.order(partnerships::partner_name.asc())
+		.load(&mut db_connection)?;
+	let partnership_ids: Vec<&str> = guild_partnerships
+		.iter()
+		.map(|partnership| partnership.id.as_str())
+		.collect();
+	let latest_statuses: Vec<(String, PartnerInviteStatus)> = partner_invite_checks::table
+		.filter(partner_invite_checks::partnership.eq_any(partnership_ids))
+		.distinct_on(partner_invite_checks::partnership)
+		.order((
+			partner_invite_checks::partnership.asc(),
+			partner_invite_checks::checked_at.desc(),
+		))
+		.select((partner_invite_checks::partnership, partner_invite_checks::status))
+		.load(&mut db_connection)?;
+	let latest_statuses: HashMap<String, PartnerInviteStatus> = latest_statuses.into_iter().collect();
+
+	let mut usernames: HashMap<Id<UserMarker>, String> = HashMap::new();
+	let mut partnership_data: Vec<PartnershipSummary> = Vec::with_capacity(guild_partnerships.len());
+	for partnership in guild_partnerships {
+		let representative_id = partnership.get_representative();
+		let representative_name = get_user_name(representative_id, &state, &mut usernames).await?;
+		let invite_status = latest_statuses.get(&partnership.id).map(|status| status.to_string());
+		partnership_data.push(PartnershipSummary {
+			id: partnership.id,
+			partner_name: partnership.partner_name,
+			member_count: partnership.member_count,
+			representative_id: representative_id.get(),
+			representative_name,
+			approved_at: partnership.approved_at,
+			invite_status,
+			active: partnership.active,
+		});
+	}
+
+	Ok(partnership_data)
+}
@@ -0,0 +1,350 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::GuildParam;
+use leptos::ev::{MouseEvent, SubmitEvent};
+use leptos::prelude::*;
+use leptos::task::spawn;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+/// How many days back the recent violation counts go
+const RECENT_DAYS: i64 = 30;
+
+#[component]
+pub fn ServerRules() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let rules = Resource::new(|| (), move |_| get_server_rules(guild_id));
+
+	let (rule_number, set_rule_number) = signal(String::new());
+	let (rule_title, set_rule_title) = signal(String::new());
+	let (rule_description, set_rule_description) = signal(String::new());
+	let (submit_error, set_submit_error) = signal(None::<String>);
+
+	let rule_submit = move |event: SubmitEvent| {
+		event.prevent_default();
+		set_submit_error.set(None);
+
+		let Ok(number) = rule_number.get().trim().parse::<i32>() else {
+			set_submit_error.set(Some(String::from("The rule number must be a number")));
+			return;
+		};
+		let title = rule_title.get().trim().to_string();
+		if title.is_empty() {
+			set_submit_error.set(Some(String::from("Rules must have a title")));
+			return;
+		}
+		let rule = RuleData {
+			rule_number: number,
+			title,
+			description: rule_description.get().trim().to_string(),
+		};
+
+		spawn(async move {
+			match save_server_rule(guild_id, rule).await {
+				Ok(()) => {
+					set_rule_number.set(String::new());
+					set_rule_title.set(String::new());
+					set_rule_description.set(String::new());
+				}
+				Err(error) => set_submit_error.set(Some(error.to_string())),
+			}
+			rules.refetch();
+		});
+	};
+
+	view! {
+		<h1>"Server Rules"</h1>
+		<Transition fallback=|| view! { <div class="server_rules_loading">"Loading rules..."</div> }>
+			{
+				move || match rules.read().as_ref().and_then(|rules| rules.as_ref().ok()).cloned().flatten() {
+					Some(rules_data) => {
+						let can_edit = rules_data.can_edit;
+						view! {
+							{
+								if rules_data.rules.is_empty() {
+									view! {
+										<div class="server_rules_empty">"There are no rules set up."</div>
+									}.into_any()
+								} else {
+									view! {
+										<table class="server_rules_list">
+											<thead>
+												<tr>
+													<th>"Rule"</th>
+													<th>"Title"</th>
+													<th>"Description"</th>
+													<th>{format!("Actions (last {} days)", RECENT_DAYS)}</th>
+													<th>"Actions (all time)"</th>
+													{can_edit.then(|| view! { <th></th> })}
+												</tr>
+											</thead>
+											<tbody>
+												{
+													rules_data.rules.into_iter().map(|rule| {
+														let number = rule.rule.rule_number;
+														let edit_rule = rule.rule.clone();
+														let edit_click = move |_: MouseEvent| {
+															set_rule_number.set(edit_rule.rule_number.to_string());
+															set_rule_title.set(edit_rule.title.clone());
+															set_rule_description.set(edit_rule.description.clone());
+														};
+														let remove_click = move |_: MouseEvent| {
+															spawn(async move {
+																if let Err(error) = remove_server_rule(guild_id, number).await {
+																	set_submit_error.set(Some(error.to_string()));
+																}
+																rules.refetch();
+															});
+														};
+														view! {
+															<tr>
+																<td>{rule.rule.rule_number}</td>
+																<td>{rule.rule.title}</td>
+																<td class="server_rules_description">{rule.rule.description}</td>
+																<td>{rule.recent_actions}</td>
+																<td>{rule.total_actions}</td>
+																{
+																	can_edit.then(|| view! {
+																		<td>
+																			<button type="button" on:click=edit_click>"Edit"</button>
+																			<button type="button" on:click=remove_click>"Remove"</button>
+																		</td>
+																	})
+																}
+															</tr>
+														}
+													}).collect::<Vec<_>>()
+												}
+											</tbody>
+										</table>
+									}.into_any()
+								}
+							}
+							{
+								can_edit.then(|| view! {
+									<form class="server_rules_form" on:submit=rule_submit>
+										<h2>"Add or Change a Rule"</h2>
+										{move || submit_error.get().map(|error| view! {
+											<div class="server_rules_submit_error">{error}</div>
+										})}
+										<div>
+											<label>
+												<span class="server_rules_label_text">"Number"</span>
+												<input type="number" min="1" bind:value=(rule_number, set_rule_number) />
+											</label>
+										</div>
+										<div>
+											<label>
+												<span class="server_rules_label_text">"Title"</span>
+												<input type="text" bind:value=(rule_title, set_rule_title) />
+											</label>
+										</div>
+										<div>
+											<label>
+												<span class="server_rules_label_text">"Description"</span>
+												<textarea bind:value=(rule_description, set_rule_description) />
+											</label>
+										</div>
+										<div>
+											<button type="submit">"Save Rule"</button>
+										</div>
+									</form>
+								})
+							}
+						}.into_any()
+					}
+					None => view! {
+						<div class="server_rules_error">"Rules couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServerRulesData {
+	/// Whether the user may change the rules
+	pub can_edit: bool,
+	pub rules: Vec<RuleStatsData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RuleStatsData {
+	pub rule: RuleData,
+	pub recent_actions: i64,
+	pub total_actions: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RuleData {
+	pub rule_number: i32,
+	pub title: String,
+	pub description: String,
+}
+
+#[server]
+async fn get_server_rules(guild_id: Option<u64>) -> Result<Option<ServerRulesData>, ServerFnError> {
+	use crate::model::ServerRule;
+	use crate::schema::{action_rules, server_rules};
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use chrono::{TimeDelta, Utc};
+	use diesel::dsl::count_star;
+	use diesel::prelude::*;
+	use std::collections::HashMap;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let rules: Vec<ServerRule> = server_rules::table
+		.filter(
+			server_rules::guild
+				.eq(guild.guild_id)
+				.and(server_rules::active.eq(true)),
+		)
+		.order(server_rules::rule_number.asc())
+		.load(&mut db_connection)?;
+	let rule_ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+
+	let total_counts: Vec<(String, i64)> = action_rules::table
+		.filter(action_rules::rule.eq_any(&rule_ids))
+		.group_by(action_rules::rule)
+		.select((action_rules::rule, count_star()))
+		.load(&mut db_connection)?;
+	let recent_since = Utc::now() - TimeDelta::days(RECENT_DAYS);
+	let recent_counts: Vec<(String, i64)> = action_rules::table
+		.filter(
+			action_rules::rule
+				.eq_any(&rule_ids)
+				.and(action_rules::action_time.ge(recent_since)),
+		)
+		.group_by(action_rules::rule)
+		.select((action_rules::rule, count_star()))
+		.load(&mut db_connection)?;
+	let total_counts: HashMap<String, i64> = total_counts.into_iter().collect();
+	let recent_counts: HashMap<String, i64> = recent_counts.into_iter().collect();
+
+	let rules = rules
+		.into_iter()
+		.map(|rule| RuleStatsData {
+			recent_actions: recent_counts.get(&rule.id).copied().unwrap_or_default(),
+			total_actions: total_counts.get(&rule.id).copied().unwrap_or_default(),
+			rule: RuleData {
+				rule_number: rule.rule_number,
+				title: rule.title,
+				description: rule.description,
+			},
+		})
+		.collect();
+
+	Ok(Some(ServerRulesData {
+		can_edit: permission_level == PermissionLevel::Admin,
+		rules,
+	}))
+}
+
+#[server]
+async fn save_server_rule(guild_id: Option<u64>, rule: RuleData) -> Result<(), ServerFnError> {
+	use crate::discord::utils::rules::{MAX_RULE_DESCRIPTION_LENGTH, MAX_RULE_TITLE_LENGTH};
+	use crate::model::ServerRule;
+	use crate::schema::server_rules;
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::prelude::*;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level != PermissionLevel::Admin {
+		return Err(ServerFnError::ServerError(String::from("Permission denied")));
+	}
+
+	let title = rule.title.trim();
+	let description = rule.description.trim();
+	if rule.rule_number < 1 {
+		return Err(ServerFnError::ServerError(String::from(
+			"Rule numbers must be at least 1",
+		)));
+	}
+	if title.is_empty() || title.chars().count() > usize::from(MAX_RULE_TITLE_LENGTH) {
+		return Err(ServerFnError::ServerError(format!(
+			"Rule titles must be between 1 and {} characters",
+			MAX_RULE_TITLE_LENGTH
+		)));
+	}
+	if description.chars().count() > usize::from(MAX_RULE_DESCRIPTION_LENGTH) {
+		return Err(ServerFnError::ServerError(format!(
+			"Rule descriptions can't be longer than {} characters",
+			MAX_RULE_DESCRIPTION_LENGTH
+		)));
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let updated_count = diesel::update(server_rules::table)
+		.filter(
+			server_rules::guild
+				.eq(guild.guild_id)
+				.and(server_rules::rule_number.eq(rule.rule_number))
+				.and(server_rules::active.eq(true)),
+		)
+		.set((server_rules::title.eq(title), server_rules::description.eq(description)))
+		.execute(&mut db_connection)?;
+	if updated_count == 0 {
+		let new_rule = ServerRule {
+			id: cuid2::create_id(),
+			guild: guild.guild_id,
+			rule_number: rule.rule_number,
+			title: title.to_string(),
+			description: description.to_string(),
+			active: true,
+		};
+		diesel::insert_into(server_rules::table)
+			.values(&new_rule)
+			.execute(&mut db_connection)?;
+	}
+
+	Ok(())
+}
+
+#[server]
+async fn remove_server_rule(guild_id: Option<u64>, rule_number: i32) -> Result<(), ServerFnError> {
+	use crate::schema::server_rules;
+	use crate::web::pages::server_utils::get_guild_and_permission_level_from_request;
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::prelude::*;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level != PermissionLevel::Admin {
+		return Err(ServerFnError::ServerError(String::from("Permission denied")));
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	diesel::update(server_rules::table)
+		.filter(
+			server_rules::guild
+				.eq(guild.guild_id)
+				.and(server_rules::rule_number.eq(rule_number))
+				.and(server_rules::active.eq(true)),
+		)
+		.set(server_rules::active.eq(false))
+		.execute(&mut db_connection)?;
+
+	Ok(())
+}
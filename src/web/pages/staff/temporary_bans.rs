@@ -0,0 +1,114 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{GuildParam, make_user_profile_url};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn TemporaryBans() -> impl IntoView {
+	let params = use_params::<GuildParam>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+
+	let temporary_bans = OnceResource::new(get_temporary_bans(guild_id));
+
+	view! {
+		<h1>"Upcoming Unbans"</h1>
+		<Transition fallback=|| view! { <div class="temporary_ban_list_loading">"Loading bans..."</div> }>
+			{
+				move || match temporary_bans.read().as_ref().and_then(|bans| bans.as_ref().ok()) {
+					Some(ban_data) if !ban_data.is_empty() => view! {
+						<table class="temporary_ban_list">
+							<thead>
+								<tr>
+									<th>"User"</th>
+									<th>"Banned By"</th>
+									<th>"Reason"</th>
+									<th>"Banned"</th>
+									<th>"Lifted"</th>
+								</tr>
+							</thead>
+							<tbody>
+								{
+									ban_data.iter().map(|ban| view! {
+										<tr>
+											<td>
+												<a href={make_user_profile_url(guild_id, ban.user_id)}>
+													{ban.user_name.clone()}
+												</a>
+											</td>
+											<td>{ban.banned_by_name.clone()}</td>
+											<td class="temporary_ban_list_reason">{ban.reason.clone()}</td>
+											<td>{ban.banned_at.to_rfc3339()}</td>
+											<td>{ban.expires_at.to_rfc3339()}</td>
+										</tr>
+									}).collect::<Vec<_>>()
+								}
+							</tbody>
+						</table>
+					}.into_any(),
+					_ => view! {
+						<div class="temporary_ban_list_empty">"There are no temporary bans."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TemporaryBanData {
+	pub user_id: u64,
+	pub user_name: String,
+	pub banned_by_name: String,
+	pub reason: String,
+	pub banned_at: DateTime<Utc>,
+	pub expires_at: DateTime<Utc>,
+}
+
+#[server]
+async fn get_temporary_bans(guild_id: Option<u64>) -> Result<Vec<TemporaryBanData>, ServerFnError> {
+	use crate::discord::utils::temporary_bans::active_temporary_bans;
+	use crate::web::pages::server_utils::{get_guild_and_permission_level_from_request, get_user_name};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use std::collections::HashMap;
+	use twilight_model::id::Id;
+	use twilight_model::id::marker::UserMarker;
+
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(Vec::new());
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let bans = active_temporary_bans(&mut db_connection, Some(guild.guild_id), None)?;
+
+	let mut usernames: HashMap<Id<UserMarker>, String> = HashMap::new();
+	let mut bans_data: Vec<TemporaryBanData> = Vec::with_capacity(bans.len());
+	for ban in bans {
+		let Some(expires_at) = ban.expires_at else {
+			continue;
+		};
+		let user_id = ban.get_banned_user();
+		let user_name = get_user_name(user_id, &state, &mut usernames).await?;
+		let banned_by_name = get_user_name(ban.get_banning_user(), &state, &mut usernames).await?;
+		bans_data.push(TemporaryBanData {
+			user_id: user_id.get(),
+			user_name,
+			banned_by_name,
+			reason: ban.current_reason().to_string(),
+			banned_at: ban.action_time,
+			expires_at,
+		});
+	}
+
+	Ok(bans_data)
+}
@@ -0,0 +1,212 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{UserParams, make_ticket_url};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn UserNotes() -> impl IntoView {
+	let params = use_params::<UserParams>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+	let user_id = params.read().as_ref().ok().and_then(|params| params.user_id);
+
+	let notes = OnceResource::new(get_user_notes(guild_id, user_id));
+
+	view! {
+		<Transition fallback=|| view! { <div class="user_notes_loading">"Loading notes..."</div> }>
+			{
+				move || match notes.read().as_ref().and_then(|notes| notes.as_ref().ok()).cloned().flatten() {
+					Some(notes_data) => view! {
+						<h1>"Notes for " {notes_data.user_name.clone()}</h1>
+						{
+							if notes_data.notes.is_empty() {
+								view! {
+									<div class="user_notes_empty">"There are no notes for this user."</div>
+								}.into_any()
+							} else {
+								notes_data.notes.into_iter().map(|note| view! {
+									<div class="user_note">
+										<div class="user_note_header">
+											<span class="user_note_author">{note.author_name}</span>
+											<span class="user_note_time">{note.created_at.to_rfc3339()}</span>
+											<span class="user_note_id">{note.id}</span>
+										</div>
+										{
+											note.action.map(|action| view! {
+												<div class="user_note_link">"About: " {action}</div>
+											})
+										}
+										{
+											note.ticket.map(|ticket| view! {
+												<div class="user_note_link">
+													<a href={make_ticket_url(guild_id, &ticket.id)}>
+														"Ticket: " {ticket.title}
+													</a>
+												</div>
+											})
+										}
+										<div class="user_note_body">{note.body}</div>
+										{
+											(!note.edits.is_empty()).then(|| view! {
+												<details class="user_note_edits">
+													<summary>"Edit history"</summary>
+													<ul>
+														{
+															note.edits.into_iter().map(|edit| view! {
+																<li>
+																	<div class="user_note_edit_header">
+																		{edit.editor_name} " at " {edit.edit_time.to_rfc3339()}
+																		" (previous version)"
+																	</div>
+																	<div class="user_note_edit_body">{edit.previous_body}</div>
+																</li>
+															}).collect::<Vec<_>>()
+														}
+													</ul>
+												</details>
+											})
+										}
+									</div>
+								}).collect::<Vec<_>>().into_any()
+							}
+						}
+					}.into_any(),
+					None => view! {
+						<div class="user_notes_error">"Notes for this user couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserNotesData {
+	pub user_name: String,
+	pub notes: Vec<UserNoteData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserNoteData {
+	pub id: String,
+	pub author_name: String,
+	pub created_at: DateTime<Utc>,
+	pub body: String,
+	pub action: Option<String>,
+	pub ticket: Option<UserNoteTicket>,
+	pub edits: Vec<UserNoteEditData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserNoteTicket {
+	pub id: String,
+	pub title: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserNoteEditData {
+	pub editor_name: String,
+	pub edit_time: DateTime<Utc>,
+	pub previous_body: String,
+}
+
+#[server]
+async fn get_user_notes(guild_id: Option<u64>, user_id: Option<u64>) -> Result<Option<UserNotesData>, ServerFnError> {
+	use crate::model::{Ticket, UserNote, UserNoteEdit, database_id_from_discord_id};
+	use crate::schema::{tickets, user_note_edits, user_notes};
+	use crate::web::pages::server_utils::{get_guild_and_permission_level_from_request, get_user_name};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::prelude::*;
+	use std::collections::HashMap;
+	use twilight_model::id::Id;
+	use twilight_model::id::marker::UserMarker;
+
+	let Some(user_id) = user_id else {
+		return Ok(None);
+	};
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let db_user_id = database_id_from_discord_id(user_id);
+	let notes: Vec<UserNote> = user_notes::table
+		.filter(
+			user_notes::guild
+				.eq(guild.guild_id)
+				.and(user_notes::target_user.eq(db_user_id)),
+		)
+		.order(user_notes::created_at.desc())
+		.load(&mut db_connection)?;
+
+	let note_ids: Vec<&str> = notes.iter().map(|note| note.id.as_str()).collect();
+	let edits: Vec<UserNoteEdit> = user_note_edits::table
+		.filter(user_note_edits::note.eq_any(&note_ids))
+		.order(user_note_edits::edit_time.desc())
+		.load(&mut db_connection)?;
+	let mut edits_by_note: HashMap<String, Vec<UserNoteEdit>> = HashMap::new();
+	for edit in edits {
+		edits_by_note.entry(edit.note.clone()).or_default().push(edit);
+	}
+
+	let ticket_ids: Vec<&str> = notes.iter().filter_map(|note| note.ticket.as_deref()).collect();
+	let linked_tickets: Vec<Ticket> = tickets::table
+		.filter(tickets::id.eq_any(&ticket_ids))
+		.load(&mut db_connection)?;
+	let ticket_titles: HashMap<String, String> = linked_tickets
+		.into_iter()
+		.map(|ticket| (ticket.id, ticket.title))
+		.collect();
+
+	let mut usernames: HashMap<Id<UserMarker>, String> = HashMap::new();
+	let user_name = get_user_name(Id::new(user_id), &state, &mut usernames).await?;
+
+	let mut notes_data: Vec<UserNoteData> = Vec::with_capacity(notes.len());
+	for note in notes {
+		let author_name = get_user_name(note.get_author(), &state, &mut usernames).await?;
+
+		let mut edits: Vec<UserNoteEditData> = Vec::new();
+		for edit in edits_by_note.remove(&note.id).unwrap_or_default() {
+			let editor_name = get_user_name(edit.get_editor(), &state, &mut usernames).await?;
+			edits.push(UserNoteEditData {
+				editor_name,
+				edit_time: edit.edit_time,
+				previous_body: edit.previous_body,
+			});
+		}
+
+		let action = match (note.action_type, note.action_id) {
+			(Some(action_type), Some(action_id)) => Some(format!("{} {}", action_type, action_id)),
+			_ => None,
+		};
+		let ticket = note.ticket.map(|ticket_id| {
+			let title = ticket_titles.get(&ticket_id).cloned().unwrap_or_default();
+			UserNoteTicket { id: ticket_id, title }
+		});
+
+		notes_data.push(UserNoteData {
+			id: note.id,
+			author_name,
+			created_at: note.created_at,
+			body: note.body,
+			action,
+			ticket,
+			edits,
+		});
+	}
+
+	Ok(Some(UserNotesData {
+		user_name,
+		notes: notes_data,
+	}))
+}
@@ -0,0 +1,594 @@
+// © 2024-2025 ElementalAlchemist and the Dainsleif Mains Development Team
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::web::pages::utils::{UserParams, make_case_url, make_ticket_url, make_user_notes_url};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params;
+use serde::{Deserialize, Serialize};
+
+#[component]
+pub fn UserProfile() -> impl IntoView {
+	let params = use_params::<UserParams>();
+	let guild_id = params.read().as_ref().ok().and_then(|params| params.guild);
+	let user_id = params.read().as_ref().ok().and_then(|params| params.user_id);
+
+	let profile = OnceResource::new(get_user_profile(guild_id, user_id));
+
+	view! {
+		<Transition fallback=|| view! { <div class="user_profile_loading">"Loading user..."</div> }>
+			{
+				move || match profile.read().as_ref().and_then(|profile| profile.as_ref().ok()).cloned().flatten() {
+					Some(profile_data) => view! {
+						<div class="user_profile_header">
+							{
+								profile_data.avatar_url.clone().map(|avatar_url| view! {
+									<img class="user_profile_avatar" src={avatar_url} />
+								})
+							}
+							<div>
+								<h1>{profile_data.user_name.clone()}</h1>
+								{
+									profile_data.display_name.clone().map(|display_name| view! {
+										<div class="user_profile_display_name">{display_name}</div>
+									})
+								}
+							</div>
+						</div>
+						<table class="user_profile_details">
+							<tbody>
+								<tr>
+									<th>"User ID"</th>
+									<td>{profile_data.user_id}</td>
+								</tr>
+								<tr>
+									<th>"Account Created"</th>
+									<td>{profile_data.created_at.map(|created_at| created_at.to_rfc3339())}</td>
+								</tr>
+								<tr>
+									<th>"Joined Server"</th>
+									<td>
+										{
+											match (profile_data.in_guild, profile_data.joined_at) {
+												(true, Some(joined_at)) => joined_at.to_rfc3339(),
+												(true, None) => String::from("Unknown"),
+												(false, _) => String::from("Not in the server"),
+											}
+										}
+									</td>
+								</tr>
+								{
+									profile_data.ticket_restricted.then(|| view! {
+										<tr>
+											<th>"Tickets"</th>
+											<td class="user_profile_restricted">"Restricted from opening tickets"</td>
+										</tr>
+									})
+								}
+								<tr>
+									<th>"Notes"</th>
+									<td>
+										<a href={make_user_notes_url(guild_id, profile_data.user_id)}>"View notes with edit history"</a>
+									</td>
+								</tr>
+							</tbody>
+						</table>
+						{
+							(!profile_data.network_bans.is_empty()).then(|| view! {
+								<h2>"Partner Network Bans"</h2>
+								<table class="user_profile_network_bans">
+									<thead>
+										<tr>
+											<th>"Server"</th>
+											<th>"Banned"</th>
+											<th>"Reason"</th>
+										</tr>
+									</thead>
+									<tbody>
+										{
+											profile_data.network_bans.iter().map(|ban| view! {
+												<tr>
+													<td>{ban.server_name.clone()}</td>
+													<td>{ban.banned_at.to_rfc3339()}</td>
+													<td>{ban.reason.clone()}</td>
+												</tr>
+											}).collect::<Vec<_>>()
+										}
+									</tbody>
+								</table>
+							})
+						}
+						<h2>"History"</h2>
+						{
+							if profile_data.timeline.is_empty() {
+								view! {
+									<div class="user_profile_timeline_empty">"Nothing has been recorded for this user."</div>
+								}.into_any()
+							} else {
+								view! {
+									<ol class="user_profile_timeline">
+										{
+											profile_data.timeline.into_iter().map(|entry| view! {
+												<li class="user_profile_timeline_entry">
+													<div class="user_profile_timeline_header">
+														<span class="user_profile_timeline_time">{entry.time.to_rfc3339()}</span>
+														<span class="user_profile_timeline_title">{entry.title}</span>
+														{
+															entry.case_number.map(|case_number| view! {
+																<a href={make_case_url(guild_id, case_number)}>"Case " {case_number}</a>
+															})
+														}
+														{
+															entry.moderator_name.map(|moderator_name| view! {
+																<span class="user_profile_timeline_moderator">"by " {moderator_name}</span>
+															})
+														}
+													</div>
+													{
+														entry.summary.map(|summary| view! {
+															<div class="user_profile_timeline_summary">{summary}</div>
+														})
+													}
+													{
+														entry.ticket.map(|ticket| view! {
+															<div class="user_profile_timeline_summary">
+																<a href={make_ticket_url(guild_id, &ticket.id)}>
+																	"Ticket: " {ticket.title}
+																</a>
+															</div>
+														})
+													}
+													{
+														entry.text.map(|text| view! {
+															<div class="user_profile_timeline_text">{text}</div>
+														})
+													}
+												</li>
+											}).collect::<Vec<_>>()
+										}
+									</ol>
+								}.into_any()
+							}
+						}
+					}.into_any(),
+					None => view! {
+						<div class="user_profile_error">"This user couldn't be loaded."</div>
+					}.into_any(),
+				}
+			}
+		</Transition>
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserProfileData {
+	pub user_id: u64,
+	pub user_name: String,
+	/// The name the user is shown with in the server, if it's different from their username
+	pub display_name: Option<String>,
+	pub avatar_url: Option<String>,
+	pub created_at: Option<DateTime<Utc>>,
+	pub in_guild: bool,
+	pub joined_at: Option<DateTime<Utc>>,
+	pub ticket_restricted: bool,
+	/// Everything recorded about the user, newest first
+	pub timeline: Vec<TimelineEntryData>,
+	/// Bans of the user shared through the partner network, if the server has TCN partner integration enabled
+	pub network_bans: Vec<NetworkBanData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NetworkBanData {
+	pub server_name: String,
+	pub banned_at: DateTime<Utc>,
+	pub reason: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TimelineEntryData {
+	pub time: DateTime<Utc>,
+	pub title: String,
+	pub moderator_name: Option<String>,
+	pub case_number: Option<i32>,
+	/// A short description of what changed
+	pub summary: Option<String>,
+	/// The reason given for an action, or the body of a note
+	pub text: Option<String>,
+	pub ticket: Option<TimelineTicket>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TimelineTicket {
+	pub id: String,
+	pub title: String,
+}
+
+#[server]
+async fn get_user_profile(
+	guild_id: Option<u64>,
+	user_id: Option<u64>,
+) -> Result<Option<UserProfileData>, ServerFnError> {
+	use crate::discord::utils::timestamp::{datetime_from_id, datetime_from_timestamp};
+	use crate::model::{
+		AutomodAction, BanAction, KickAction, MessageDeleteAction, NicknameChangeAction, RoleChangeAction, TcnBan,
+		Ticket, TimeoutAction, UserNote, WarnAction, database_id_from_discord_id,
+	};
+	use crate::schema::{
+		automod_actions, ban_actions, kick_actions, message_delete_actions, moderation_cases, nickname_change_actions,
+		role_change_actions, tcn_bans, tcn_partners, ticket_messages, ticket_restricted_users, tickets,
+		timeout_actions, user_notes, warn_actions,
+	};
+	use crate::web::pages::server_utils::{get_guild_and_permission_level_from_request, get_user_name};
+	use crate::web::permissions::PermissionLevel;
+	use crate::web::state::AppState;
+	use diesel::dsl::{exists, min};
+	use diesel::prelude::*;
+	use std::collections::HashMap;
+	use twilight_model::id::Id;
+	use twilight_model::id::marker::UserMarker;
+
+	fn reason_text(reason: &str) -> Option<String> {
+		(!reason.is_empty()).then(|| reason.to_string())
+	}
+
+	let Some(user_id) = user_id else {
+		return Ok(None);
+	};
+	let (guild, permission_level) = get_guild_and_permission_level_from_request(guild_id).await?;
+	if permission_level == PermissionLevel::Member {
+		return Ok(None);
+	}
+
+	let state: AppState = expect_context();
+	let mut db_connection = state.db_connection_pool.get()?;
+
+	let discord_guild_id = guild.get_guild_id();
+	let user_id: Id<UserMarker> = Id::new(user_id);
+	let db_user_id = database_id_from_discord_id(user_id.get());
+
+	let user = state.discord_client.user(user_id).await?.model().await?;
+	// Users who aren't in the server can't be fetched as members, but their history is still worth showing
+	let member = match state.discord_client.guild_member(discord_guild_id, user_id).await {
+		Ok(response) => Some(response.model().await?),
+		Err(_) => None,
+	};
+
+	let ticket_restricted: bool = diesel::select(exists(
+		ticket_restricted_users::table.find((guild.guild_id, db_user_id)),
+	))
+	.get_result(&mut db_connection)?;
+
+	let bans: Vec<BanAction> = ban_actions::table
+		.filter(
+			ban_actions::guild
+				.eq(guild.guild_id)
+				.and(ban_actions::banned_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let kicks: Vec<KickAction> = kick_actions::table
+		.filter(
+			kick_actions::guild
+				.eq(guild.guild_id)
+				.and(kick_actions::kicked_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let timeouts: Vec<TimeoutAction> = timeout_actions::table
+		.filter(
+			timeout_actions::guild
+				.eq(guild.guild_id)
+				.and(timeout_actions::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let warnings: Vec<WarnAction> = warn_actions::table
+		.filter(
+			warn_actions::guild
+				.eq(guild.guild_id)
+				.and(warn_actions::warned_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let automod: Vec<AutomodAction> = automod_actions::table
+		.filter(
+			automod_actions::guild
+				.eq(guild.guild_id)
+				.and(automod_actions::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let role_changes: Vec<RoleChangeAction> = role_change_actions::table
+		.filter(
+			role_change_actions::guild
+				.eq(guild.guild_id)
+				.and(role_change_actions::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let nickname_changes: Vec<NicknameChangeAction> = nickname_change_actions::table
+		.filter(
+			nickname_change_actions::guild
+				.eq(guild.guild_id)
+				.and(nickname_change_actions::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let message_deletes: Vec<MessageDeleteAction> = message_delete_actions::table
+		.filter(
+			message_delete_actions::guild
+				.eq(guild.guild_id)
+				.and(message_delete_actions::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let notes: Vec<UserNote> = user_notes::table
+		.filter(
+			user_notes::guild
+				.eq(guild.guild_id)
+				.and(user_notes::target_user.eq(db_user_id)),
+		)
+		.load(&mut db_connection)?;
+	let user_tickets: Vec<Ticket> = tickets::table
+		.filter(tickets::guild.eq(guild.guild_id).and(tickets::with_user.eq(db_user_id)))
+		.load(&mut db_connection)?;
+
+	let mut action_ids: Vec<&str> = Vec::new();
+	action_ids.extend(bans.iter().map(|action| action.id.as_str()));
+	action_ids.extend(kicks.iter().map(|action| action.id.as_str()));
+	action_ids.extend(timeouts.iter().map(|action| action.id.as_str()));
+	action_ids.extend(warnings.iter().map(|action| action.id.as_str()));
+	action_ids.extend(automod.iter().map(|action| action.id.as_str()));
+	let case_numbers: HashMap<String, i32> = moderation_cases::table
+		.filter(
+			moderation_cases::guild
+				.eq(guild.guild_id)
+				.and(moderation_cases::action_id.eq_any(&action_ids)),
+		)
+		.select((moderation_cases::action_id, moderation_cases::case_number))
+		.load::<(String, i32)>(&mut db_connection)?
+		.into_iter()
+		.collect();
+
+	let ticket_ids: Vec<&str> = user_tickets.iter().map(|ticket| ticket.id.as_str()).collect();
+	// Tickets don't store when they were opened, so the first message stands in for it
+	let ticket_opened_times: HashMap<String, Option<DateTime<Utc>>> = ticket_messages::table
+		.filter(ticket_messages::ticket.eq_any(&ticket_ids))
+		.group_by(ticket_messages::ticket)
+		.select((ticket_messages::ticket, min(ticket_messages::send_time)))
+		.load::<(String, Option<DateTime<Utc>>)>(&mut db_connection)?
+		.into_iter()
+		.collect();
+	let ticket_titles: HashMap<String, String> = user_tickets
+		.iter()
+		.map(|ticket| (ticket.id.clone(), ticket.title.clone()))
+		.collect();
+
+	let role_names: HashMap<u64, String> = if role_changes.is_empty() {
+		HashMap::new()
+	} else {
+		let roles = state.discord_client.roles(discord_guild_id).await?.models().await?;
+		roles.into_iter().map(|role| (role.id.get(), role.name)).collect()
+	};
+	let channel_names: HashMap<u64, String> = if message_deletes.is_empty() {
+		HashMap::new()
+	} else {
+		let channels = state
+			.discord_client
+			.guild_channels(discord_guild_id)
+			.await?
+			.models()
+			.await?;
+		channels
+			.into_iter()
+			.filter_map(|channel| channel.name.map(|name| (channel.id.get(), name)))
+			.collect()
+	};
+
+	let mut usernames: HashMap<Id<UserMarker>, String> = HashMap::new();
+	usernames.insert(user_id, user.name.clone());
+	let mut timeline: Vec<TimelineEntryData> = Vec::new();
+
+	for action in bans {
+		let title = if action.added { "Ban" } else { "Unban" };
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from(title),
+			moderator_name: Some(get_user_name(action.get_banning_user(), &state, &mut usernames).await?),
+			case_number: case_numbers.get(&action.id).copied(),
+			summary: None,
+			text: reason_text(action.current_reason()),
+			ticket: None,
+		});
+	}
+	for action in kicks {
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from("Kick"),
+			moderator_name: Some(get_user_name(action.get_kicking_user(), &state, &mut usernames).await?),
+			case_number: case_numbers.get(&action.id).copied(),
+			summary: None,
+			text: reason_text(action.current_reason()),
+			ticket: None,
+		});
+	}
+	for action in timeouts {
+		let (title, summary) = match action.timeout_until {
+			Some(timeout_until) => ("Timeout", Some(format!("Until {}", timeout_until.to_rfc3339()))),
+			None => ("Timeout Removed", None),
+		};
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from(title),
+			moderator_name: Some(get_user_name(action.get_performing_user(), &state, &mut usernames).await?),
+			case_number: case_numbers.get(&action.id).copied(),
+			summary,
+			text: reason_text(action.current_reason()),
+			ticket: None,
+		});
+	}
+	for action in warnings {
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from("Warning"),
+			moderator_name: Some(get_user_name(action.get_warning_user(), &state, &mut usernames).await?),
+			case_number: case_numbers.get(&action.id).copied(),
+			summary: None,
+			text: reason_text(action.current_reason()),
+			ticket: None,
+		});
+	}
+	for action in automod {
+		let title = action.action_type.title();
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from(title),
+			moderator_name: None,
+			case_number: case_numbers.get(&action.id).copied(),
+			summary: Some(format!("Rule: {}", action.rule_name)),
+			text: action.message_content,
+			ticket: None,
+		});
+	}
+	for action in role_changes {
+		let title = if action.added { "Role Added" } else { "Role Removed" };
+		let role = action.get_role();
+		let role_name = role_names
+			.get(&role.get())
+			.cloned()
+			.unwrap_or_else(|| format!("Deleted role ({})", role));
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from(title),
+			moderator_name: Some(get_user_name(action.get_performing_user(), &state, &mut usernames).await?),
+			case_number: None,
+			summary: Some(role_name),
+			text: reason_text(&action.reason),
+			ticket: None,
+		});
+	}
+	for action in nickname_changes {
+		let old_nickname = action.old_nickname.as_deref().unwrap_or("(none)");
+		let new_nickname = action.new_nickname.as_deref().unwrap_or("(none)");
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from("Nickname Changed"),
+			moderator_name: Some(get_user_name(action.get_performing_user(), &state, &mut usernames).await?),
+			case_number: None,
+			summary: Some(format!("{} → {}", old_nickname, new_nickname)),
+			text: reason_text(&action.reason),
+			ticket: None,
+		});
+	}
+	for action in message_deletes {
+		let channel = action.get_channel();
+		let channel_name = channel_names
+			.get(&channel.get())
+			.cloned()
+			.unwrap_or_else(|| channel.to_string());
+		let summary = if action.message_count == 1 {
+			format!("1 message in #{}", channel_name)
+		} else {
+			format!("{} messages in #{}", action.message_count, channel_name)
+		};
+		timeline.push(TimelineEntryData {
+			time: action.action_time,
+			title: String::from("Messages Deleted"),
+			moderator_name: Some(get_user_name(action.get_performing_user(), &state, &mut usernames).await?),
+			case_number: None,
+			summary: Some(summary),
+			text: None,
+			ticket: None,
+		});
+	}
+	for ticket in user_tickets {
+		let opened_at = ticket_opened_times.get(&ticket.id).copied().flatten();
+		let Some(time) = opened_at.or(ticket.closed_at) else {
+			continue;
+		};
+		let summary = ticket
+			.closed_at
+			.map(|closed_at| format!("Closed {}", closed_at.to_rfc3339()));
+		let title = if ticket.closed_at.is_some() {
+			"Ticket (closed)"
+		} else {
+			"Ticket (open)"
+		};
+		timeline.push(TimelineEntryData {
+			time,
+			title: String::from(title),
+			moderator_name: None,
+			case_number: None,
+			summary,
+			text: None,
+			ticket: Some(TimelineTicket {
+				id: ticket.id,
+				title: ticket.title,
+			}),
+		});
+	}
+	for note in notes {
+		let ticket = note.ticket.map(|ticket_id| {
+			let title = ticket_titles.get(&ticket_id).cloned().unwrap_or_default();
+			TimelineTicket { id: ticket_id, title }
+		});
+		timeline.push(TimelineEntryData {
+			time: note.created_at,
+			title: String::from("Note"),
+			moderator_name: Some(get_user_name(note.get_author(), &state, &mut usernames).await?),
+			case_number: None,
+			summary: None,
+			text: Some(note.body),
+			ticket,
+		});
+	}
+
+	timeline.sort_by(|a, b| b.time.cmp(&a.time));
+
+	let network_bans: Vec<NetworkBanData> = if guild.tcn_partner_integration {
+		let user_network_bans: Vec<TcnBan> = tcn_bans::table
+			.filter(tcn_bans::user_id.eq(db_user_id))
+			.order(tcn_bans::banned_at.desc())
+			.load(&mut db_connection)?;
+		let origin_guilds: Vec<i64> = user_network_bans.iter().map(|ban| ban.origin_guild).collect();
+		let origin_guild_names: Vec<(i64, String)> = tcn_partners::table
+			.filter(tcn_partners::guild_id.eq_any(origin_guilds))
+			.select((tcn_partners::guild_id, tcn_partners::name))
+			.load(&mut db_connection)?;
+		let origin_guild_names: HashMap<i64, String> = origin_guild_names.into_iter().collect();
+		user_network_bans
+			.into_iter()
+			.map(|ban| NetworkBanData {
+				server_name: origin_guild_names
+					.get(&ban.origin_guild)
+					.cloned()
+					.unwrap_or_else(|| ban.get_origin_guild().to_string()),
+				banned_at: ban.banned_at,
+				reason: ban.reason,
+			})
+			.collect()
+	} else {
+		Vec::new()
+	};
+
+	let display_name = member
+		.as_ref()
+		.and_then(|member| member.nick.clone())
+		.or_else(|| user.global_name.clone())
+		.filter(|display_name| *display_name != user.name);
+	let avatar_url = user
+		.avatar
+		.map(|avatar_hash| format!("https://cdn.discordapp.com/avatars/{}/{}.png", user_id, avatar_hash));
+	let joined_at = member
+		.as_ref()
+		.and_then(|member| member.joined_at.as_ref())
+		.and_then(datetime_from_timestamp);
+
+	Ok(Some(UserProfileData {
+		user_id: user_id.get(),
+		user_name: user.name,
+		display_name,
+		avatar_url,
+		created_at: datetime_from_id(user_id),
+		in_guild: member.is_some(),
+		joined_at,
+		ticket_restricted,
+		timeline,
+		network_bans,
+	}))
+}
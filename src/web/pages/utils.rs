@@ -81,3 +81,61 @@ pub struct FormParams {
 	pub guild: Option<u64>,
 	pub form_id: Option<String>,
 }
+
+#[derive(Clone, Debug, Params, PartialEq)]
+pub struct UserParams {
+	pub guild: Option<u64>,
+	pub user_id: Option<u64>,
+}
+
+/// Makes a URL to the staff view of a user's profile and history
+pub fn make_user_profile_url(guild_id: Option<u64>, user_id: u64) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/user/{}", id, user_id),
+		None => format!("/staff/user/{}", user_id),
+	}
+}
+
+/// Makes a URL to the staff view of notes about a user
+pub fn make_user_notes_url(guild_id: Option<u64>, user_id: u64) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/user_notes/{}", id, user_id),
+		None => format!("/staff/user_notes/{}", user_id),
+	}
+}
+
+#[derive(Clone, Debug, Params, PartialEq)]
+pub struct CaseParams {
+	pub guild: Option<u64>,
+	pub case_number: Option<i32>,
+}
+
+/// Makes a URL to the staff view of a moderation case
+pub fn make_case_url(guild_id: Option<u64>, case_number: i32) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/case/{}", id, case_number),
+		None => format!("/staff/case/{}", case_number),
+	}
+}
+
+#[derive(Clone, Debug, Params, PartialEq)]
+pub struct PartnershipParams {
+	pub guild: Option<u64>,
+	pub partnership_id: Option<String>,
+}
+
+/// Makes a URL to the staff view of a partnership
+pub fn make_partnership_url(guild_id: Option<u64>, partnership_id: &str) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/staff/partnership/{}", id, partnership_id),
+		None => format!("/staff/partnership/{}", partnership_id),
+	}
+}
+
+/// Makes a URL to the public list of a guild's active partners
+pub fn make_partner_directory_url(guild_id: Option<u64>) -> String {
+	match guild_id {
+		Some(id) => format!("/{}/partners", id),
+		None => String::from("/partners"),
+	}
+}